@@ -20,7 +20,7 @@ pub async fn run_subscriptions(nats: &NatsHelper, limit: usize) -> Result<()> {
             Err(_) => result.increment_error_count(),
             Ok(decoded) => {
                 result
-                    .add_publish_time(decoded.ts_as_millis())
+                    .add_publish_time(decoded.metadata.timestamp_ms as u128)
                     .increment_message_count();
                 if result.is_complete() {
                     result.finalize();
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use data_parser::{generate_test_block, generate_test_tx};
+use fuel_data_parser::{
+    CompressionLevel,
+    CompressionStrategy,
+    DataParser,
+    ZstdCompressionStrategy,
+    ZstdDictionary,
+    ZstdDictionaryCompressionStrategy,
+    DEFAULT_ZSTD_DICTIONARY_SIZE,
+};
+
+fn bench_compress_dictionary(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compress_dictionary");
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    // train the dictionary on serialized payloads, as they are compressed
+    let parser = DataParser::default();
+    let samples = runtime.block_on(async {
+        let mut samples = Vec::new();
+        for _ in 0..500 {
+            samples.push(parser.serialize(&generate_test_tx()).await.unwrap());
+            samples
+                .push(parser.serialize(&generate_test_block()).await.unwrap());
+        }
+        samples
+    });
+    let dictionary =
+        ZstdDictionary::train(&samples, DEFAULT_ZSTD_DICTIONARY_SIZE)
+            .expect("dictionary training error");
+
+    let strategies: [Arc<dyn CompressionStrategy>; 2] = [
+        Arc::new(ZstdCompressionStrategy::new(CompressionLevel::Default)),
+        Arc::new(ZstdDictionaryCompressionStrategy::with_dictionary(
+            &dictionary,
+            CompressionLevel::Default,
+        )),
+    ];
+
+    let test_tx = generate_test_tx();
+    for compression_strategy in strategies.iter() {
+        let data_parser = DataParser::default()
+            .with_compression_strategy(compression_strategy);
+
+        let encoded = runtime.block_on(data_parser.encode(&test_tx)).unwrap();
+        println!(
+            "[{}] encoded transaction size: {} bytes",
+            compression_strategy.name(),
+            encoded.len()
+        );
+
+        group.bench_function(
+            format!("[encode][{:?}]", compression_strategy.name()),
+            |b| {
+                b.to_async(&runtime).iter(|| async {
+                    let result = data_parser
+                        .encode(&test_tx)
+                        .await
+                        .expect("serialization and compression error");
+                    black_box(result.len());
+                });
+            },
+        );
+
+        group.bench_function(
+            format!("[decode][{:?}]", compression_strategy.name()),
+            |b| {
+                b.to_async(&runtime).iter(|| async {
+                    let result: fuel_core_types::fuel_tx::Transaction =
+                        data_parser
+                            .decode(&encoded)
+                            .await
+                            .expect("decompression and deserialization error");
+                    black_box(result);
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_compress_dictionary);
+criterion_main!(benches);
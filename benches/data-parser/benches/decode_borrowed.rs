@@ -0,0 +1,94 @@
+use std::borrow::Cow;
+
+use criterion::{
+    black_box,
+    criterion_group,
+    criterion_main,
+    BenchmarkId,
+    Criterion,
+    Throughput,
+};
+use data_parser::{
+    generate_test_raw_payload,
+    BorrowedRawPayload,
+    OwnedRawPayload,
+    RAW_PAYLOAD_SIZES,
+};
+use fuel_data_parser::{DataParser, SerializationType};
+
+fn bench_decode_borrowed(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let mut group = c.benchmark_group("decode_borrowed");
+
+    // Owned vs borrowed decoding of the same uncompressed payload, for the
+    // binary formats which can borrow bytes. Both run on the current thread,
+    // so the difference is the copy of the fields into owned buffers.
+    for serialization_type in
+        [SerializationType::Bincode, SerializationType::Postcard]
+    {
+        let data_parser =
+            DataParser::default().with_serialization_type(serialization_type);
+
+        for size in RAW_PAYLOAD_SIZES {
+            let encoded = runtime.block_on(async {
+                data_parser
+                    .encode(&generate_test_raw_payload(size))
+                    .await
+                    .expect("serialization failed")
+            });
+            let borrowed = data_parser
+                .decode_borrowed::<BorrowedRawPayload>(&encoded)
+                .expect("decoding failed");
+            assert!(matches!(borrowed.subject, Cow::Borrowed(_)));
+            group.throughput(Throughput::Bytes(encoded.len() as u64));
+
+            group.bench_with_input(
+                BenchmarkId::new(
+                    format!(
+                        "[{}][raw_payload][decode_owned]",
+                        serialization_type
+                    ),
+                    size,
+                ),
+                &encoded,
+                |b, encoded| {
+                    b.iter(|| {
+                        let result = data_parser
+                            .decode_blocking::<OwnedRawPayload>(encoded)
+                            .expect("decoding failed");
+                        // Use black_box to make sure 'result' is considered used by the compiler
+                        black_box(result);
+                    });
+                },
+            );
+
+            group.bench_with_input(
+                BenchmarkId::new(
+                    format!(
+                        "[{}][raw_payload][decode_borrowed]",
+                        serialization_type
+                    ),
+                    size,
+                ),
+                &encoded,
+                |b, encoded| {
+                    b.iter(|| {
+                        let result = data_parser
+                            .decode_borrowed::<BorrowedRawPayload>(encoded)
+                            .expect("decoding failed");
+                        black_box(result);
+                    });
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_decode_borrowed);
+criterion_main!(benches);
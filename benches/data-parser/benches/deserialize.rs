@@ -16,6 +16,7 @@ fn bench_deserialize(c: &mut Criterion) {
 
     // Benchmarks for different serialization methods
     let parametric_matrix = SerializationType::iter()
+        .filter(SerializationType::is_serde)
         .map(|serialization_type| {
             (serialization_type, DEFAULT_COMPRESSION_STRATEGY.clone())
         })
@@ -28,7 +29,7 @@ fn bench_deserialize(c: &mut Criterion) {
             let test_block = generate_test_block();
             let data_parser = DataParser::default()
                 .with_compression_strategy(compression_strategy)
-                .with_serialization_type(serialization_type.clone());
+                .with_serialization_type(*serialization_type);
 
             // Perform serialization asynchronously and collect the results
             let serialized = runtime.block_on(async {
@@ -38,7 +39,7 @@ fn bench_deserialize(c: &mut Criterion) {
                     .expect("serialization failed")
             });
 
-            (serialization_type.clone(), compression_strategy, serialized)
+            (*serialization_type, compression_strategy, serialized)
         })
         .collect();
 
@@ -52,7 +53,7 @@ fn bench_deserialize(c: &mut Criterion) {
         group.bench_function(&bench_name, |b| {
             let data_parser = DataParser::default()
                 .with_compression_strategy(compression_strategy)
-                .with_serialization_type(serialization_type.clone());
+                .with_serialization_type(serialization_type);
 
             b.iter(|| {
                 // Perform deserialization
@@ -16,10 +16,12 @@ fn bench_decompress_deserialize(c: &mut Criterion) {
 
     // Pre-serialize data for each combination type
     let mut parametric_matrix = vec![];
-    for serialization_type in SerializationType::iter() {
+    for serialization_type in
+        SerializationType::iter().filter(SerializationType::is_serde)
+    {
         for compression_strategy in ALL_COMPRESSION_STRATEGIES.iter() {
             let data_parser = DataParser::default()
-                .with_serialization_type(serialization_type.clone())
+                .with_serialization_type(serialization_type)
                 .with_compression_strategy(compression_strategy);
 
             let serialized_and_compressed = runtime.block_on(async {
@@ -30,7 +32,7 @@ fn bench_decompress_deserialize(c: &mut Criterion) {
             });
 
             parametric_matrix.push((
-                serialization_type.clone(),
+                serialization_type,
                 compression_strategy,
                 serialized_and_compressed,
             ));
@@ -52,7 +54,7 @@ fn bench_decompress_deserialize(c: &mut Criterion) {
         group.bench_function(&bench_name, |b| {
             let data_parser = DataParser::default()
                 .with_compression_strategy(compression_strategy)
-                .with_serialization_type(serialization_type.clone());
+                .with_serialization_type(*serialization_type);
 
             b.to_async(&runtime).iter(|| async {
                 let deserialized_and_decompressed = data_parser
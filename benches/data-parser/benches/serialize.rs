@@ -19,6 +19,7 @@ fn bench_serialize(c: &mut Criterion) {
 
     // Benchmarks for different serialization methods
     let parametric_matrix = SerializationType::iter()
+        .filter(SerializationType::is_serde)
         .map(|serialization_type| {
             (serialization_type, DEFAULT_COMPRESSION_STRATEGY.clone())
         })
@@ -30,7 +31,7 @@ fn bench_serialize(c: &mut Criterion) {
         group.bench_function(bench_name, |b| {
             let data_parser = DataParser::default()
                 .with_compression_strategy(&compression_strategy)
-                .with_serialization_type(serialization_type.clone());
+                .with_serialization_type(serialization_type);
 
             b.to_async(&runtime).iter(|| async {
                 let result = data_parser
@@ -18,7 +18,9 @@ fn bench_serialize_compress(c: &mut Criterion) {
         .unwrap();
 
     // build test matrix
-    for serialization_type in SerializationType::iter() {
+    for serialization_type in
+        SerializationType::iter().filter(SerializationType::is_serde)
+    {
         for compression_strategy in ALL_COMPRESSION_STRATEGIES.iter() {
             let bench_name = format!(
                 "[{:?}][{:?}]",
@@ -28,7 +30,7 @@ fn bench_serialize_compress(c: &mut Criterion) {
 
             group.bench_function(&bench_name, |b| {
                 let data_parser = DataParser::default()
-                    .with_serialization_type(serialization_type.clone())
+                    .with_serialization_type(serialization_type)
                     .with_compression_strategy(compression_strategy);
 
                 b.to_async(&runtime).iter(|| async {
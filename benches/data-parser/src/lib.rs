@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use fuel_core_types::{
     blockchain::{
         block::{Block, BlockV1},
@@ -9,6 +11,7 @@ use fuel_core_types::{
     tai64::Tai64,
 };
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 pub fn generate_test_block() -> Block<Transaction> {
     let mut rng = rand::thread_rng();
@@ -46,3 +49,35 @@ pub fn generate_test_block() -> Block<Transaction> {
 pub fn generate_test_tx() -> Transaction {
     Transaction::default_test_tx()
 }
+
+/// A receipt-like payload owning its data, as decoded by `DataParser::decode`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnedRawPayload {
+    pub subject: String,
+    pub timestamp: String,
+    pub data: Vec<u8>,
+}
+
+/// The same payload borrowing its data from the encoded buffer, as decoded
+/// by `DataParser::decode_borrowed`
+#[derive(Debug, Deserialize)]
+pub struct BorrowedRawPayload<'a> {
+    #[serde(borrow)]
+    pub subject: Cow<'a, str>,
+    #[serde(borrow)]
+    pub timestamp: Cow<'a, str>,
+    pub data: &'a [u8],
+}
+
+/// Sizes of the data of the payloads generated by `generate_test_raw_payload`
+pub const RAW_PAYLOAD_SIZES: [usize; 3] = [256, 4096, 65536];
+
+pub fn generate_test_raw_payload(size: usize) -> OwnedRawPayload {
+    let mut rng = rand::thread_rng();
+    let data = (0..size).map(|_| rng.gen::<u8>()).collect();
+    OwnedRawPayload {
+        subject: "receipts.1.0x0000000000000000000000000000000000000000000000000000000000000000.0.log".to_string(),
+        timestamp: "2024-11-28T00:00:00Z".to_string(),
+        data,
+    }
+}
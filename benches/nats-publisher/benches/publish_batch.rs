@@ -0,0 +1,74 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use fuel_streams_core::prelude::*;
+use tokio::runtime::Runtime;
+
+static NATS_URL: &str = "localhost:4222";
+static MSGS_LIMIT: u32 = 1000;
+
+// Every iteration publishes blocks of new heights, so that the publishes
+// aren't deduplicated by JetStream
+static NEXT_HEIGHT: AtomicU32 = AtomicU32::new(1);
+
+fn packets() -> Vec<PublishPacket<Block>> {
+    let start = NEXT_HEIGHT.fetch_add(MSGS_LIMIT, Ordering::Relaxed);
+    (start..start + MSGS_LIMIT)
+        .map(|height| {
+            let block = MockBlock::build(height);
+            let subject = BlocksSubject::from(&block).arc();
+            block.to_packet(subject)
+        })
+        .collect()
+}
+
+fn benchmark_publish(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("Publish Benchmarks");
+    let stream = rt.block_on(async {
+        let opts = NatsClientOpts::admin_opts(NATS_URL).with_rdn_namespace();
+        let client = NatsClient::connect(&opts).await.unwrap();
+        Stream::<Block>::new(&client).await
+    });
+    let stream = &stream;
+
+    group.bench_function("publish_one_at_a_time", |b| {
+        b.to_async(&rt).iter_batched(
+            packets,
+            |packets| async move {
+                for packet in packets {
+                    stream
+                        .publish(&*packet.subject, &packet.payload)
+                        .await
+                        .unwrap();
+                }
+            },
+            criterion::BatchSize::PerIteration,
+        );
+    });
+
+    for publish_window in [1, 16, 64, DEFAULT_PUBLISH_WINDOW] {
+        let stream = &stream.clone().with_publish_window(publish_window);
+        group.bench_function(format!("publish_batch_{publish_window}"), |b| {
+            b.to_async(&rt).iter_batched(
+                packets,
+                |packets| async move {
+                    stream
+                        .publish_batch(&packets, StreamMetadata::now())
+                        .await
+                        .unwrap();
+                },
+                criterion::BatchSize::PerIteration,
+            );
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = benchmark_publish
+);
+criterion_main!(benches);
@@ -0,0 +1,213 @@
+//! Synchronous codecs backing the blocking methods of the built-in
+//! compression strategies.
+//!
+//! These use the same underlying libraries as `async_compression`, so data
+//! compressed through the async API can be decompressed through the blocking
+//! one and vice versa.
+
+use std::io::{self, Write};
+
+use crate::CompressionLevel;
+
+/// Maps a `CompressionLevel` onto a codec specific quality range.
+fn quality(
+    level: CompressionLevel,
+    fastest: i32,
+    best: i32,
+    default: i32,
+) -> i32 {
+    match level {
+        CompressionLevel::Fastest => fastest,
+        CompressionLevel::Best => best,
+        CompressionLevel::Precise(quality) => quality.clamp(fastest, best),
+        _ => default,
+    }
+}
+
+fn flate2_compression(level: CompressionLevel) -> flate2::Compression {
+    flate2::Compression::new(quality(level, 1, 9, 6) as u32)
+}
+
+pub(crate) fn zlib_compress(
+    data: &[u8],
+    level: CompressionLevel,
+) -> io::Result<Vec<u8>> {
+    let mut encoder =
+        flate2::write::ZlibEncoder::new(Vec::new(), flate2_compression(level));
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+pub(crate) fn zlib_decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decoder = flate2::write::ZlibDecoder::new(Vec::new());
+    decoder.write_all(data)?;
+    decoder.finish()
+}
+
+#[cfg(feature = "bench-helpers")]
+pub(crate) fn gzip_compress(
+    data: &[u8],
+    level: CompressionLevel,
+) -> io::Result<Vec<u8>> {
+    let mut encoder =
+        flate2::write::GzEncoder::new(Vec::new(), flate2_compression(level));
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+#[cfg(feature = "bench-helpers")]
+pub(crate) fn gzip_decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decoder = flate2::write::GzDecoder::new(Vec::new());
+    decoder.write_all(data)?;
+    decoder.finish()
+}
+
+#[cfg(feature = "bench-helpers")]
+pub(crate) fn deflate_compress(
+    data: &[u8],
+    level: CompressionLevel,
+) -> io::Result<Vec<u8>> {
+    let mut encoder = flate2::write::DeflateEncoder::new(
+        Vec::new(),
+        flate2_compression(level),
+    );
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+#[cfg(feature = "bench-helpers")]
+pub(crate) fn deflate_decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decoder = flate2::write::DeflateDecoder::new(Vec::new());
+    decoder.write_all(data)?;
+    decoder.finish()
+}
+
+#[cfg(feature = "brotli")]
+pub(crate) fn brotli_compress(
+    data: &[u8],
+    level: CompressionLevel,
+) -> io::Result<Vec<u8>> {
+    let params = brotli::enc::BrotliEncoderParams {
+        quality: quality(level, 0, 11, 11),
+        ..Default::default()
+    };
+    let mut compressed = Vec::new();
+    brotli::BrotliCompress(&mut &data[..], &mut compressed, &params)?;
+    Ok(compressed)
+}
+
+#[cfg(feature = "brotli")]
+pub(crate) fn brotli_decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    brotli::BrotliDecompress(&mut &data[..], &mut decompressed)?;
+    Ok(decompressed)
+}
+
+#[cfg(feature = "bench-helpers")]
+pub(crate) fn bz_compress(
+    data: &[u8],
+    level: CompressionLevel,
+) -> io::Result<Vec<u8>> {
+    let compression = bzip2::Compression::new(quality(level, 1, 9, 6) as u32);
+    let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), compression);
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+#[cfg(feature = "bench-helpers")]
+pub(crate) fn bz_decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decoder = bzip2::write::BzDecoder::new(Vec::new());
+    decoder.write_all(data)?;
+    decoder.finish()
+}
+
+#[cfg(feature = "bench-helpers")]
+pub(crate) fn lzma_compress(
+    data: &[u8],
+    level: CompressionLevel,
+) -> io::Result<Vec<u8>> {
+    let options =
+        xz2::stream::LzmaOptions::new_preset(quality(level, 0, 9, 6) as u32)?;
+    let stream = xz2::stream::Stream::new_lzma_encoder(&options)?;
+    let mut encoder = xz2::write::XzEncoder::new_stream(Vec::new(), stream);
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+#[cfg(feature = "bench-helpers")]
+pub(crate) fn lzma_decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    use std::io::Read;
+
+    let stream = xz2::stream::Stream::new_lzma_decoder(u64::MAX)?;
+    let mut decoder = xz2::read::XzDecoder::new_stream(data, stream);
+    let mut decompressed = Vec::new();
+    decoder.read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+#[cfg(feature = "zstd")]
+pub(crate) fn zstd_level(level: CompressionLevel) -> i32 {
+    quality(
+        level,
+        1,
+        *zstd::compression_level_range().end(),
+        zstd::DEFAULT_COMPRESSION_LEVEL,
+    )
+}
+
+#[cfg(feature = "zstd")]
+pub(crate) fn zstd_compress(
+    data: &[u8],
+    level: CompressionLevel,
+) -> io::Result<Vec<u8>> {
+    zstd::stream::encode_all(data, zstd_level(level))
+}
+
+#[cfg(feature = "zstd")]
+pub(crate) fn zstd_decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    zstd::stream::decode_all(data)
+}
+
+#[cfg(feature = "zstd")]
+pub(crate) fn zstd_dict_compress(
+    data: &[u8],
+    dictionary: &zstd::dict::EncoderDictionary<'_>,
+) -> io::Result<Vec<u8>> {
+    let mut encoder = zstd::stream::write::Encoder::with_prepared_dictionary(
+        Vec::new(),
+        dictionary,
+    )?;
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+#[cfg(feature = "zstd")]
+pub(crate) fn zstd_dict_decompress(
+    data: &[u8],
+    dictionary: &zstd::dict::DecoderDictionary<'_>,
+) -> io::Result<Vec<u8>> {
+    let mut decoder = zstd::stream::write::Decoder::with_prepared_dictionary(
+        Vec::new(),
+        dictionary,
+    )?;
+    decoder.write_all(data)?;
+    decoder.flush()?;
+    Ok(decoder.into_inner())
+}
+
+#[cfg(feature = "lz4")]
+pub(crate) fn lz4_compress(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+    encoder.write_all(data)?;
+    encoder.finish().map_err(io::Error::other)
+}
+
+#[cfg(feature = "lz4")]
+pub(crate) fn lz4_decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut decoder = lz4_flex::frame::FrameDecoder::new(data);
+    let mut decompressed = Vec::new();
+    decoder.read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
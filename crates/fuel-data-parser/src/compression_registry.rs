@@ -0,0 +1,261 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use crate::{
+    CompressionLevel,
+    CompressionRegistryError,
+    CompressionStrategy,
+    ZLibCompressionStrategy,
+    NO_COMPRESSION_ID,
+};
+
+/// Builds a compression strategy for a given compression level.
+pub type CompressionStrategyFactory =
+    Arc<dyn Fn(CompressionLevel) -> Arc<dyn CompressionStrategy> + Send + Sync>;
+
+#[derive(Clone)]
+struct RegisteredStrategy {
+    id: u8,
+    factory: CompressionStrategyFactory,
+}
+
+/// A `CompressionLevel` as a key of the strategies built by a registry, as
+/// levels can't be hashed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum LevelKey {
+    Fastest,
+    Best,
+    Default,
+    Precise(i32),
+}
+
+impl LevelKey {
+    /// Returns `None` for levels added to `CompressionLevel` since, which
+    /// aren't cached.
+    fn of(level: CompressionLevel) -> Option<Self> {
+        match level {
+            CompressionLevel::Fastest => Some(Self::Fastest),
+            CompressionLevel::Best => Some(Self::Best),
+            CompressionLevel::Default => Some(Self::Default),
+            CompressionLevel::Precise(quality) => Some(Self::Precise(quality)),
+            _ => None,
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref GLOBAL_COMPRESSION_REGISTRY: CompressionRegistry =
+        CompressionRegistry::with_builtins();
+}
+
+/// A registry of compression strategies, looked up by name or by the id
+/// written into the envelope header.
+///
+/// The global registry, returned by [`CompressionRegistry::global`], comes
+/// with every built-in strategy enabled through crate features and is used
+/// by `DataParser::decode` to resolve the compression of incoming payloads.
+/// Custom strategies registered in it can therefore be selected from
+/// configuration and decoded like the built-in ones.
+///
+/// Strategies are built once per id and level, and shared by every lookup
+/// after that, as decoding looks one up for every payload.
+///
+/// # Examples
+///
+/// ```
+/// use fuel_data_parser::{CompressionLevel, CompressionRegistry, DataParser};
+///
+/// let registry = CompressionRegistry::global();
+/// let strategy = registry
+///     .get_with_level("zlib", CompressionLevel::Best)
+///     .expect("zlib is always available");
+/// let parser = DataParser::default().with_compression_strategy(&strategy);
+/// ```
+#[derive(Default)]
+pub struct CompressionRegistry {
+    strategies: RwLock<HashMap<String, RegisteredStrategy>>,
+    built: RwLock<HashMap<(u8, LevelKey), Arc<dyn CompressionStrategy>>>,
+}
+
+impl CompressionRegistry {
+    /// Returns the process-wide registry.
+    pub fn global() -> &'static CompressionRegistry {
+        &GLOBAL_COMPRESSION_REGISTRY
+    }
+
+    /// Creates a registry holding every built-in strategy enabled through
+    /// crate features.
+    pub fn with_builtins() -> Self {
+        let registry = Self::default();
+
+        macro_rules! register_builtin {
+            ($strategy:ty) => {
+                registry
+                    .register(<$strategy>::NAME, <$strategy>::ID, |level| {
+                        Arc::new(<$strategy>::new(level))
+                    })
+                    .expect(
+                        "Built-in strategies must have unique names and ids",
+                    );
+            };
+        }
+
+        register_builtin!(ZLibCompressionStrategy);
+        #[cfg(feature = "bench-helpers")]
+        register_builtin!(crate::GzipCompressionStrategy);
+        #[cfg(feature = "brotli")]
+        register_builtin!(crate::BrotliCompressionStrategy);
+        #[cfg(feature = "bench-helpers")]
+        register_builtin!(crate::BzCompressionStrategy);
+        #[cfg(feature = "bench-helpers")]
+        register_builtin!(crate::LzmaCompressionStrategy);
+        #[cfg(feature = "bench-helpers")]
+        register_builtin!(crate::DeflateCompressionStrategy);
+        #[cfg(feature = "zstd")]
+        register_builtin!(crate::ZstdCompressionStrategy);
+        #[cfg(feature = "zstd")]
+        register_builtin!(crate::ZstdDictionaryCompressionStrategy);
+        #[cfg(feature = "lz4")]
+        register_builtin!(crate::Lz4CompressionStrategy);
+
+        registry
+    }
+
+    /// Registers a compression strategy under a name and envelope id.
+    ///
+    /// Both the name and the id must be unique within the registry, and the
+    /// id `0` is reserved for uncompressed payloads.
+    pub fn register<F>(
+        &self,
+        name: &str,
+        id: u8,
+        factory: F,
+    ) -> Result<(), CompressionRegistryError>
+    where
+        F: Fn(CompressionLevel) -> Arc<dyn CompressionStrategy>
+            + Send
+            + Sync
+            + 'static,
+    {
+        if id == NO_COMPRESSION_ID {
+            return Err(CompressionRegistryError::ReservedId(id));
+        }
+
+        let mut strategies =
+            self.strategies.write().expect("Registry lock poisoned");
+        if strategies.contains_key(name) {
+            return Err(CompressionRegistryError::NameTaken(name.to_string()));
+        }
+        if strategies.values().any(|strategy| strategy.id == id) {
+            return Err(CompressionRegistryError::IdTaken(id));
+        }
+
+        strategies.insert(
+            name.to_string(),
+            RegisteredStrategy {
+                id,
+                factory: Arc::new(factory),
+            },
+        );
+        Ok(())
+    }
+
+    /// Looks up a strategy by name, with its default compression level.
+    pub fn get(&self, name: &str) -> Option<Arc<dyn CompressionStrategy>> {
+        self.get_with_level(name, CompressionLevel::Fastest)
+    }
+
+    /// Looks up a strategy by name, configured with the given compression level.
+    pub fn get_with_level(
+        &self,
+        name: &str,
+        level: CompressionLevel,
+    ) -> Option<Arc<dyn CompressionStrategy>> {
+        let strategies =
+            self.strategies.read().expect("Registry lock poisoned");
+        strategies
+            .get(name)
+            .map(|strategy| self.build(strategy, level))
+    }
+
+    /// Looks up a strategy by the id written into the envelope header.
+    pub fn get_by_id(&self, id: u8) -> Option<Arc<dyn CompressionStrategy>> {
+        let strategies =
+            self.strategies.read().expect("Registry lock poisoned");
+        strategies
+            .values()
+            .find(|strategy| strategy.id == id)
+            .map(|strategy| self.build(strategy, CompressionLevel::Fastest))
+    }
+
+    /// Drops the strategies built for an id, so that the next lookups build
+    /// them again from the state they depend on.
+    #[cfg(feature = "zstd")]
+    pub(crate) fn evict(&self, id: u8) {
+        self.built
+            .write()
+            .expect("Registry lock poisoned")
+            .retain(|(built_id, _), _| *built_id != id);
+    }
+
+    /// Returns the names of all registered strategies, sorted alphabetically.
+    pub fn names(&self) -> Vec<String> {
+        let strategies =
+            self.strategies.read().expect("Registry lock poisoned");
+        let mut names = strategies.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    /// Returns the strategy built for the id of `strategy` and `level`,
+    /// building it on the first lookup.
+    fn build(
+        &self,
+        strategy: &RegisteredStrategy,
+        level: CompressionLevel,
+    ) -> Arc<dyn CompressionStrategy> {
+        let Some(key) = LevelKey::of(level).map(|key| (strategy.id, key))
+        else {
+            return (strategy.factory)(level);
+        };
+        if let Some(built) =
+            self.built.read().expect("Registry lock poisoned").get(&key)
+        {
+            return Arc::clone(built);
+        }
+
+        let built = (strategy.factory)(level);
+        let mut cache = self.built.write().expect("Registry lock poisoned");
+        Arc::clone(cache.entry(key).or_insert(built))
+    }
+}
+
+/// Parses a compression level from configuration.
+///
+/// Accepts `fastest`, `best`, `default` or an algorithm specific integer
+/// quality.
+///
+/// # Examples
+///
+/// ```
+/// use fuel_data_parser::parse_compression_level;
+///
+/// assert!(parse_compression_level("best").is_ok());
+/// assert!(parse_compression_level("9").is_ok());
+/// assert!(parse_compression_level("fast-ish").is_err());
+/// ```
+pub fn parse_compression_level(
+    level: &str,
+) -> Result<CompressionLevel, CompressionRegistryError> {
+    match level.trim().to_lowercase().as_str() {
+        "fastest" => Ok(CompressionLevel::Fastest),
+        "best" => Ok(CompressionLevel::Best),
+        "default" => Ok(CompressionLevel::Default),
+        other => other
+            .parse()
+            .map(CompressionLevel::Precise)
+            .map_err(|_| CompressionRegistryError::InvalidLevel(level.into())),
+    }
+}
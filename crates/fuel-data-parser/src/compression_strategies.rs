@@ -1,34 +1,40 @@
+use std::sync::Arc;
+
 pub use async_compression::{
     tokio::write as compression_encoders_and_decoders,
     Level as CompressionLevel,
 };
 use tokio::io::AsyncWriteExt;
 
-use crate::CompressionError;
-
-/// A private module used to seal the `CompressionStrategy` trait. This ensures
-/// that the trait cannot be implemented outside of this module.
-mod private {
-    pub trait Sealed {}
-}
+use crate::{blocking_codecs, CompressionError, CompressionRegistry};
 
 /// The `CompressionStrategy` trait defines the interface for compression and decompression strategies.
-/// It is sealed to restrict external implementations.
+/// It is open for external implementations, which can be made available to
+/// `DataParser::decode` by registering them in the [`CompressionRegistry`].
 ///
 /// # Requirements
 /// Implementations must:
 /// - Provide a name for the strategy.
+/// - Provide a unique id, written into the envelope header of encoded payloads.
 /// - Implement asynchronous methods for compressing and decompressing data.
+/// - Implement blocking methods for compressing and decompressing data.
 ///
 /// # Associated Types
 /// - `name` - Returns the name of the compression strategy.
+/// - `id` - Returns the id of the compression strategy.
 /// - `compress` - Compresses the provided data asynchronously.
 /// - `decompress` - Decompresses the provided data asynchronously.
+/// - `compress_blocking` - Compresses the provided data on the current thread.
+/// - `decompress_blocking` - Decompresses the provided data on the current thread.
 #[async_trait::async_trait]
-pub trait CompressionStrategy: private::Sealed + Sync + Send {
+pub trait CompressionStrategy: Sync + Send {
     /// Returns the name of the compression strategy.
     fn name(&self) -> &'static str;
 
+    /// Returns the id of the compression strategy, as written into the
+    /// envelope header of encoded payloads.
+    fn id(&self) -> u8;
+
     /// Compresses the provided data asynchronously.
     ///
     /// # Arguments
@@ -56,31 +62,91 @@ pub trait CompressionStrategy: private::Sealed + Sync + Send {
         &self,
         compressed: &[u8],
     ) -> Result<Vec<u8>, CompressionError>;
+
+    /// Compresses the provided data on the current thread, without going
+    /// through an async writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `uncompressed` - A slice of bytes representing the data to be compressed.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `Vec<u8>` of the compressed data or a `CompressionError` if compression fails.
+    fn compress_blocking(
+        &self,
+        uncompressed: &[u8],
+    ) -> Result<Vec<u8>, CompressionError>;
+
+    /// Decompresses the provided data on the current thread, without going
+    /// through an async writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `compressed` - A slice of bytes representing the data to be decompressed.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `Vec<u8>` of the decompressed data or a `CompressionError` if decompression fails.
+    fn decompress_blocking(
+        &self,
+        compressed: &[u8],
+    ) -> Result<Vec<u8>, CompressionError>;
 }
 
 /// A macro to define a new compression strategy by implementing the `CompressionStrategy` trait.
 ///
+/// The generated struct holds the `CompressionLevel` used for compression,
+/// which defaults to `CompressionLevel::Fastest`.
+///
 /// # Parameters
-/// - `$name`: The name of the compression strategy (typically a unit struct).
+/// - `$name`: The name of the compression strategy struct.
 /// - `$compression_type`: The compression type (e.g., ZLib, Gzip, Brotli, Bz, Lzma, Deflate, Zstd).
-/// - `$compression_level`: The compression level to be used (using `CompressionLevel` enum).
+/// - `$id`: The id of the compression strategy, written into the envelope header.
+/// - `$strategy_name`: The name the strategy is registered under in the `CompressionRegistry`.
 ///
 /// # Example
-/// struct TestCompressionStrategy;
-/// define_compression_strategy!(TestCompressionStrategy, Zlib, CompressionLevel::Fastest);
+/// define_compression_strategy!(TestCompressionStrategy, Zlib, 1, "zlib");
 macro_rules! define_compression_strategy {
-    ($name:ident, $compression_type:ident, $compression_level:ty) => {
-        impl private::Sealed for $name {}
+    ($name:ident, $compression_type:ident, $id:expr, $strategy_name:literal) => {
+        #[doc = concat!("Compression strategy using ", $strategy_name, ".")]
+        #[derive(Debug, Clone, Copy)]
+        pub struct $name {
+            level: CompressionLevel,
+        }
+
+        impl $name {
+            pub const NAME: &'static str = $strategy_name;
+            pub const ID: u8 = $id;
+
+            pub fn new(level: CompressionLevel) -> Self {
+                Self { level }
+            }
+
+            pub fn level(&self) -> CompressionLevel {
+                self.level
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new(CompressionLevel::Fastest)
+            }
+        }
 
         #[async_trait::async_trait]
         impl CompressionStrategy for $name {
             fn name(&self) -> &'static str {
-                stringify!($name)
+                Self::NAME
+            }
+
+            fn id(&self) -> u8 {
+                Self::ID
             }
 
             async fn compress(&self, uncompressed: &[u8]) -> Result<Vec<u8>, CompressionError> {
                 paste::paste! {
-                    let mut encoder = compression_encoders_and_decoders::[<$compression_type Encoder>]::with_quality(Vec::new(), $compression_level);
+                    let mut encoder = compression_encoders_and_decoders::[<$compression_type Encoder>]::with_quality(Vec::new(), self.level);
                     encoder
                         .write_all(uncompressed)
                         .await
@@ -107,94 +173,144 @@ macro_rules! define_compression_strategy {
                     Ok(decoder.into_inner())
                 }
             }
+
+            fn compress_blocking(&self, uncompressed: &[u8]) -> Result<Vec<u8>, CompressionError> {
+                paste::paste! {
+                    blocking_codecs::[<$compression_type:snake _compress>](uncompressed, self.level)
+                        .map_err(CompressionError::[<$compression_type>])
+                }
+            }
+
+            fn decompress_blocking(&self, compressed: &[u8]) -> Result<Vec<u8>, CompressionError> {
+                paste::paste! {
+                    blocking_codecs::[<$compression_type:snake _decompress>](compressed)
+                        .map_err(CompressionError::[<$compression_type>])
+                }
+            }
         }
     };
 }
 
-#[derive(Clone)]
-pub struct ZLibCompressionStrategy;
-define_compression_strategy!(
-    ZLibCompressionStrategy,
-    Zlib,
-    CompressionLevel::Fastest
-);
+define_compression_strategy!(ZLibCompressionStrategy, Zlib, 1, "zlib");
 
 #[cfg(feature = "bench-helpers")]
-#[derive(Clone)]
-pub struct GzipCompressionStrategy;
-#[cfg(feature = "bench-helpers")]
-define_compression_strategy!(
-    GzipCompressionStrategy,
-    Gzip,
-    CompressionLevel::Fastest
-);
+define_compression_strategy!(GzipCompressionStrategy, Gzip, 2, "gzip");
 
-#[cfg(feature = "bench-helpers")]
-#[derive(Clone)]
-pub struct BrotliCompressionStrategy;
-#[cfg(feature = "bench-helpers")]
-define_compression_strategy!(
-    BrotliCompressionStrategy,
-    Brotli,
-    CompressionLevel::Fastest
-);
+#[cfg(feature = "brotli")]
+define_compression_strategy!(BrotliCompressionStrategy, Brotli, 3, "brotli");
 
 #[cfg(feature = "bench-helpers")]
-#[derive(Clone)]
-pub struct BzCompressionStrategy;
-#[cfg(feature = "bench-helpers")]
-define_compression_strategy!(
-    BzCompressionStrategy,
-    Bz,
-    CompressionLevel::Fastest
-);
+define_compression_strategy!(BzCompressionStrategy, Bz, 4, "bzip2");
 
 #[cfg(feature = "bench-helpers")]
-#[derive(Clone)]
-pub struct LzmaCompressionStrategy;
-#[cfg(feature = "bench-helpers")]
-define_compression_strategy!(
-    LzmaCompressionStrategy,
-    Lzma,
-    CompressionLevel::Fastest
-);
+define_compression_strategy!(LzmaCompressionStrategy, Lzma, 5, "lzma");
 
 #[cfg(feature = "bench-helpers")]
-#[derive(Clone)]
-pub struct DeflateCompressionStrategy;
-#[cfg(feature = "bench-helpers")]
-define_compression_strategy!(
-    DeflateCompressionStrategy,
-    Deflate,
-    CompressionLevel::Fastest
-);
+define_compression_strategy!(DeflateCompressionStrategy, Deflate, 6, "deflate");
 
-#[cfg(feature = "bench-helpers")]
-#[derive(Clone)]
-pub struct ZstdCompressionStrategy;
-#[cfg(feature = "bench-helpers")]
-define_compression_strategy!(
-    ZstdCompressionStrategy,
-    Zstd,
-    CompressionLevel::Fastest
-);
+#[cfg(feature = "zstd")]
+define_compression_strategy!(ZstdCompressionStrategy, Zstd, 7, "zstd");
 
-use std::sync::Arc;
+/// Compression strategy using the LZ4 frame format.
+///
+/// LZ4 has no compression levels, so the level is accepted for consistency
+/// with the other strategies but ignored. There is no async LZ4 writer, so
+/// the async methods run the blocking codec, which is cheap for in-memory
+/// buffers.
+#[cfg(feature = "lz4")]
+#[derive(Debug, Clone, Copy)]
+pub struct Lz4CompressionStrategy {
+    level: CompressionLevel,
+}
+
+#[cfg(feature = "lz4")]
+impl Lz4CompressionStrategy {
+    pub const NAME: &'static str = "lz4";
+    pub const ID: u8 = 8;
+
+    pub fn new(level: CompressionLevel) -> Self {
+        Self { level }
+    }
+
+    pub fn level(&self) -> CompressionLevel {
+        self.level
+    }
+}
+
+#[cfg(feature = "lz4")]
+impl Default for Lz4CompressionStrategy {
+    fn default() -> Self {
+        Self::new(CompressionLevel::Fastest)
+    }
+}
+
+#[cfg(feature = "lz4")]
+#[async_trait::async_trait]
+impl CompressionStrategy for Lz4CompressionStrategy {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn id(&self) -> u8 {
+        Self::ID
+    }
+
+    async fn compress(
+        &self,
+        uncompressed: &[u8],
+    ) -> Result<Vec<u8>, CompressionError> {
+        self.compress_blocking(uncompressed)
+    }
+
+    async fn decompress(
+        &self,
+        compressed: &[u8],
+    ) -> Result<Vec<u8>, CompressionError> {
+        self.decompress_blocking(compressed)
+    }
+
+    fn compress_blocking(
+        &self,
+        uncompressed: &[u8],
+    ) -> Result<Vec<u8>, CompressionError> {
+        blocking_codecs::lz4_compress(uncompressed)
+            .map_err(CompressionError::Lz4)
+    }
+
+    fn decompress_blocking(
+        &self,
+        compressed: &[u8],
+    ) -> Result<Vec<u8>, CompressionError> {
+        blocking_codecs::lz4_decompress(compressed)
+            .map_err(CompressionError::Lz4)
+    }
+}
 
 lazy_static::lazy_static! {
-    pub static ref DEFAULT_COMPRESSION_STRATEGY: Arc<dyn CompressionStrategy> =  Arc::new(ZLibCompressionStrategy);
+    pub static ref DEFAULT_COMPRESSION_STRATEGY: Arc<dyn CompressionStrategy> =  Arc::new(ZLibCompressionStrategy::default());
+}
+
+/// Looks up a compression strategy by the id written into the envelope header.
+///
+/// Returns `None` if no strategy with this id is registered in the global
+/// [`CompressionRegistry`].
+pub fn compression_strategy_by_id(
+    id: u8,
+) -> Option<Arc<dyn CompressionStrategy>> {
+    CompressionRegistry::global().get_by_id(id)
 }
 
 #[cfg(feature = "bench-helpers")]
 lazy_static::lazy_static! {
-    pub static ref ALL_COMPRESSION_STRATEGIES: [Arc<dyn CompressionStrategy>; 7] = [
-        Arc::new(ZLibCompressionStrategy),
-        Arc::new(GzipCompressionStrategy),
-        Arc::new(BrotliCompressionStrategy),
-        Arc::new(BzCompressionStrategy),
-        Arc::new(LzmaCompressionStrategy),
-        Arc::new(DeflateCompressionStrategy),
-        Arc::new(ZstdCompressionStrategy),
+    pub static ref ALL_COMPRESSION_STRATEGIES: [Arc<dyn CompressionStrategy>; 8] = [
+        Arc::new(ZLibCompressionStrategy::default()),
+        Arc::new(GzipCompressionStrategy::default()),
+        Arc::new(BrotliCompressionStrategy::default()),
+        Arc::new(BzCompressionStrategy::default()),
+        Arc::new(LzmaCompressionStrategy::default()),
+        Arc::new(DeflateCompressionStrategy::default()),
+        Arc::new(ZstdCompressionStrategy::default()),
+        Arc::new(Lz4CompressionStrategy::default()),
     ];
 
 }
@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use crate::{
+    compression_strategy_by_id,
+    CompressionStrategy,
+    EnvelopeError,
+    SerializationType,
+};
+
+/// Magic byte marking the start of an enveloped payload.
+pub const ENVELOPE_MAGIC: u8 = 0xF5;
+
+/// Current version of the envelope header layout.
+pub const ENVELOPE_VERSION: u8 = 2;
+
+/// Length in bytes of the envelope header.
+pub const ENVELOPE_HEADER_LEN: usize = 6;
+
+/// Length in bytes of the version 1 envelope header, which has no schema
+/// version.
+pub const ENVELOPE_V1_HEADER_LEN: usize = 4;
+
+/// Schema version of payloads that were encoded without one.
+pub const UNVERSIONED_SCHEMA: u16 = 0;
+
+/// Compression id used when a payload is not compressed.
+pub const NO_COMPRESSION_ID: u8 = 0;
+
+/// A self-describing header prepended to every payload produced by
+/// `DataParser::encode`.
+///
+/// The header is laid out as six bytes:
+///
+/// | byte | content                              |
+/// |------|--------------------------------------|
+/// | 0    | magic byte (`ENVELOPE_MAGIC`)        |
+/// | 1    | envelope format version              |
+/// | 2    | serialization id                     |
+/// | 3    | compression id (`0` for none)        |
+/// | 4-5  | payload schema version (big endian)  |
+///
+/// The schema version describes the layout of the payload itself, so that
+/// consumers can migrate payloads published with an older layout. Version 1
+/// headers stop after the compression id and read as `UNVERSIONED_SCHEMA`.
+///
+/// # Examples
+///
+/// ```
+/// use fuel_data_parser::{EnvelopeHeader, SerializationType};
+///
+/// let header = EnvelopeHeader::new(SerializationType::Postcard, None)
+///     .with_schema_version(3);
+/// let bytes = header.to_bytes();
+///
+/// let (parsed, body) = EnvelopeHeader::parse(&bytes).unwrap().unwrap();
+/// assert_eq!(parsed, header);
+/// assert!(body.is_empty());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvelopeHeader {
+    pub version: u8,
+    pub serialization_type: SerializationType,
+    pub compression_id: u8,
+    pub schema_version: u16,
+}
+
+impl EnvelopeHeader {
+    pub fn new(
+        serialization_type: SerializationType,
+        compression_strategy: Option<&Arc<dyn CompressionStrategy>>,
+    ) -> Self {
+        Self {
+            version: ENVELOPE_VERSION,
+            serialization_type,
+            compression_id: compression_strategy
+                .map(|strategy| strategy.id())
+                .unwrap_or(NO_COMPRESSION_ID),
+            schema_version: UNVERSIONED_SCHEMA,
+        }
+    }
+
+    pub fn with_schema_version(mut self, schema_version: u16) -> Self {
+        self.schema_version = schema_version;
+        self
+    }
+
+    pub fn to_bytes(&self) -> [u8; ENVELOPE_HEADER_LEN] {
+        let [schema_hi, schema_lo] = self.schema_version.to_be_bytes();
+        [
+            ENVELOPE_MAGIC,
+            self.version,
+            self.serialization_type.id(),
+            self.compression_id,
+            schema_hi,
+            schema_lo,
+        ]
+    }
+
+    /// Splits an enveloped payload into its header and body.
+    ///
+    /// Returns `Ok(None)` when the payload does not start with the envelope
+    /// magic byte, which is the case for payloads produced before the
+    /// envelope was introduced.
+    pub fn parse(data: &[u8]) -> Result<Option<(Self, &[u8])>, EnvelopeError> {
+        if data.first() != Some(&ENVELOPE_MAGIC) {
+            return Ok(None);
+        }
+        let version = *data.get(1).ok_or(EnvelopeError::Truncated {
+            expected: ENVELOPE_V1_HEADER_LEN,
+            actual: data.len(),
+        })?;
+        let header_len = match version {
+            1 => ENVELOPE_V1_HEADER_LEN,
+            ENVELOPE_VERSION => ENVELOPE_HEADER_LEN,
+            _ => return Err(EnvelopeError::UnsupportedVersion(version)),
+        };
+        if data.len() < header_len {
+            return Err(EnvelopeError::Truncated {
+                expected: header_len,
+                actual: data.len(),
+            });
+        }
+
+        let serialization_type = SerializationType::try_from(data[2])?;
+        let schema_version = match version {
+            1 => UNVERSIONED_SCHEMA,
+            _ => u16::from_be_bytes([data[4], data[5]]),
+        };
+        let header = Self {
+            version,
+            serialization_type,
+            compression_id: data[3],
+            schema_version,
+        };
+
+        Ok(Some((header, &data[header_len..])))
+    }
+
+    /// Resolves the compression strategy referenced by this header.
+    pub fn compression_strategy(
+        &self,
+    ) -> Result<Option<Arc<dyn CompressionStrategy>>, EnvelopeError> {
+        if self.compression_id == NO_COMPRESSION_ID {
+            return Ok(None);
+        }
+
+        compression_strategy_by_id(self.compression_id)
+            .map(Some)
+            .ok_or(EnvelopeError::UnknownCompression(self.compression_id))
+    }
+}
@@ -20,6 +20,29 @@ pub enum CompressionError {
     Deflate(std::io::Error),
     /// Failed to compress or decompress data using zstd: {0}
     Zstd(std::io::Error),
+    /// Failed to compress or decompress data using lz4: {0}
+    Lz4(std::io::Error),
+    /// No zstd dictionary is loaded to compress with
+    NoZstdDictionary,
+    /// Zstd dictionary {0} is not loaded, it is required to decompress this payload
+    UnknownZstdDictionary(u32),
+    /// Zstd dictionary has no id, only trained dictionaries are supported
+    ZstdDictionaryWithoutId,
+    /// Payload was not compressed with a zstd dictionary
+    MissingZstdDictionaryId,
+}
+
+/// Compression registry error types.
+#[derive(Debug, DisplayDoc, Error)]
+pub enum CompressionRegistryError {
+    /// A compression strategy named {0} is already registered
+    NameTaken(String),
+    /// A compression strategy with id {0} is already registered
+    IdTaken(u8),
+    /// Compression id {0} is reserved for uncompressed payloads
+    ReservedId(u8),
+    /// Invalid compression level: {0}
+    InvalidLevel(String),
 }
 
 /// Serialization/Deserialization error types.
@@ -31,6 +54,32 @@ pub enum SerdeError {
     Postcard(#[from] postcard::Error),
     /// Failed to serialize or deserialize data using JSON: {0}
     Json(#[from] serde_json::Error),
+    /// Failed to serialize data using MessagePack: {0}
+    MessagePackEncode(#[from] rmp_serde::encode::Error),
+    /// Failed to deserialize data using MessagePack: {0}
+    MessagePackDecode(#[from] rmp_serde::decode::Error),
+    /// Failed to serialize data using CBOR: {0}
+    CborEncode(
+        #[from] cbor4ii::serde::EncodeError<std::collections::TryReserveError>,
+    ),
+    /// Failed to deserialize data using CBOR: {0}
+    CborDecode(#[from] cbor4ii::serde::DecodeError<std::convert::Infallible>),
+    /// Failed to decode data using protobuf: {0}
+    #[cfg(feature = "protobuf")]
+    Protobuf(#[from] prost::DecodeError),
+}
+
+/// Envelope header error types.
+#[derive(Debug, DisplayDoc, Error)]
+pub enum EnvelopeError {
+    /// Envelope header is truncated: expected {expected} bytes, got {actual}
+    Truncated { expected: usize, actual: usize },
+    /// Unsupported envelope version: {0}
+    UnsupportedVersion(u8),
+    /// Unknown serialization id in envelope header: {0}
+    UnknownSerialization(u8),
+    /// Unknown or unavailable compression id in envelope header: {0}
+    UnknownCompression(u8),
 }
 
 /// Data parser error types.
@@ -40,4 +89,15 @@ pub enum Error {
     Compression(#[from] CompressionError),
     /// An error occurred during data serialization or deserialization: {0}
     Serde(#[from] SerdeError),
+    /// An error occurred while reading the payload envelope: {0}
+    Envelope(#[from] EnvelopeError),
+    /// Cannot decode a payload compressed with {0} without copying it
+    BorrowCompressed(&'static str),
+    /// Protobuf payloads can only be encoded from or decoded into protobuf messages
+    ProtobufRequiresMessage,
+    /// Expected a protobuf payload, got a {0} one
+    NotProtobuf(crate::SerializationType),
+    /// The parser thread pool dropped the task before completing it
+    #[cfg(feature = "thread-pool")]
+    ThreadPoolTaskDropped,
 }
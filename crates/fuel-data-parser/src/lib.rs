@@ -1,26 +1,111 @@
 #![doc = include_str!("../README.md")]
 
+mod blocking_codecs;
+mod compression_registry;
 mod compression_strategies;
+mod envelope;
 mod error;
+#[cfg(feature = "thread-pool")]
+mod thread_pool;
+#[cfg(feature = "zstd")]
+mod zstd_dictionary;
 
-use std::{fmt::Debug, sync::Arc};
+use std::{borrow::Cow, fmt::Debug, sync::Arc};
 
+pub use compression_registry::*;
 pub use compression_strategies::*;
+pub use envelope::*;
+#[cfg(feature = "thread-pool")]
+pub use thread_pool::*;
+#[cfg(feature = "zstd")]
+pub use zstd_dictionary::*;
 
-pub use crate::error::{CompressionError, Error, SerdeError};
+pub use crate::error::{
+    CompressionError,
+    CompressionRegistryError,
+    EnvelopeError,
+    Error,
+    SerdeError,
+};
 
 /// Serialization types supported for data parsing
-#[derive(Debug, Clone, strum::EnumIter, strum_macros::Display)]
+///
+/// The discriminant of each variant is the serialization id written into
+/// the envelope header of encoded payloads and must never be reused.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum::EnumIter,
+    strum_macros::Display,
+    strum_macros::EnumString,
+)]
+#[repr(u8)]
 pub enum SerializationType {
     /// Bincode serialization
     #[strum(serialize = "bincode")]
-    Bincode,
+    Bincode = 1,
     /// Postcard serialization
     #[strum(serialize = "postcard")]
-    Postcard,
+    Postcard = 2,
     /// json serialization
     #[strum(serialize = "json")]
-    Json,
+    Json = 3,
+    /// MessagePack serialization, with named struct fields
+    #[strum(serialize = "messagepack")]
+    MessagePack = 4,
+    /// CBOR serialization
+    #[strum(serialize = "cbor")]
+    Cbor = 5,
+    /// Protocol Buffers serialization
+    ///
+    /// Protobuf is schema based, so it does not go through serde: payloads
+    /// are encoded from and decoded into `prost` messages with
+    /// `DataParser::encode_protobuf` and `DataParser::decode_protobuf`.
+    #[strum(serialize = "protobuf")]
+    Protobuf = 6,
+}
+
+impl SerializationType {
+    /// Returns the serialization id written into the envelope header.
+    pub fn id(&self) -> u8 {
+        *self as u8
+    }
+
+    /// Returns whether any `DataParseable` type can be encoded with this
+    /// serialization, as opposed to schema based ones.
+    pub fn is_serde(&self) -> bool {
+        !matches!(self, SerializationType::Protobuf)
+    }
+
+    /// Returns whether payloads carry their field names, so that readers can
+    /// skip fields they don't know about.
+    pub fn is_self_describing(&self) -> bool {
+        matches!(
+            self,
+            SerializationType::Json
+                | SerializationType::MessagePack
+                | SerializationType::Cbor
+        )
+    }
+}
+
+impl TryFrom<u8> for SerializationType {
+    type Error = EnvelopeError;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        match id {
+            1 => Ok(SerializationType::Bincode),
+            2 => Ok(SerializationType::Postcard),
+            3 => Ok(SerializationType::Json),
+            4 => Ok(SerializationType::MessagePack),
+            5 => Ok(SerializationType::Cbor),
+            6 => Ok(SerializationType::Protobuf),
+            _ => Err(EnvelopeError::UnknownSerialization(id)),
+        }
+    }
 }
 
 /// Traits required for a data type to be parseable
@@ -45,12 +130,18 @@ impl<
 /// optimizing memory usage and I/O bandwidth by applying different
 /// serialization formats and optional compression strategies.
 ///
+/// Encoded payloads are prefixed with an [`EnvelopeHeader`] describing the
+/// serialization and compression used, so `decode` does not depend on the
+/// decoding parser being configured like the encoding one.
+///
 /// # Fields
 ///
 /// * `compression_strategy` - An `Option<Arc<dyn CompressionStrategy>>` that defines
 ///   the method of data compression. If `None`, no compression is applied.
 /// * `serialization_type` - An enum that specifies the serialization format
-///   (e.g., Bincode, Postcard, JSON).
+///   (e.g., Bincode, Postcard, JSON, MessagePack, CBOR, Protobuf).
+/// * `schema_version` - The version of the payload layout recorded in the
+///   envelope header, `UNVERSIONED_SCHEMA` by default.
 ///
 /// # Examples
 ///
@@ -79,6 +170,7 @@ impl<
 pub struct DataParser {
     compression_strategy: Option<Arc<dyn CompressionStrategy>>,
     pub serialization_type: SerializationType,
+    pub schema_version: u16,
 }
 
 impl Default for DataParser {
@@ -97,6 +189,7 @@ impl Default for DataParser {
         Self {
             compression_strategy: None,
             serialization_type: SerializationType::Json,
+            schema_version: UNVERSIONED_SCHEMA,
         }
     }
 }
@@ -155,7 +248,46 @@ impl DataParser {
         self
     }
 
-    /// Encodes the provided data by serializing and optionally compressing it.
+    /// Sets the payload schema version recorded in the envelope header of
+    /// encoded payloads.
+    ///
+    /// The parser does not interpret the version: it lets consumers of types
+    /// whose layout changed over time decode older payloads accordingly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fuel_data_parser::DataParser;
+    ///
+    /// let parser = DataParser::default().with_schema_version(2);
+    /// assert_eq!(parser.schema_version, 2);
+    /// ```
+    pub fn with_schema_version(mut self, schema_version: u16) -> Self {
+        self.schema_version = schema_version;
+        self
+    }
+
+    /// Returns the compression strategy of the `DataParser`, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fuel_data_parser::{DataParser, DEFAULT_COMPRESSION_STRATEGY};
+    ///
+    /// assert!(DataParser::default().compression_strategy().is_none());
+    ///
+    /// let parser = DataParser::default()
+    ///     .with_compression_strategy(&DEFAULT_COMPRESSION_STRATEGY);
+    /// assert!(parser.compression_strategy().is_some());
+    /// ```
+    pub fn compression_strategy(
+        &self,
+    ) -> Option<&Arc<dyn CompressionStrategy>> {
+        self.compression_strategy.as_ref()
+    }
+
+    /// Encodes the provided data by serializing and optionally compressing it,
+    /// prefixed with an envelope header describing the encoding.
     ///
     /// # Arguments
     ///
@@ -163,7 +295,7 @@ impl DataParser {
     ///
     /// # Returns
     ///
-    /// A `Result` containing either a `Vec<u8>` of the serialized (and optionally compressed) data,
+    /// A `Result` containing either a `Vec<u8>` of the enveloped, serialized (and optionally compressed) data,
     /// or an `Error` if encoding fails.
     ///
     /// # Examples
@@ -190,10 +322,99 @@ impl DataParser {
         data: &T,
     ) -> Result<Vec<u8>, Error> {
         let serialized_data = self.serialize(data).await?;
-        Ok(match &self.compression_strategy {
+        let body = match &self.compression_strategy {
             Some(strategy) => strategy.compress(&serialized_data[..]).await?,
             None => serialized_data,
-        })
+        };
+        Ok(self.seal_envelope(self.serialization_type, &body))
+    }
+
+    /// Encodes the provided data on the current thread, without going through
+    /// the async compression writers.
+    ///
+    /// This is the preferred API for CPU-bound code, such as rayon workers,
+    /// and produces exactly the same bytes as [`DataParser::encode`].
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - A reference to a data structure implementing the `DataParseable` trait.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing either a `Vec<u8>` of the enveloped, serialized (and optionally compressed) data,
+    /// or an `Error` if encoding fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fuel_data_parser::{DataParser, DEFAULT_COMPRESSION_STRATEGY};
+    ///
+    /// #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+    /// struct TestData {
+    ///     field: String,
+    /// }
+    ///
+    /// let parser = DataParser::default()
+    ///     .with_compression_strategy(&DEFAULT_COMPRESSION_STRATEGY);
+    /// let data = TestData { field: "test".to_string() };
+    /// let encoded = parser.encode_blocking(&data).unwrap();
+    /// let decoded: TestData = parser.decode_blocking(&encoded).unwrap();
+    /// assert_eq!(data, decoded);
+    /// ```
+    pub fn encode_blocking<T: DataParseable>(
+        &self,
+        data: &T,
+    ) -> Result<Vec<u8>, Error> {
+        let serialized_data =
+            Self::serialize_as(self.serialization_type, data)?;
+        let body = match &self.compression_strategy {
+            Some(strategy) => strategy.compress_blocking(&serialized_data)?,
+            None => serialized_data,
+        };
+        Ok(self.seal_envelope(self.serialization_type, &body))
+    }
+
+    /// Encodes the provided protobuf message and optionally compresses it,
+    /// prefixed with an envelope header marking it as protobuf regardless of
+    /// this parser's serialization type.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - A reference to a `prost` message.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing either a `Vec<u8>` of the enveloped, encoded (and optionally compressed) message,
+    /// or an `Error` if compression fails.
+    #[cfg(feature = "protobuf")]
+    pub async fn encode_protobuf<M: prost::Message>(
+        &self,
+        message: &M,
+    ) -> Result<Vec<u8>, Error> {
+        let serialized_data = message.encode_to_vec();
+        let body = match &self.compression_strategy {
+            Some(strategy) => strategy.compress(&serialized_data[..]).await?,
+            None => serialized_data,
+        };
+        Ok(self.seal_envelope(SerializationType::Protobuf, &body))
+    }
+
+    /// Prefixes an encoded body with an envelope header describing the given
+    /// serialization and the compression of this parser.
+    fn seal_envelope(
+        &self,
+        serialization_type: SerializationType,
+        body: &[u8],
+    ) -> Vec<u8> {
+        let header = EnvelopeHeader::new(
+            serialization_type,
+            self.compression_strategy.as_ref(),
+        )
+        .with_schema_version(self.schema_version);
+        let mut encoded = Vec::with_capacity(ENVELOPE_HEADER_LEN + body.len());
+        encoded.extend_from_slice(&header.to_bytes());
+        encoded.extend_from_slice(body);
+        encoded
     }
 
     /// Serializes the provided data according to the selected `SerializationType`.
@@ -210,18 +431,39 @@ impl DataParser {
         &self,
         raw_data: &T,
     ) -> Result<Vec<u8>, Error> {
-        match self.serialization_type {
+        Self::serialize_as(self.serialization_type, raw_data)
+    }
+
+    fn serialize_as<T: DataParseable>(
+        serialization_type: SerializationType,
+        raw_data: &T,
+    ) -> Result<Vec<u8>, Error> {
+        match serialization_type {
             SerializationType::Bincode => bincode::serialize(&raw_data)
                 .map_err(|e| Error::Serde(SerdeError::Bincode(*e))),
             SerializationType::Postcard => postcard::to_allocvec(&raw_data)
                 .map_err(|e| Error::Serde(SerdeError::Postcard(e))),
             SerializationType::Json => serde_json::to_vec(&raw_data)
                 .map_err(|e| Error::Serde(SerdeError::Json(e))),
+            SerializationType::MessagePack => {
+                rmp_serde::to_vec_named(&raw_data)
+                    .map_err(|e| Error::Serde(SerdeError::MessagePackEncode(e)))
+            }
+            SerializationType::Cbor => {
+                cbor4ii::serde::to_vec(Vec::new(), &raw_data)
+                    .map_err(|e| Error::Serde(SerdeError::CborEncode(e)))
+            }
+            SerializationType::Protobuf => Err(Error::ProtobufRequiresMessage),
         }
     }
 
     /// Decodes the provided data by deserializing and optionally decompressing it.
     ///
+    /// The serialization and compression are read from the envelope header, so
+    /// payloads produced by any `DataParser` configuration can be decoded.
+    /// Payloads without an envelope header, as produced by earlier versions,
+    /// are decoded using this parser's own configuration.
+    ///
     /// # Arguments
     ///
     /// * `data` - A byte slice (`&[u8]`) representing the serialized (and optionally compressed) data.
@@ -255,14 +497,190 @@ impl DataParser {
         &self,
         data: &[u8],
     ) -> Result<T, Error> {
-        let data = match &self.compression_strategy {
-            Some(strategy) => strategy.decompress(data).await?,
-            None => data.to_vec(),
+        let (serialization_type, compression_strategy, body) =
+            self.open_envelope(data)?;
+
+        // Only decompression needs an owned buffer; uncompressed bodies are
+        // deserialized straight from the input slice.
+        let data = match &compression_strategy {
+            Some(strategy) => Cow::Owned(strategy.decompress(body).await?),
+            None => Cow::Borrowed(body),
         };
-        let decoded_data = self.deserialize(&data[..]).await?;
+        let decoded_data = Self::deserialize_as(serialization_type, &data)?;
         Ok(decoded_data)
     }
 
+    /// Decodes the provided data on the current thread, without going through
+    /// the async decompression writers.
+    ///
+    /// Like [`DataParser::decode`], the serialization and compression are read
+    /// from the envelope header.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - A byte slice (`&[u8]`) representing the serialized (and optionally compressed) data.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing either the deserialized data structure,
+    /// or an `Error` if decoding fails.
+    pub fn decode_blocking<T: DataParseable>(
+        &self,
+        data: &[u8],
+    ) -> Result<T, Error> {
+        let (serialization_type, compression_strategy, body) =
+            self.open_envelope(data)?;
+
+        let data = match &compression_strategy {
+            Some(strategy) => Cow::Owned(strategy.decompress_blocking(body)?),
+            None => Cow::Borrowed(body),
+        };
+        Self::deserialize_as(serialization_type, &data)
+    }
+
+    /// Decodes the provided data into a type that may borrow from it, without
+    /// any intermediate allocation.
+    ///
+    /// This is only possible for uncompressed payloads: compressed payloads
+    /// have to be decompressed into an owned buffer first, so they are
+    /// rejected with `Error::BorrowCompressed` and should go through
+    /// [`DataParser::decode`] instead. `bytes::Bytes` and `Vec<u8>` payloads
+    /// can be passed by reference, as they dereference to `&[u8]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - A byte slice (`&[u8]`) representing the serialized data.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing either the deserialized data structure, borrowing
+    /// from `data` where the format allows it, or an `Error` if decoding fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fuel_data_parser::{DataParser, SerializationType};
+    ///
+    /// #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+    /// struct TestData {
+    ///     field: String,
+    /// }
+    ///
+    /// #[derive(serde::Deserialize, Debug)]
+    /// struct BorrowedTestData<'a> {
+    ///     field: &'a str,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let parser = DataParser::default()
+    ///         .with_serialization_type(SerializationType::Postcard);
+    ///     let original_data = TestData { field: "test".to_string() };
+    ///     let encoded = parser.encode(&original_data).await?;
+    ///     let decoded: BorrowedTestData = parser.decode_borrowed(&encoded)?;
+    ///     assert_eq!(original_data.field, decoded.field);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn decode_borrowed<'a, T: serde::Deserialize<'a>>(
+        &self,
+        data: &'a [u8],
+    ) -> Result<T, Error> {
+        let (serialization_type, compression_strategy, body) =
+            self.open_envelope(data)?;
+
+        if let Some(strategy) = compression_strategy {
+            return Err(Error::BorrowCompressed(strategy.name()));
+        }
+        Self::deserialize_as(serialization_type, body)
+    }
+
+    /// Decodes the provided data into a protobuf message, decompressing it if
+    /// needed.
+    ///
+    /// Payloads that are not protobuf encoded, according to their envelope
+    /// header or to this parser's configuration for payloads without one,
+    /// are rejected with `Error::NotProtobuf`.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - A byte slice (`&[u8]`) representing the encoded (and optionally compressed) message.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing either the decoded message,
+    /// or an `Error` if decoding fails.
+    #[cfg(feature = "protobuf")]
+    pub async fn decode_protobuf<M: prost::Message + Default>(
+        &self,
+        data: &[u8],
+    ) -> Result<M, Error> {
+        let (serialization_type, compression_strategy, body) =
+            self.open_envelope(data)?;
+        if serialization_type != SerializationType::Protobuf {
+            return Err(Error::NotProtobuf(serialization_type));
+        }
+
+        let data = match &compression_strategy {
+            Some(strategy) => Cow::Owned(strategy.decompress(body).await?),
+            None => Cow::Borrowed(body),
+        };
+        M::decode(&data[..]).map_err(|e| Error::Serde(SerdeError::Protobuf(e)))
+    }
+
+    /// Returns the serialization of an encoded payload, as read from its
+    /// envelope header, or this parser's own for payloads without one.
+    ///
+    /// This lets callers handling both serde and protobuf payloads pick the
+    /// matching decode method.
+    pub fn serialization_type_of(
+        &self,
+        data: &[u8],
+    ) -> Result<SerializationType, Error> {
+        Ok(match EnvelopeHeader::parse(data)? {
+            Some((header, _)) => header.serialization_type,
+            None => self.serialization_type,
+        })
+    }
+
+    /// Returns the schema version of an encoded payload, as read from its
+    /// envelope header, or `UNVERSIONED_SCHEMA` for payloads encoded without
+    /// one.
+    pub fn schema_version_of(&self, data: &[u8]) -> Result<u16, Error> {
+        Ok(match EnvelopeHeader::parse(data)? {
+            Some((header, _)) => header.schema_version,
+            None => UNVERSIONED_SCHEMA,
+        })
+    }
+
+    /// Reads the envelope header of an encoded payload, falling back to this
+    /// parser's configuration for payloads without one.
+    #[allow(clippy::type_complexity)]
+    fn open_envelope<'a>(
+        &self,
+        data: &'a [u8],
+    ) -> Result<
+        (
+            SerializationType,
+            Option<Arc<dyn CompressionStrategy>>,
+            &'a [u8],
+        ),
+        Error,
+    > {
+        Ok(match EnvelopeHeader::parse(data)? {
+            Some((header, body)) => (
+                header.serialization_type,
+                header.compression_strategy()?,
+                body,
+            ),
+            None => (
+                self.serialization_type,
+                self.compression_strategy.clone(),
+                data,
+            ),
+        })
+    }
+
     /// Deserializes the provided data according to the selected `SerializationType`.
     ///
     /// # Arguments
@@ -277,19 +695,33 @@ impl DataParser {
         &self,
         raw_data: &'a [u8],
     ) -> Result<T, Error> {
-        match self.serialization_type {
+        Self::deserialize_as(self.serialization_type, raw_data)
+    }
+
+    fn deserialize_as<'a, T: serde::Deserialize<'a>>(
+        serialization_type: SerializationType,
+        raw_data: &'a [u8],
+    ) -> Result<T, Error> {
+        match serialization_type {
             SerializationType::Bincode => bincode::deserialize(raw_data)
                 .map_err(|e| Error::Serde(SerdeError::Bincode(*e))),
             SerializationType::Postcard => postcard::from_bytes(raw_data)
                 .map_err(|e| Error::Serde(SerdeError::Postcard(e))),
             SerializationType::Json => serde_json::from_slice(raw_data)
                 .map_err(|e| Error::Serde(SerdeError::Json(e))),
+            SerializationType::MessagePack => rmp_serde::from_slice(raw_data)
+                .map_err(|e| Error::Serde(SerdeError::MessagePackDecode(e))),
+            SerializationType::Cbor => cbor4ii::serde::from_slice(raw_data)
+                .map_err(|e| Error::Serde(SerdeError::CborDecode(e))),
+            SerializationType::Protobuf => Err(Error::ProtobufRequiresMessage),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use strum::IntoEnumIterator;
+
     use super::*;
 
     #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
@@ -314,11 +746,9 @@ mod tests {
             field: "test".to_string(),
         };
 
-        for serialization_type in [
-            SerializationType::Bincode,
-            SerializationType::Postcard,
-            SerializationType::Json,
-        ] {
+        for serialization_type in
+            SerializationType::iter().filter(SerializationType::is_serde)
+        {
             let parser = DataParser::default()
                 .with_serialization_type(serialization_type);
             let encoded = parser.encode(&data).await.unwrap();
@@ -328,16 +758,519 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_compression_strategies() {
+    async fn test_protobuf_requires_message() {
+        let data = TestData {
+            field: "test".to_string(),
+        };
+        let parser = DataParser::default()
+            .with_serialization_type(SerializationType::Protobuf);
+        assert!(matches!(
+            parser.encode(&data).await,
+            Err(Error::ProtobufRequiresMessage)
+        ));
+    }
+
+    #[cfg(feature = "protobuf")]
+    #[tokio::test]
+    async fn test_encode_decode_protobuf() {
+        #[derive(Clone, PartialEq, prost::Message)]
+        struct TestMessage {
+            #[prost(string, tag = "1")]
+            field: String,
+        }
+
+        let message = TestMessage {
+            field: "test".to_string(),
+        };
+        let encoder = DataParser::default()
+            .with_compression_strategy(&DEFAULT_COMPRESSION_STRATEGY);
+        let encoded = encoder.encode_protobuf(&message).await.unwrap();
+
+        let parser = DataParser::default();
+        assert_eq!(
+            parser.serialization_type_of(&encoded).unwrap(),
+            SerializationType::Protobuf
+        );
+        let decoded: TestMessage =
+            parser.decode_protobuf(&encoded).await.unwrap();
+        assert_eq!(message, decoded);
+
+        let json = parser
+            .encode(&TestData {
+                field: "test".to_string(),
+            })
+            .await
+            .unwrap();
+        assert!(matches!(
+            parser.decode_protobuf::<TestMessage>(&json).await,
+            Err(Error::NotProtobuf(SerializationType::Json))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_decode_reads_envelope() {
+        let data = TestData {
+            field: "test".to_string(),
+        };
+        let strategy: Arc<dyn CompressionStrategy> =
+            Arc::new(ZLibCompressionStrategy::default());
+        let encoder = DataParser::default()
+            .with_serialization_type(SerializationType::Postcard)
+            .with_compression_strategy(&strategy);
+        let encoded = encoder.encode(&data).await.unwrap();
+
+        let (header, _) = EnvelopeHeader::parse(&encoded).unwrap().unwrap();
+        assert_eq!(header.serialization_type, SerializationType::Postcard);
+        assert_eq!(header.compression_id, strategy.id());
+
+        // A default (JSON, uncompressed) parser decodes it from the header alone
+        let decoded: TestData =
+            DataParser::default().decode(&encoded).await.unwrap();
+        assert_eq!(data, decoded);
+    }
+
+    #[tokio::test]
+    async fn test_decode_legacy_payload_without_envelope() {
         let data = TestData {
             field: "test".to_string(),
         };
+        let parser = DataParser::default()
+            .with_serialization_type(SerializationType::Bincode);
+        let legacy = parser.serialize(&data).await.unwrap();
+        let decoded: TestData = parser.decode(&legacy).await.unwrap();
+        assert_eq!(data, decoded);
+    }
+
+    #[tokio::test]
+    async fn test_decode_rejects_unknown_envelope() {
+        let parser = DataParser::default();
+        let unknown_version = [ENVELOPE_MAGIC, 99, 3, 0];
+        assert!(matches!(
+            parser.decode::<TestData>(&unknown_version).await,
+            Err(Error::Envelope(EnvelopeError::UnsupportedVersion(99)))
+        ));
+
+        let unknown_serialization =
+            [ENVELOPE_MAGIC, ENVELOPE_VERSION, 42, 0, 0, 1];
+        assert!(matches!(
+            parser.decode::<TestData>(&unknown_serialization).await,
+            Err(Error::Envelope(EnvelopeError::UnknownSerialization(42)))
+        ));
+
+        let json = SerializationType::Json.id();
+        // A version from the future, with a header of the current length
+        let future_version = [
+            ENVELOPE_MAGIC,
+            ENVELOPE_VERSION + 1,
+            json,
+            0,
+            0,
+            1,
+            b'{',
+            b'}',
+        ];
+        assert!(matches!(
+            parser.decode::<TestData>(&future_version).await,
+            Err(Error::Envelope(EnvelopeError::UnsupportedVersion(version)))
+                if version == ENVELOPE_VERSION + 1
+        ));
+        assert!(matches!(
+            parser.schema_version_of(&future_version),
+            Err(Error::Envelope(EnvelopeError::UnsupportedVersion(_)))
+        ));
+
+        let unknown_compression = [
+            ENVELOPE_MAGIC,
+            ENVELOPE_VERSION,
+            json,
+            200,
+            0,
+            1,
+            b'{',
+            b'}',
+        ];
+        assert!(matches!(
+            parser.decode::<TestData>(&unknown_compression).await,
+            Err(Error::Envelope(EnvelopeError::UnknownCompression(200)))
+        ));
+        assert!(matches!(
+            parser.decode_blocking::<TestData>(&unknown_compression),
+            Err(Error::Envelope(EnvelopeError::UnknownCompression(200)))
+        ));
+
+        let truncated = [ENVELOPE_MAGIC, ENVELOPE_VERSION];
+        assert!(matches!(
+            parser.decode::<TestData>(&truncated).await,
+            Err(Error::Envelope(EnvelopeError::Truncated {
+                expected: ENVELOPE_HEADER_LEN,
+                actual: 2
+            }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_schema_version() {
+        let data = TestData {
+            field: "test".to_string(),
+        };
+        let parser = DataParser::default()
+            .with_serialization_type(SerializationType::Bincode)
+            .with_schema_version(3);
+        let encoded = parser.encode(&data).await.unwrap();
+        assert_eq!(parser.schema_version_of(&encoded).unwrap(), 3);
+
+        let decoded: TestData = parser.decode(&encoded).await.unwrap();
+        assert_eq!(data, decoded);
+
+        // Version 1 headers are still decoded, without a schema version
+        let body = parser.serialize(&data).await.unwrap();
+        let v1 = [
+            &[ENVELOPE_MAGIC, 1, SerializationType::Bincode.id(), 0][..],
+            &body,
+        ]
+        .concat();
+        assert_eq!(parser.schema_version_of(&v1).unwrap(), UNVERSIONED_SCHEMA);
+        let decoded: TestData =
+            DataParser::default().decode(&v1).await.unwrap();
+        assert_eq!(data, decoded);
+    }
+
+    #[tokio::test]
+    async fn test_decode_borrowed() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct BorrowedData<'a> {
+            field: &'a str,
+        }
+
+        let data = TestData {
+            field: "test".to_string(),
+        };
+        for serialization_type in
+            SerializationType::iter().filter(SerializationType::is_serde)
+        {
+            let parser = DataParser::default()
+                .with_serialization_type(serialization_type);
+            let encoded = parser.encode(&data).await.unwrap();
+            let decoded: BorrowedData =
+                parser.decode_borrowed(&encoded).unwrap();
+            assert_eq!(decoded.field, data.field);
+        }
+
+        let compressed = DataParser::default()
+            .with_compression_strategy(&DEFAULT_COMPRESSION_STRATEGY)
+            .encode(&data)
+            .await
+            .unwrap();
+        assert!(matches!(
+            DataParser::default().decode_borrowed::<BorrowedData>(&compressed),
+            Err(Error::BorrowCompressed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_blocking_matches_async() {
+        let data = TestData {
+            field: "test".repeat(64),
+        };
         let compression_strategies: Vec<Arc<dyn CompressionStrategy>> = vec![
-            Arc::new(ZLibCompressionStrategy),
+            Arc::new(ZLibCompressionStrategy::default()),
+            #[cfg(feature = "bench-helpers")]
+            Arc::new(GzipCompressionStrategy::default()),
+            #[cfg(feature = "brotli")]
+            Arc::new(BrotliCompressionStrategy::default()),
+            #[cfg(feature = "bench-helpers")]
+            Arc::new(BzCompressionStrategy::default()),
+            #[cfg(feature = "bench-helpers")]
+            Arc::new(LzmaCompressionStrategy::default()),
             #[cfg(feature = "bench-helpers")]
-            Arc::new(GzipCompressionStrategy),
+            Arc::new(DeflateCompressionStrategy::default()),
+            #[cfg(feature = "zstd")]
+            Arc::new(ZstdCompressionStrategy::default()),
+            #[cfg(feature = "lz4")]
+            Arc::new(Lz4CompressionStrategy::default()),
+        ];
+
+        for strategy in compression_strategies {
+            let parser =
+                DataParser::default().with_compression_strategy(&strategy);
+
+            let encoded_async = parser.encode(&data).await.unwrap();
+            let decoded: TestData =
+                parser.decode_blocking(&encoded_async).unwrap();
+            assert_eq!(data, decoded, "{}", strategy.name());
+
+            let encoded_blocking = parser.encode_blocking(&data).unwrap();
+            let decoded: TestData =
+                parser.decode(&encoded_blocking).await.unwrap();
+            assert_eq!(data, decoded, "{}", strategy.name());
+        }
+    }
+
+    #[cfg(feature = "thread-pool")]
+    #[tokio::test]
+    async fn test_thread_pool_offloads_large_payloads() {
+        let pool = ParserThreadPool::new(1).unwrap().with_offload_threshold(0);
+        let parser = DataParser::default()
+            .with_compression_strategy(&DEFAULT_COMPRESSION_STRATEGY);
+        let data = TestData {
+            field: "test".repeat(1024),
+        };
+
+        let encoded = pool.encode(&parser, data.clone()).await.unwrap();
+        let decoded: TestData = pool.decode(&parser, encoded).await.unwrap();
+        assert_eq!(data, decoded);
+    }
+
+    #[tokio::test]
+    async fn test_compression_levels() {
+        let data = TestData {
+            field: "test".repeat(64),
+        };
+        for level in ["fastest", "best", "default", "3"] {
+            let level = parse_compression_level(level).unwrap();
+            let strategy = CompressionRegistry::global()
+                .get_with_level("zlib", level)
+                .unwrap();
+            let parser =
+                DataParser::default().with_compression_strategy(&strategy);
+            let encoded = parser.encode(&data).await.unwrap();
+            let decoded: TestData =
+                DataParser::default().decode(&encoded).await.unwrap();
+            assert_eq!(data, decoded);
+        }
+    }
+
+    #[test]
+    fn test_registry_builds_strategies_once() {
+        let registry = CompressionRegistry::global();
+        let fastest = registry.get("zlib").unwrap();
+        assert!(Arc::ptr_eq(&fastest, &registry.get("zlib").unwrap()));
+        assert!(Arc::ptr_eq(
+            &fastest,
+            &registry.get_by_id(ZLibCompressionStrategy::ID).unwrap()
+        ));
+
+        let best = registry
+            .get_with_level("zlib", CompressionLevel::Best)
+            .unwrap();
+        assert!(!Arc::ptr_eq(&fastest, &best));
+        assert!(Arc::ptr_eq(
+            &best,
+            &registry
+                .get_with_level("zlib", CompressionLevel::Best)
+                .unwrap()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_registry_custom_strategy() {
+        #[derive(Debug)]
+        struct ReverseCompressionStrategy;
+
+        #[async_trait::async_trait]
+        impl CompressionStrategy for ReverseCompressionStrategy {
+            fn name(&self) -> &'static str {
+                "reverse"
+            }
+
+            fn id(&self) -> u8 {
+                200
+            }
+
+            async fn compress(
+                &self,
+                uncompressed: &[u8],
+            ) -> Result<Vec<u8>, CompressionError> {
+                self.compress_blocking(uncompressed)
+            }
+
+            async fn decompress(
+                &self,
+                compressed: &[u8],
+            ) -> Result<Vec<u8>, CompressionError> {
+                self.decompress_blocking(compressed)
+            }
+
+            fn compress_blocking(
+                &self,
+                uncompressed: &[u8],
+            ) -> Result<Vec<u8>, CompressionError> {
+                Ok(uncompressed.iter().rev().copied().collect())
+            }
+
+            fn decompress_blocking(
+                &self,
+                compressed: &[u8],
+            ) -> Result<Vec<u8>, CompressionError> {
+                Ok(compressed.iter().rev().copied().collect())
+            }
+        }
+
+        let registry = CompressionRegistry::global();
+        registry
+            .register("reverse", 200, |_| Arc::new(ReverseCompressionStrategy))
+            .unwrap();
+        assert!(matches!(
+            registry.register("reverse", 201, |_| Arc::new(
+                ReverseCompressionStrategy
+            )),
+            Err(CompressionRegistryError::NameTaken(_))
+        ));
+        assert!(matches!(
+            registry.register(
+                "zlib-again",
+                ZLibCompressionStrategy::ID,
+                |_| { Arc::new(ReverseCompressionStrategy) }
+            ),
+            Err(CompressionRegistryError::IdTaken(_))
+        ));
+        assert!(registry.names().contains(&"zlib".to_string()));
+
+        let strategy = registry.get("reverse").unwrap();
+        let data = TestData {
+            field: "test".to_string(),
+        };
+        let encoded = DataParser::default()
+            .with_compression_strategy(&strategy)
+            .encode(&data)
+            .await
+            .unwrap();
+        let decoded: TestData =
+            DataParser::default().decode(&encoded).await.unwrap();
+        assert_eq!(data, decoded);
+    }
+
+    #[cfg(feature = "zstd")]
+    fn zstd_dictionary_samples() -> Vec<Vec<u8>> {
+        (0..2000u64)
+            .map(|i| {
+                let data = TestData {
+                    field: format!("0x{:064x}", i.wrapping_mul(0x9e37_79b9)),
+                };
+                serde_json::to_vec(&data).unwrap()
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "zstd")]
+    #[tokio::test]
+    async fn test_zstd_dictionary_compression() {
+        let dictionary = ZstdDictionary::train(
+            &zstd_dictionary_samples(),
+            DEFAULT_ZSTD_DICTIONARY_SIZE,
+        )
+        .unwrap();
+        let strategy: Arc<dyn CompressionStrategy> =
+            Arc::new(ZstdDictionaryCompressionStrategy::with_dictionary(
+                &dictionary,
+                CompressionLevel::Default,
+            ));
+        let plain_strategy: Arc<dyn CompressionStrategy> =
+            Arc::new(ZstdCompressionStrategy::new(CompressionLevel::Default));
+
+        let data = TestData {
+            field: format!("0x{:064x}", 123_456_789u64),
+        };
+        let encoded = DataParser::default()
+            .with_compression_strategy(&strategy)
+            .encode(&data)
+            .await
+            .unwrap();
+        let encoded_plain = DataParser::default()
+            .with_compression_strategy(&plain_strategy)
+            .encode(&data)
+            .await
+            .unwrap();
+        assert!(encoded.len() < encoded_plain.len());
+
+        let decoded: TestData =
+            DataParser::default().decode(&encoded).await.unwrap();
+        assert_eq!(data, decoded);
+        let decoded: TestData =
+            DataParser::default().decode_blocking(&encoded).unwrap();
+        assert_eq!(data, decoded);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[tokio::test]
+    async fn test_zstd_dictionary_must_be_loaded_to_decode() {
+        let samples = zstd_dictionary_samples();
+        let dictionary =
+            ZstdDictionary::train(&samples[..1000], 16 * 1024).unwrap();
+        let strategy =
+            ZstdDictionaryCompressionStrategy::new(CompressionLevel::Fastest);
+        assert!(matches!(
+            strategy.compress_blocking(b"data"),
+            Err(CompressionError::NoZstdDictionary)
+        ));
+
+        // Compress with a dictionary that is not in the global store
+        let bytes = dictionary.as_bytes().to_vec();
+        let encoder = zstd::dict::EncoderDictionary::copy(&bytes, 3);
+        let compressed =
+            blocking_codecs::zstd_dict_compress(&samples[0], &encoder).unwrap();
+        let unknown = ZstdDictionary::from_bytes(bytes).unwrap();
+        assert!(matches!(
+            strategy.decompress_blocking(&compressed),
+            Err(CompressionError::UnknownZstdDictionary(id)) if id == unknown.id()
+        ));
+
+        ZstdDictionaryStore::global().insert(unknown);
+        assert_eq!(
+            strategy.decompress_blocking(&compressed).unwrap(),
+            samples[0]
+        );
+
+        // The registry builds the strategy once, until another dictionary
+        // is loaded
+        let dictionary_id = |compressed: &[u8]| {
+            zstd::zstd_safe::get_dict_id_from_frame(compressed)
+                .unwrap()
+                .get()
+        };
+        let registry = CompressionRegistry::global();
+        ZstdDictionaryStore::global().load(dictionary.clone());
+        let strategy = registry
+            .get(ZstdDictionaryCompressionStrategy::NAME)
+            .unwrap();
+        assert!(Arc::ptr_eq(
+            &strategy,
+            &registry
+                .get_by_id(ZstdDictionaryCompressionStrategy::ID)
+                .unwrap()
+        ));
+        let compressed = strategy.compress_blocking(&samples[0]).unwrap();
+        assert_eq!(dictionary_id(&compressed), dictionary.id());
+
+        let other = ZstdDictionary::train(&samples[1000..], 16 * 1024).unwrap();
+        assert_ne!(other.id(), dictionary.id());
+        ZstdDictionaryStore::global().load(other.clone());
+        let strategy = registry
+            .get(ZstdDictionaryCompressionStrategy::NAME)
+            .unwrap();
+        let compressed = strategy.compress_blocking(&samples[0]).unwrap();
+        assert_eq!(dictionary_id(&compressed), other.id());
+        assert_eq!(
+            registry
+                .get_by_id(ZstdDictionaryCompressionStrategy::ID)
+                .unwrap()
+                .decompress_blocking(&compressed)
+                .unwrap(),
+            samples[0]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compression_strategies() {
+        let data = TestData {
+            field: "test".to_string(),
+        };
+        let compression_strategies: Vec<Arc<dyn CompressionStrategy>> = vec![
+            Arc::new(ZLibCompressionStrategy::default()),
             #[cfg(feature = "bench-helpers")]
-            Arc::new(BrotliCompressionStrategy),
+            Arc::new(GzipCompressionStrategy::default()),
+            #[cfg(feature = "brotli")]
+            Arc::new(BrotliCompressionStrategy::default()),
         ];
 
         for strategy in compression_strategies {
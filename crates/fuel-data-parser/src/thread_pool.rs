@@ -0,0 +1,101 @@
+use crate::{DataParseable, DataParser, Error};
+
+/// Payloads smaller than this many bytes are decoded inline by default.
+pub const DEFAULT_OFFLOAD_THRESHOLD: usize = 64 * 1024;
+
+/// A dedicated thread pool running the blocking `DataParser` API, so large
+/// payloads can be encoded and decoded without stalling the async runtime.
+///
+/// Encoding always runs on the pool. Decoding only runs on the pool for
+/// payloads of at least `offload_threshold` bytes, as moving small payloads
+/// between threads costs more than decoding them inline.
+///
+/// # Examples
+///
+/// ```
+/// use fuel_data_parser::{DataParser, ParserThreadPool};
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+/// struct TestData {
+///     field: String,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let pool = ParserThreadPool::new(2)?;
+///     let parser = DataParser::default();
+///
+///     let data = TestData { field: "test".to_string() };
+///     let encoded = pool.encode(&parser, data.clone()).await?;
+///     let decoded: TestData = pool.decode(&parser, encoded).await?;
+///     assert_eq!(data, decoded);
+///     Ok(())
+/// }
+/// ```
+pub struct ParserThreadPool {
+    pool: rayon::ThreadPool,
+    offload_threshold: usize,
+}
+
+impl ParserThreadPool {
+    pub fn new(
+        num_threads: usize,
+    ) -> Result<Self, rayon::ThreadPoolBuildError> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .thread_name(|index| format!("data-parser-{index}"))
+            .build()?;
+
+        Ok(Self {
+            pool,
+            offload_threshold: DEFAULT_OFFLOAD_THRESHOLD,
+        })
+    }
+
+    /// Sets the payload size, in bytes, from which decoding is moved onto
+    /// the pool.
+    pub fn with_offload_threshold(self, offload_threshold: usize) -> Self {
+        Self {
+            offload_threshold,
+            ..self
+        }
+    }
+
+    /// Encodes the provided data on the pool, see [`DataParser::encode_blocking`].
+    pub async fn encode<T: DataParseable + 'static>(
+        &self,
+        parser: &DataParser,
+        data: T,
+    ) -> Result<Vec<u8>, Error> {
+        let parser = parser.clone();
+        self.run(move || parser.encode_blocking(&data)).await
+    }
+
+    /// Decodes the provided data, on the pool if it is larger than the
+    /// offload threshold, see [`DataParser::decode_blocking`].
+    pub async fn decode<T: DataParseable + 'static>(
+        &self,
+        parser: &DataParser,
+        data: impl AsRef<[u8]> + Send + 'static,
+    ) -> Result<T, Error> {
+        if data.as_ref().len() < self.offload_threshold {
+            return parser.decode_blocking(data.as_ref());
+        }
+
+        let parser = parser.clone();
+        self.run(move || parser.decode_blocking(data.as_ref()))
+            .await
+    }
+
+    async fn run<R: Send + 'static>(
+        &self,
+        task: impl FnOnce() -> Result<R, Error> + Send + 'static,
+    ) -> Result<R, Error> {
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        self.pool.spawn(move || {
+            // The receiver may have been dropped if the caller gave up
+            let _ = sender.send(task());
+        });
+        receiver.await.map_err(|_| Error::ThreadPoolTaskDropped)?
+    }
+}
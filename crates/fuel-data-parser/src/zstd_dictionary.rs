@@ -0,0 +1,288 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+use zstd::dict::{DecoderDictionary, EncoderDictionary};
+
+use crate::{
+    blocking_codecs,
+    CompressionError,
+    CompressionLevel,
+    CompressionRegistry,
+    CompressionStrategy,
+};
+
+/// Default maximum size of a trained dictionary, in bytes.
+pub const DEFAULT_ZSTD_DICTIONARY_SIZE: usize = 112 * 1024;
+
+/// A trained zstd dictionary.
+///
+/// Every trained dictionary carries an id in its header, which zstd writes
+/// into the frame of each payload compressed with it. This is how a consumer
+/// finds the dictionary it needs to decompress a payload, so the bytes of a
+/// dictionary are the only thing that has to be shared between publisher
+/// and consumers.
+#[derive(Clone)]
+pub struct ZstdDictionary {
+    id: u32,
+    bytes: Arc<[u8]>,
+    decoder: Arc<DecoderDictionary<'static>>,
+}
+
+impl ZstdDictionary {
+    /// Trains a dictionary from samples of uncompressed payloads, e.g.
+    /// serialized blocks, transactions and receipts.
+    ///
+    /// zstd needs a fair amount of samples to train on, a few thousands
+    /// being a good start.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fuel_data_parser::{ZstdDictionary, DEFAULT_ZSTD_DICTIONARY_SIZE};
+    ///
+    /// let samples = (0..2000)
+    ///     .map(|i| format!(r#"{{"height":{i},"id":"0x{:064x}"}}"#, i * 7919))
+    ///     .collect::<Vec<_>>();
+    /// let dictionary =
+    ///     ZstdDictionary::train(&samples, DEFAULT_ZSTD_DICTIONARY_SIZE)
+    ///         .expect("Training must succeed");
+    /// assert_ne!(dictionary.id(), 0);
+    /// ```
+    pub fn train<S: AsRef<[u8]>>(
+        samples: &[S],
+        max_size: usize,
+    ) -> Result<Self, CompressionError> {
+        let bytes = zstd::dict::from_samples(samples, max_size)
+            .map_err(CompressionError::Zstd)?;
+        Self::from_bytes(bytes)
+    }
+
+    /// Loads a dictionary previously trained with [`ZstdDictionary::train`]
+    /// or `zstd --train`.
+    pub fn from_bytes(
+        bytes: impl Into<Vec<u8>>,
+    ) -> Result<Self, CompressionError> {
+        let bytes: Arc<[u8]> = bytes.into().into();
+        let id = zstd::zstd_safe::get_dict_id(&bytes)
+            .ok_or(CompressionError::ZstdDictionaryWithoutId)?
+            .get();
+
+        Ok(Self {
+            id,
+            decoder: Arc::new(DecoderDictionary::copy(&bytes)),
+            bytes,
+        })
+    }
+
+    /// Returns the id of the dictionary, as written into compressed payloads.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Returns the raw dictionary, e.g. to save it to a file.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl fmt::Debug for ZstdDictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZstdDictionary")
+            .field("id", &self.id)
+            .field("size", &self.bytes.len())
+            .finish()
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref GLOBAL_ZSTD_DICTIONARY_STORE: ZstdDictionaryStore =
+        ZstdDictionaryStore::default();
+}
+
+/// The dictionaries available to [`ZstdDictionaryCompressionStrategy`].
+///
+/// Consumers must load every dictionary the payloads they decode were
+/// compressed with. Publishers load the dictionary to compress with; the
+/// most recently loaded dictionary is the one picked up by strategies built
+/// from the [`crate::CompressionRegistry`].
+#[derive(Debug, Default)]
+pub struct ZstdDictionaryStore {
+    dictionaries: RwLock<HashMap<u32, ZstdDictionary>>,
+    current: RwLock<Option<ZstdDictionary>>,
+}
+
+impl ZstdDictionaryStore {
+    /// Returns the process-wide store, used to decode payloads.
+    pub fn global() -> &'static ZstdDictionaryStore {
+        &GLOBAL_ZSTD_DICTIONARY_STORE
+    }
+
+    /// Makes a dictionary available for decompression.
+    pub fn insert(&self, dictionary: ZstdDictionary) {
+        self.dictionaries
+            .write()
+            .expect("Dictionary store lock poisoned")
+            .insert(dictionary.id(), dictionary);
+    }
+
+    /// Makes a dictionary available for decompression, and the current
+    /// dictionary for compression.
+    pub fn load(&self, dictionary: ZstdDictionary) {
+        self.insert(dictionary.clone());
+        *self
+            .current
+            .write()
+            .expect("Dictionary store lock poisoned") = Some(dictionary);
+
+        // The global registry keeps the strategies it built, which compress
+        // with the dictionary that was current back then
+        if std::ptr::eq(self, Self::global()) {
+            CompressionRegistry::global()
+                .evict(ZstdDictionaryCompressionStrategy::ID);
+        }
+    }
+
+    /// Looks up a dictionary by id.
+    pub fn get(&self, id: u32) -> Option<ZstdDictionary> {
+        self.dictionaries
+            .read()
+            .expect("Dictionary store lock poisoned")
+            .get(&id)
+            .cloned()
+    }
+
+    /// Returns the most recently loaded dictionary.
+    pub fn current(&self) -> Option<ZstdDictionary> {
+        self.current
+            .read()
+            .expect("Dictionary store lock poisoned")
+            .clone()
+    }
+}
+
+/// Compression strategy using zstd with a trained dictionary.
+///
+/// Small and repetitive payloads, which is what Fuel payloads are, compress
+/// much better against a dictionary than on their own. Decompression reads
+/// the dictionary id from the payload and looks the dictionary up in the
+/// global [`ZstdDictionaryStore`].
+#[derive(Clone)]
+pub struct ZstdDictionaryCompressionStrategy {
+    level: CompressionLevel,
+    encoder: Option<(u32, Arc<EncoderDictionary<'static>>)>,
+}
+
+impl ZstdDictionaryCompressionStrategy {
+    pub const NAME: &'static str = "zstd-dict";
+    pub const ID: u8 = 9;
+
+    /// Creates a strategy compressing with the current dictionary of the
+    /// global [`ZstdDictionaryStore`], if any.
+    pub fn new(level: CompressionLevel) -> Self {
+        // The current dictionary is already in the store
+        match ZstdDictionaryStore::global().current() {
+            Some(dictionary) => Self::prepare(&dictionary, level),
+            None => Self {
+                level,
+                encoder: None,
+            },
+        }
+    }
+
+    /// Creates a strategy compressing with the given dictionary.
+    ///
+    /// The dictionary is also inserted into the global [`ZstdDictionaryStore`]
+    /// so payloads compressed with it can be decoded in this process.
+    pub fn with_dictionary(
+        dictionary: &ZstdDictionary,
+        level: CompressionLevel,
+    ) -> Self {
+        ZstdDictionaryStore::global().insert(dictionary.clone());
+        Self::prepare(dictionary, level)
+    }
+
+    /// Prepares the dictionary for compression at the given level, which
+    /// copies and digests it, so this is done once per strategy.
+    fn prepare(dictionary: &ZstdDictionary, level: CompressionLevel) -> Self {
+        let encoder = EncoderDictionary::copy(
+            dictionary.as_bytes(),
+            blocking_codecs::zstd_level(level),
+        );
+        Self {
+            level,
+            encoder: Some((dictionary.id(), Arc::new(encoder))),
+        }
+    }
+
+    pub fn level(&self) -> CompressionLevel {
+        self.level
+    }
+
+    /// Returns the id of the dictionary used for compression.
+    pub fn dictionary_id(&self) -> Option<u32> {
+        self.encoder.as_ref().map(|(id, _)| *id)
+    }
+}
+
+impl fmt::Debug for ZstdDictionaryCompressionStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZstdDictionaryCompressionStrategy")
+            .field("level", &self.level)
+            .field("dictionary_id", &self.dictionary_id())
+            .finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl CompressionStrategy for ZstdDictionaryCompressionStrategy {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn id(&self) -> u8 {
+        Self::ID
+    }
+
+    async fn compress(
+        &self,
+        uncompressed: &[u8],
+    ) -> Result<Vec<u8>, CompressionError> {
+        self.compress_blocking(uncompressed)
+    }
+
+    async fn decompress(
+        &self,
+        compressed: &[u8],
+    ) -> Result<Vec<u8>, CompressionError> {
+        self.decompress_blocking(compressed)
+    }
+
+    fn compress_blocking(
+        &self,
+        uncompressed: &[u8],
+    ) -> Result<Vec<u8>, CompressionError> {
+        let (_, encoder) = self
+            .encoder
+            .as_ref()
+            .ok_or(CompressionError::NoZstdDictionary)?;
+        blocking_codecs::zstd_dict_compress(uncompressed, encoder)
+            .map_err(CompressionError::Zstd)
+    }
+
+    fn decompress_blocking(
+        &self,
+        compressed: &[u8],
+    ) -> Result<Vec<u8>, CompressionError> {
+        let id = zstd::zstd_safe::get_dict_id_from_frame(compressed)
+            .ok_or(CompressionError::MissingZstdDictionaryId)?
+            .get();
+        let dictionary = ZstdDictionaryStore::global()
+            .get(id)
+            .ok_or(CompressionError::UnknownZstdDictionary(id))?;
+        blocking_codecs::zstd_dict_decompress(compressed, &dictionary.decoder)
+            .map_err(CompressionError::Zstd)
+    }
+}
@@ -72,7 +72,13 @@ async fn sync_blocks(
 
     while let Some(msg) = subscription.next().await {
         let msg = msg?;
-        let block = Block::decode(msg.payload.clone().into()).await;
+        let block = match Block::decode(&msg.payload).await {
+            Ok(block) => block,
+            Err(error) => {
+                eprintln!("Skipping block: {error}");
+                continue;
+            }
+        };
         let height = block.height;
         let id = height.to_string();
         let key = ("block".to_string(), id.clone());
@@ -105,7 +111,13 @@ async fn sync_transactions(
 
     while let Some(msg) = subscription.next().await {
         let msg = msg?;
-        let transaction = Transaction::decode(msg.payload.clone().into()).await;
+        let transaction = match Transaction::decode(&msg.payload).await {
+            Ok(transaction) => transaction,
+            Err(error) => {
+                eprintln!("Skipping transaction: {error}");
+                continue;
+            }
+        };
         let tx_id = &transaction.id;
         let id = format!("0x{}", tx_id);
         let key = ("transaction".to_string(), id.clone());
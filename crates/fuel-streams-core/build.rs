@@ -0,0 +1,11 @@
+//! Generates the protobuf messages of `proto/fuel_streams.proto`.
+//!
+//! The schema is parsed with `protox`, so building does not require `protoc`.
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=proto");
+
+    let file_descriptors = protox::compile(["fuel_streams.proto"], ["proto"])?;
+    prost_build::Config::new().compile_fds(file_descriptors)?;
+    Ok(())
+}
@@ -1,15 +1,24 @@
 pub mod subjects;
 pub mod types;
 
+use fuel_streams_macros::subject::{IntoSubject, SubjectBuildable};
 pub use subjects::*;
 
 use super::types::*;
-use crate::{StreamEncoder, Streamable};
+use crate::{protobuf::impl_protobuf_stream_encoder, Streamable};
 
-impl StreamEncoder for Block {}
+impl_protobuf_stream_encoder!(Block);
 impl Streamable for Block {
     const NAME: &'static str = "blocks";
     const WILDCARD_LIST: &'static [&'static str] = &[BlocksSubject::WILDCARD];
+
+    fn block_height_subject(height: u32) -> Option<String> {
+        Some(
+            BlocksSubject::new()
+                .with_height(Some(height.into()))
+                .parse(),
+        )
+    }
 }
 
 #[cfg(test)]
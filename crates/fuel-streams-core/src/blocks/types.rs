@@ -1,7 +1,7 @@
 use crate::types::*;
 
 // Block type
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Block {
     pub consensus: Consensus,
@@ -38,7 +38,9 @@ impl Block {
     }
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema,
+)]
 pub struct BlockHeight(String);
 
 impl From<FuelCoreBlockHeight> for BlockHeight {
@@ -61,14 +63,16 @@ impl std::fmt::Display for BlockHeight {
 }
 
 // Consensus enum
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum Consensus {
     Genesis(Genesis),
     PoAConsensus(PoAConsensus),
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct Genesis {
     pub chain_config_hash: Bytes32,
@@ -90,7 +94,7 @@ impl From<FuelCoreGenesis> for Genesis {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct PoAConsensus {
     pub signature: Signature,
 }
@@ -128,14 +132,14 @@ impl From<FuelCoreConsensus> for Consensus {
 }
 
 // BlockVersion enum
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum BlockVersion {
     V1,
 }
 
 // Header type
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BlockHeader {
     pub application_hash: Bytes32,
@@ -148,6 +152,7 @@ pub struct BlockHeader {
     pub message_receipt_count: u32,
     pub prev_root: Bytes32,
     pub state_transition_bytecode_version: u32,
+    #[schemars(with = "String")]
     pub time: FuelCoreTai64Timestamp,
     pub transactions_count: u16,
     pub transactions_root: Bytes32,
@@ -181,7 +186,7 @@ impl From<&FuelCoreBlockHeader> for BlockHeader {
 }
 
 // BlockHeaderVersion enum
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum BlockHeaderVersion {
     V1,
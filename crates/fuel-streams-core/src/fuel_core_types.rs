@@ -17,7 +17,10 @@ pub use fuel_core_types::{
         field::{Inputs as FuelCoreInputs, Outputs as FuelCoreOutputs},
         input::contract::Contract as FuelCoreInputContract,
         output::contract::Contract as FuelCoreOutputContract,
-        policies::Policies as FuelCorePolicies,
+        policies::{
+            Policies as FuelCorePolicies,
+            PolicyType as FuelCorePolicyType,
+        },
         Address as FuelCoreAddress,
         AssetId as FuelCoreAssetId,
         BlobId as FuelCoreBlobId,
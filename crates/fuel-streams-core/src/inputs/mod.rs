@@ -4,12 +4,13 @@ pub mod types;
 pub use subjects::*;
 
 use super::types::*;
-use crate::{StreamEncoder, Streamable};
+use crate::{protobuf::impl_protobuf_stream_encoder, Streamable};
 
-impl StreamEncoder for Input {}
+impl_protobuf_stream_encoder!(Input);
 impl Streamable for Input {
     const NAME: &'static str = "inputs";
     const WILDCARD_LIST: &'static [&'static str] = &[
+        InputsByIdSubject::WILDCARD,
         InputsCoinSubject::WILDCARD,
         InputsContractSubject::WILDCARD,
         InputsMessageSubject::WILDCARD,
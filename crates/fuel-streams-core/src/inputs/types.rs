@@ -1,7 +1,7 @@
 use crate::types::*;
 
 // Input enum
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum Input {
     Contract(InputContract),
@@ -118,7 +118,9 @@ impl Default for Input {
 }
 
 // InputCoin type
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct InputCoin {
     pub amount: u64,
@@ -133,7 +135,9 @@ pub struct InputCoin {
 }
 
 // InputContract type
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct InputContract {
     pub balance_root: Bytes32,
@@ -156,7 +160,9 @@ impl From<&FuelCoreInputContract> for InputContract {
 }
 
 // InputMessage type
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct InputMessage {
     pub amount: u64,
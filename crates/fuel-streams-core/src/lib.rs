@@ -9,6 +9,8 @@ pub mod transactions;
 pub mod utxos;
 
 pub mod nats;
+pub mod protobuf;
+pub mod schema;
 pub mod stream;
 
 pub mod subjects;
@@ -1,13 +1,22 @@
 pub mod subjects;
 pub mod types;
 
+use fuel_streams_macros::subject::{IntoSubject, SubjectBuildable};
 pub use subjects::*;
 
 use super::types::*;
-use crate::{StreamEncoder, Streamable};
+use crate::{protobuf::impl_protobuf_stream_encoder, Streamable};
 
-impl StreamEncoder for Log {}
+impl_protobuf_stream_encoder!(Log);
 impl Streamable for Log {
     const NAME: &'static str = "logs";
     const WILDCARD_LIST: &'static [&'static str] = &[LogsSubject::WILDCARD];
+
+    fn block_height_subject(height: u32) -> Option<String> {
+        Some(
+            LogsSubject::new()
+                .with_block_height(Some(height.into()))
+                .parse(),
+        )
+    }
 }
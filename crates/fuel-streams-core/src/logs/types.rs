@@ -2,7 +2,9 @@ use crate::types::*;
 
 /// A convenient aggregate type to represent a Fuel logs to allow users
 /// think about them agnostic of receipts.
-#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(tag = "type")]
 pub enum Log {
     WithoutData {
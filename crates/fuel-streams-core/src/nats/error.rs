@@ -26,6 +26,9 @@ pub enum NatsError {
         max_payload_size: PayloadSize,
     },
 
+    /// Client is in memory, and has no connection to a NATS server
+    InMemory,
+
     /// Failed to connect to NATS server at {url}
     ConnectionError {
         url: String,
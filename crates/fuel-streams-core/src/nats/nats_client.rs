@@ -1,7 +1,4 @@
-use async_nats::{
-    error,
-    jetstream::{context::CreateKeyValueErrorKind, kv},
-};
+use async_nats::jetstream::kv;
 use tracing::info;
 
 use super::{types::*, NatsClientOpts, NatsError, NatsNamespace};
@@ -43,10 +40,10 @@ use super::{types::*, NatsClientOpts, NatsError, NatsNamespace};
 /// ```
 #[derive(Debug, Clone)]
 pub struct NatsClient {
-    /// The underlying NATS client
-    pub nats_client: async_nats::Client,
-    /// The JetStream context for this client
-    pub jetstream: JetStreamContext,
+    /// The underlying NATS client, unless the client is in memory
+    nats_client: Option<async_nats::Client>,
+    /// The JetStream context for this client, unless the client is in memory
+    jetstream: Option<JetStreamContext>,
     /// The namespace used for this client
     pub namespace: NatsNamespace,
     /// The options used to create this client
@@ -55,8 +52,18 @@ pub struct NatsClient {
 
 impl NatsClient {
     pub async fn connect(opts: &NatsClientOpts) -> Result<Self, NatsError> {
-        let url = &opts.url;
         let namespace = opts.namespace.clone();
+        #[cfg(feature = "in-memory")]
+        if opts.in_memory {
+            return Ok(Self {
+                nats_client: None,
+                jetstream: None,
+                opts: opts.to_owned(),
+                namespace,
+            });
+        }
+
+        let url = &opts.url;
         let nats_client =
             opts.connect_opts().connect(&url).await.map_err(|e| {
                 NatsError::ConnectionError {
@@ -68,32 +75,51 @@ impl NatsClient {
         info!("Connected to NATS server at {}", url);
 
         Ok(Self {
-            nats_client,
-            jetstream,
+            nats_client: Some(nats_client),
+            jetstream: Some(jetstream),
             opts: opts.to_owned(),
             namespace,
         })
     }
 
+    /// The underlying NATS client, which in-memory clients don't have.
+    pub fn nats_client(&self) -> Result<&async_nats::Client, NatsError> {
+        self.nats_client.as_ref().ok_or(NatsError::InMemory)
+    }
+
+    /// The JetStream context for this client, which in-memory clients
+    /// don't have.
+    pub fn jetstream(&self) -> Result<&JetStreamContext, NatsError> {
+        self.jetstream.as_ref().ok_or(NatsError::InMemory)
+    }
+
+    /// Whether streams of this client are kept in the memory of the process
+    /// rather than in NATS, see `NatsClientOpts::in_memory`.
+    pub fn is_in_memory(&self) -> bool {
+        self.nats_client.is_none()
+    }
+
     pub async fn get_or_create_kv_store(
         &self,
         options: kv::Config,
-    ) -> Result<kv::Store, error::Error<CreateKeyValueErrorKind>> {
+    ) -> Result<kv::Store, NatsError> {
+        let jetstream = self.jetstream()?;
         let bucket = options.bucket.clone();
-        let store = self.jetstream.get_key_value(&bucket).await;
+        let store = jetstream.get_key_value(&bucket).await;
         let store = match store {
             Ok(store) => store,
-            Err(_) => self.jetstream.create_key_value(options).await?,
+            Err(_) => jetstream.create_key_value(options).await?,
         };
 
         Ok(store)
     }
 
     pub fn is_connected(&self) -> bool {
-        self.state() == ConnectionState::Connected
-    }
-
-    fn state(&self) -> ConnectionState {
-        self.nats_client.connection_state()
+        match &self.nats_client {
+            Some(nats_client) => {
+                nats_client.connection_state() == ConnectionState::Connected
+            }
+            None => true,
+        }
     }
 }
@@ -1,8 +1,9 @@
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use async_nats::ConnectOptions;
 
 use super::NatsNamespace;
+use crate::stream::{RetentionConfig, DEFAULT_PUBLISH_WINDOW};
 
 #[derive(Debug, Clone, Default)]
 pub enum NatsUserRole {
@@ -50,6 +51,15 @@ pub struct NatsClientOpts {
     pub(crate) namespace: NatsNamespace,
     /// The timeout in seconds for NATS operations.
     pub(crate) timeout_secs: u64,
+    /// The retention of streams created by the client, if set.
+    pub(crate) retention: Option<RetentionConfig>,
+    /// The retention of specific streams, by `Streamable::NAME`.
+    pub(crate) stream_retention: HashMap<String, RetentionConfig>,
+    /// The number of publishes of a batch awaiting their ack at once.
+    pub(crate) publish_window: usize,
+    /// Whether streams are kept in the memory of the process instead.
+    #[cfg(feature = "in-memory")]
+    pub(crate) in_memory: bool,
 }
 
 impl NatsClientOpts {
@@ -59,6 +69,25 @@ impl NatsClientOpts {
             role: NatsUserRole::default(),
             namespace: NatsNamespace::default(),
             timeout_secs: 5,
+            retention: None,
+            stream_retention: HashMap::new(),
+            publish_window: DEFAULT_PUBLISH_WINDOW,
+            #[cfg(feature = "in-memory")]
+            in_memory: false,
+        }
+    }
+
+    /// Options of a client that doesn't connect to NATS, and keeps streams in
+    /// the memory of the process instead.
+    ///
+    /// Every in-memory client of the process sees the streams of the others
+    /// in the same namespace, so that a publisher and its consumers can run
+    /// in a single test.
+    #[cfg(feature = "in-memory")]
+    pub fn in_memory() -> Self {
+        Self {
+            in_memory: true,
+            ..Self::new("memory://")
         }
     }
 
@@ -94,6 +123,58 @@ impl NatsClientOpts {
         }
     }
 
+    /// Sets the retention of the streams created by the client, except
+    /// for the ones given one with `with_stream_retention`.
+    pub fn with_retention(self, retention: RetentionConfig) -> Self {
+        Self {
+            retention: Some(retention),
+            ..self
+        }
+    }
+
+    /// Sets the retention of the stream of the `Streamable` named `name`.
+    pub fn with_stream_retention(
+        mut self,
+        name: impl ToString,
+        retention: RetentionConfig,
+    ) -> Self {
+        self.stream_retention.insert(name.to_string(), retention);
+        self
+    }
+
+    /// Sets the number of publishes of a batch awaiting their ack at once,
+    /// see `Stream::publish_batch`.
+    pub fn with_publish_window(self, publish_window: usize) -> Self {
+        Self {
+            publish_window,
+            ..self
+        }
+    }
+
+    /// The retention of the stream of the `Streamable` named `name`.
+    pub fn retention(&self, name: &str) -> RetentionConfig {
+        self.explicit_retention(name).cloned().unwrap_or_default()
+    }
+
+    /// The retention the client applies to the stream of the `Streamable`
+    /// named `name`, when it exists already. Only admin clients given one
+    /// with `with_retention` or `with_stream_retention` change the limits of
+    /// a stream, so that clients opening it with the default options leave
+    /// the limits set by its publisher alone.
+    pub(crate) fn managed_retention(
+        &self,
+        name: &str,
+    ) -> Option<&RetentionConfig> {
+        match self.role {
+            NatsUserRole::Admin => self.explicit_retention(name),
+            NatsUserRole::Default => None,
+        }
+    }
+
+    fn explicit_retention(&self, name: &str) -> Option<&RetentionConfig> {
+        self.stream_retention.get(name).or(self.retention.as_ref())
+    }
+
     pub(super) fn connect_opts(&self) -> ConnectOptions {
         let (user, pass) = match self.role {
             NatsUserRole::Admin => (
@@ -4,9 +4,9 @@ pub mod types;
 pub use subjects::*;
 
 use super::types::*;
-use crate::{StreamEncoder, Streamable};
+use crate::{protobuf::impl_protobuf_stream_encoder, Streamable};
 
-impl StreamEncoder for Output {}
+impl_protobuf_stream_encoder!(Output);
 impl Streamable for Output {
     const NAME: &'static str = "outputs";
     const WILDCARD_LIST: &'static [&'static str] = &[
@@ -1,7 +1,7 @@
 use crate::types::*;
 
 // Output enum
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum Output {
     Coin(CoinOutput),
@@ -55,7 +55,9 @@ impl From<&FuelCoreOutput> for Output {
     }
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct CoinOutput {
     pub amount: u64,
@@ -63,7 +65,9 @@ pub struct CoinOutput {
     pub to: Address,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct ChangeOutput {
     pub amount: u64,
@@ -71,7 +75,9 @@ pub struct ChangeOutput {
     pub to: Address,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct VariableOutput {
     pub amount: u64,
@@ -79,7 +85,9 @@ pub struct VariableOutput {
     pub to: Address,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct OutputContract {
     pub balance_root: Bytes32,
@@ -97,9 +105,11 @@ impl From<&FuelCoreOutputContract> for OutputContract {
     }
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct ContractCreated {
-    contract_id: ContractId,
-    state_root: Bytes32,
+    pub contract_id: ContractId,
+    pub state_root: Bytes32,
 }
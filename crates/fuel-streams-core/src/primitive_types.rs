@@ -1,10 +1,20 @@
 use fuel_core_types::fuel_types;
+pub use schemars::JsonSchema;
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Schema, SchemaObject, StringValidation},
+};
 pub use serde::{Deserialize, Serialize};
 
 use crate::fuel_core_types::*;
 
-/// Implements hex-formatted serialization and deserialization for a type
-/// that implements Display and FromStr
+/// Implements serialization and deserialization for a byte-based type that
+/// implements Display, FromStr and `TryFrom<&[u8]>`.
+///
+/// Human-readable formats (JSON) get a `0x` prefixed hex string, while binary
+/// formats (Bincode, Postcard) get the raw bytes, which are about half the
+/// size and much cheaper to encode. `HexBytes` (de)serializes them as hex
+/// strings in binary formats as well.
 macro_rules! impl_hex_serde {
     ($type:ty) => {
         impl Serialize for $type {
@@ -12,7 +22,11 @@ macro_rules! impl_hex_serde {
             where
                 S: serde::Serializer,
             {
-                serializer.serialize_str(&self.to_string())
+                if serializer.is_human_readable() {
+                    serializer.serialize_str(&self.to_string())
+                } else {
+                    serializer.serialize_bytes(AsRef::<[u8]>::as_ref(&self.0))
+                }
             }
         }
 
@@ -21,13 +35,100 @@ macro_rules! impl_hex_serde {
             where
                 D: serde::Deserializer<'de>,
             {
-                let s = String::deserialize(deserializer)?;
-                s.parse().map_err(serde::de::Error::custom)
+                let visitor = HexVisitor(std::marker::PhantomData::<$type>);
+                if deserializer.is_human_readable() {
+                    deserializer.deserialize_str(visitor)
+                } else {
+                    deserializer.deserialize_bytes(visitor)
+                }
+            }
+        }
+    };
+}
+
+/// Implements `JsonSchema` for a type serialized by `impl_hex_serde!`.
+///
+/// The schema describes the human-readable form, a `0x` prefixed hex string,
+/// with `$byte_size` bytes when the size is fixed.
+macro_rules! impl_hex_json_schema {
+    ($type:ty, $name:expr) => {
+        impl_hex_json_schema!($type, $name, None);
+    };
+    ($type:ty, $name:expr, $byte_size:expr) => {
+        impl JsonSchema for $type {
+            fn schema_name() -> String {
+                $name.to_string()
+            }
+
+            fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+                hex_schema("0x", $byte_size)
             }
         }
     };
 }
 
+/// Schema of a hex string with the given prefix, and of `byte_size` bytes
+/// if given.
+fn hex_schema(prefix: &str, byte_size: Option<usize>) -> Schema {
+    let digits = match byte_size {
+        Some(size) => format!("[0-9a-fA-F]{{{}}}", size * 2),
+        None => "([0-9a-fA-F]{2})*".to_string(),
+    };
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        string: Some(Box::new(StringValidation {
+            pattern: Some(format!("^{prefix}{digits}$")),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Schema of the unprefixed hex string fuel-core serializes ids as.
+pub(crate) fn unprefixed_bytes32_schema(_gen: &mut SchemaGenerator) -> Schema {
+    hex_schema("", Some(32))
+}
+
+/// Reads the hex string or raw bytes written by `impl_hex_serde!`.
+///
+/// Both are accepted whatever the format, as internally tagged enums buffer
+/// their content and hand it over as human-readable, even when it was
+/// written by a binary format.
+struct HexVisitor<T>(std::marker::PhantomData<T>);
+
+impl<'de, T> serde::de::Visitor<'de> for HexVisitor<T>
+where
+    T: std::str::FromStr + for<'a> TryFrom<&'a [u8]>,
+    <T as std::str::FromStr>::Err: std::fmt::Display,
+    for<'a> <T as TryFrom<&'a [u8]>>::Error: std::fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a hex string or a byte array")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<T, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<T, E> {
+        T::try_from(v).map_err(E::custom)
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> Result<T, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
 /// Macro to generate a wrapper type for different byte-based types (including Address type).
 ///
 /// This macro creates a new struct that wraps the specified inner type,
@@ -54,6 +155,11 @@ macro_rules! generate_byte_type_wrapper {
         pub struct $wrapper_type(pub $inner_type);
 
         impl_hex_serde!($wrapper_type);
+        impl_hex_json_schema!(
+            $wrapper_type,
+            stringify!($wrapper_type),
+            Some($byte_size)
+        );
 
         impl From<$inner_type> for $wrapper_type {
             fn from(value: $inner_type) -> Self {
@@ -79,6 +185,14 @@ macro_rules! generate_byte_type_wrapper {
             }
         }
 
+        impl TryFrom<&[u8]> for $wrapper_type {
+            type Error = std::array::TryFromSliceError;
+
+            fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+                <$inner_type>::try_from(value).map($wrapper_type)
+            }
+        }
+
         impl std::fmt::Display for $wrapper_type {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 write!(f, "0x{}", self.0)
@@ -90,19 +204,10 @@ macro_rules! generate_byte_type_wrapper {
 
             fn from_str(s: &str) -> Result<Self, Self::Err> {
                 let s = s.strip_prefix("0x").unwrap_or(s);
-                if s.len() != std::mem::size_of::<$inner_type>() * 2 {
-                    panic!("Invalid length for {}", stringify!($wrapper_type));
-                }
-                let mut inner = <$inner_type>::zeroed();
-                for (i, chunk) in s.as_bytes().chunks(2).enumerate() {
-                    let byte = u8::from_str_radix(
-                        std::str::from_utf8(chunk).unwrap(),
-                        16,
-                    )
-                    .unwrap();
-                    inner.as_mut()[i] = byte;
-                }
-                Ok($wrapper_type(inner))
+                let bytes = hex::decode(s).map_err(|e| e.to_string())?;
+                <$wrapper_type>::try_from(bytes.as_slice()).map_err(|_| {
+                    format!("Invalid length for {}", stringify!($wrapper_type))
+                })
             }
         }
 
@@ -180,6 +285,7 @@ impl From<&Bytes32> for MessageId {
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct HexString(pub Vec<u8>);
 impl_hex_serde!(HexString);
+impl_hex_json_schema!(HexString, "HexString");
 
 impl From<&[u8]> for HexString {
     fn from(value: &[u8]) -> Self {
@@ -213,9 +319,11 @@ impl std::str::FromStr for HexString {
     Hash,
     Deserialize,
     Serialize,
+    JsonSchema,
 )]
 #[serde(rename_all = "camelCase")]
 pub struct TxPointer {
+    #[schemars(with = "u32")]
     block_height: FuelCoreBlockHeight,
     tx_index: u16,
 }
@@ -229,6 +337,16 @@ impl From<FuelCoreTxPointer> for TxPointer {
     }
 }
 
+impl TxPointer {
+    pub fn block_height(&self) -> FuelCoreBlockHeight {
+        self.block_height
+    }
+
+    pub fn tx_index(&self) -> u16 {
+        self.tx_index
+    }
+}
+
 #[derive(
     Debug,
     Default,
@@ -241,12 +359,69 @@ impl From<FuelCoreTxPointer> for TxPointer {
     Hash,
     Serialize,
     Deserialize,
+    JsonSchema,
 )]
 #[serde(rename_all = "camelCase")]
 pub struct UtxoId {
+    #[schemars(schema_with = "unprefixed_bytes32_schema")]
+    #[serde(deserialize_with = "deserialize_tx_id")]
     tx_id: FuelCoreTxId,
     output_index: u16,
 }
+
+/// Reads a transaction id as written by `FuelCoreTxId`, i.e. a hex string
+/// or a tuple of bytes.
+///
+/// Both are accepted whatever the format, as internally tagged enums hand
+/// their buffered content over as human-readable.
+fn deserialize_tx_id<'de, D>(deserializer: D) -> Result<FuelCoreTxId, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct TxIdVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for TxIdVisitor {
+        type Value = FuelCoreTxId;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("a hex string or 32 bytes")
+        }
+
+        fn visit_str<E: serde::de::Error>(
+            self,
+            v: &str,
+        ) -> Result<Self::Value, E> {
+            v.parse().map_err(E::custom)
+        }
+
+        fn visit_bytes<E: serde::de::Error>(
+            self,
+            v: &[u8],
+        ) -> Result<Self::Value, E> {
+            FuelCoreTxId::try_from(v).map_err(E::custom)
+        }
+
+        fn visit_seq<A: serde::de::SeqAccess<'de>>(
+            self,
+            mut seq: A,
+        ) -> Result<Self::Value, A::Error> {
+            let mut bytes = [0u8; 32];
+            for (index, byte) in bytes.iter_mut().enumerate() {
+                *byte = seq.next_element()?.ok_or_else(|| {
+                    serde::de::Error::invalid_length(index, &self)
+                })?;
+            }
+            Ok(bytes.into())
+        }
+    }
+
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(TxIdVisitor)
+    } else {
+        deserializer.deserialize_tuple(32, TxIdVisitor)
+    }
+}
+
 impl From<FuelCoreUtxoId> for UtxoId {
     fn from(value: FuelCoreUtxoId) -> Self {
         Self::from(&value)
@@ -261,6 +436,16 @@ impl From<&FuelCoreUtxoId> for UtxoId {
     }
 }
 
+impl UtxoId {
+    pub fn tx_id(&self) -> &FuelCoreTxId {
+        &self.tx_id
+    }
+
+    pub fn output_index(&self) -> u16 {
+        self.output_index
+    }
+}
+
 /// Macro to implement conversion from a type to `Bytes32`.
 ///
 /// This macro creates an implementation of the `From` trait, allowing for conversion
@@ -299,3 +484,73 @@ macro_rules! impl_from_bytes32 {
 impl_from_bytes32!(fuel_types::ContractId);
 impl_from_bytes32!(fuel_types::AssetId);
 impl_from_bytes32!(fuel_types::Address);
+
+#[cfg(test)]
+mod tests {
+    use fuel_data_parser::{DataParser, SerializationType};
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Payload {
+        address: Address,
+        asset_id: AssetId,
+        signature: Signature,
+        data: HexString,
+    }
+
+    fn payload() -> Payload {
+        Payload {
+            address: [1u8; 32].into(),
+            asset_id: [2u8; 32].into(),
+            signature: [3u8; 64].into(),
+            data: HexString(vec![0xde, 0xad, 0xbe, 0xef]),
+        }
+    }
+
+    #[test]
+    fn test_human_readable_serialization_is_hex() {
+        let json = serde_json::to_value(payload()).unwrap();
+        assert_eq!(json["address"], format!("0x{}", "01".repeat(32)).as_str());
+        assert_eq!(json["data"], "0xdeadbeef");
+
+        let decoded: Payload = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, payload());
+    }
+
+    #[test]
+    fn test_binary_serialization_is_raw_bytes() {
+        let bytes = postcard::to_allocvec(&Bytes32::from([7u8; 32])).unwrap();
+        // length prefix followed by the raw bytes
+        assert_eq!(bytes.len(), 1 + 32);
+        assert_eq!(&bytes[1..], &[7u8; 32]);
+
+        let bytes = bincode::serialize(&HexString(vec![1, 2, 3])).unwrap();
+        assert_eq!(bytes.len(), 8 + 3);
+
+        assert!(postcard::from_bytes::<Bytes32>(&[3, 1, 2, 3]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_roundtrip_with_every_serialization_type() {
+        for serialization_type in [
+            SerializationType::Json,
+            SerializationType::Bincode,
+            SerializationType::Postcard,
+            SerializationType::MessagePack,
+            SerializationType::Cbor,
+        ] {
+            let parser = DataParser::default()
+                .with_serialization_type(serialization_type);
+            let encoded = parser.encode(&payload()).await.unwrap();
+            let decoded: Payload = parser.decode(&encoded).await.unwrap();
+            assert_eq!(decoded, payload(), "{serialization_type}");
+
+            if serialization_type != SerializationType::Json {
+                let json =
+                    DataParser::default().encode(&payload()).await.unwrap();
+                assert!(encoded.len() < json.len(), "{serialization_type}");
+            }
+        }
+    }
+}
@@ -0,0 +1,1109 @@
+use std::array::TryFromSliceError;
+
+use super::{proto, ProtobufError};
+use crate::{types::*, StreamData, StreamEncoder, StreamMetadata};
+
+// ------------------------------------------------------------------------
+// Helpers
+// ------------------------------------------------------------------------
+
+fn from_bytes<T>(field: &'static str, bytes: &[u8]) -> Result<T, ProtobufError>
+where
+    T: for<'a> TryFrom<&'a [u8], Error = TryFromSliceError>,
+{
+    T::try_from(bytes)
+        .map_err(|source| ProtobufError::InvalidBytes { field, source })
+}
+
+fn from_optional_bytes<T>(
+    field: &'static str,
+    bytes: Option<Vec<u8>>,
+) -> Result<Option<T>, ProtobufError>
+where
+    T: for<'a> TryFrom<&'a [u8], Error = TryFromSliceError>,
+{
+    bytes.map(|bytes| from_bytes(field, &bytes)).transpose()
+}
+
+fn from_bytes_list<T>(
+    field: &'static str,
+    list: Vec<Vec<u8>>,
+) -> Result<Vec<T>, ProtobufError>
+where
+    T: for<'a> TryFrom<&'a [u8], Error = TryFromSliceError>,
+{
+    list.iter().map(|bytes| from_bytes(field, bytes)).collect()
+}
+
+fn required<T>(
+    field: &'static str,
+    value: Option<T>,
+) -> Result<T, ProtobufError> {
+    value.ok_or(ProtobufError::MissingField(field))
+}
+
+fn narrow<T: TryFrom<U>, U>(
+    field: &'static str,
+    value: U,
+) -> Result<T, ProtobufError> {
+    T::try_from(value).map_err(|_| ProtobufError::OutOfRange(field))
+}
+
+fn enum_value<T: TryFrom<i32>>(
+    field: &'static str,
+    value: i32,
+) -> Result<T, ProtobufError> {
+    T::try_from(value)
+        .map_err(|_| ProtobufError::UnknownEnumValue { field, value })
+}
+
+// ------------------------------------------------------------------------
+// StreamData
+// ------------------------------------------------------------------------
+
+impl<T: StreamEncoder> StreamData<T> {
+    /// Converts the stream data into its protobuf message, if the payload
+    /// type has one.
+    pub fn to_protobuf(&self) -> Option<proto::StreamData> {
+        Some(proto::StreamData {
+            subject: self.subject.clone(),
+            timestamp: String::new(),
+            payload: Some(self.payload.to_protobuf()?),
+            metadata: Some((&self.metadata).into()),
+        })
+    }
+
+    pub fn from_protobuf(
+        message: proto::StreamData,
+    ) -> Result<Self, ProtobufError> {
+        let payload = required("payload", message.payload)?;
+        let metadata = match message.metadata {
+            Some(metadata) => metadata.into(),
+            // Published before the metadata was added
+            None => StreamMetadata::from_rfc3339(&message.timestamp),
+        };
+        Ok(Self {
+            subject: message.subject,
+            metadata,
+            payload: T::from_protobuf(payload)?,
+        })
+    }
+}
+
+impl From<&StreamMetadata> for proto::StreamMetadata {
+    fn from(metadata: &StreamMetadata) -> Self {
+        Self {
+            timestamp_ms: metadata.timestamp_ms,
+            chain_id: metadata.chain_id,
+            block_height: metadata.block_height,
+            block_timestamp_ms: metadata.block_timestamp_ms,
+            publisher_id: metadata.publisher_id.clone(),
+            sequence: metadata.sequence,
+        }
+    }
+}
+
+impl From<proto::StreamMetadata> for StreamMetadata {
+    fn from(metadata: proto::StreamMetadata) -> Self {
+        Self {
+            timestamp_ms: metadata.timestamp_ms,
+            chain_id: metadata.chain_id,
+            block_height: metadata.block_height,
+            block_timestamp_ms: metadata.block_timestamp_ms,
+            publisher_id: metadata.publisher_id,
+            sequence: metadata.sequence,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------
+// Common
+// ------------------------------------------------------------------------
+
+impl From<&TxPointer> for proto::TxPointer {
+    fn from(pointer: &TxPointer) -> Self {
+        Self {
+            block_height: *pointer.block_height(),
+            tx_index: pointer.tx_index().into(),
+        }
+    }
+}
+
+impl TryFrom<proto::TxPointer> for TxPointer {
+    type Error = ProtobufError;
+
+    fn try_from(pointer: proto::TxPointer) -> Result<Self, Self::Error> {
+        Ok(FuelCoreTxPointer::new(
+            pointer.block_height.into(),
+            narrow("tx_pointer.tx_index", pointer.tx_index)?,
+        )
+        .into())
+    }
+}
+
+impl From<&UtxoId> for proto::UtxoId {
+    fn from(utxo_id: &UtxoId) -> Self {
+        Self {
+            tx_id: utxo_id.tx_id().to_vec(),
+            output_index: utxo_id.output_index().into(),
+        }
+    }
+}
+
+impl TryFrom<proto::UtxoId> for UtxoId {
+    type Error = ProtobufError;
+
+    fn try_from(utxo_id: proto::UtxoId) -> Result<Self, Self::Error> {
+        let tx_id: Bytes32 = from_bytes("utxo_id.tx_id", &utxo_id.tx_id)?;
+        Ok(FuelCoreUtxoId::new(
+            tx_id.into_inner(),
+            narrow("utxo_id.output_index", utxo_id.output_index)?,
+        )
+        .into())
+    }
+}
+
+// ------------------------------------------------------------------------
+// Blocks
+// ------------------------------------------------------------------------
+
+impl From<&Block> for proto::Block {
+    fn from(block: &Block) -> Self {
+        let version = match block.version {
+            BlockVersion::V1 => proto::BlockVersion::V1,
+        };
+        Self {
+            consensus: Some((&block.consensus).into()),
+            header: Some((&block.header).into()),
+            height: block.height,
+            id: block.id.0.to_vec(),
+            transaction_ids: block
+                .transaction_ids
+                .iter()
+                .map(|id| id.0.to_vec())
+                .collect(),
+            version: version.into(),
+        }
+    }
+}
+
+impl TryFrom<proto::Block> for Block {
+    type Error = ProtobufError;
+
+    fn try_from(block: proto::Block) -> Result<Self, Self::Error> {
+        let version = match enum_value("block.version", block.version)? {
+            proto::BlockVersion::V1 => BlockVersion::V1,
+        };
+        Ok(Self {
+            consensus: required("block.consensus", block.consensus)?
+                .try_into()?,
+            header: required("block.header", block.header)?.try_into()?,
+            height: block.height,
+            id: from_bytes("block.id", &block.id)?,
+            transaction_ids: from_bytes_list(
+                "block.transaction_ids",
+                block.transaction_ids,
+            )?,
+            version,
+        })
+    }
+}
+
+impl From<&Consensus> for proto::Consensus {
+    fn from(consensus: &Consensus) -> Self {
+        let kind = match consensus {
+            Consensus::Genesis(genesis) => {
+                proto::consensus::Kind::Genesis(proto::Genesis {
+                    chain_config_hash: genesis.chain_config_hash.0.to_vec(),
+                    coins_root: genesis.coins_root.0.to_vec(),
+                    contracts_root: genesis.contracts_root.0.to_vec(),
+                    messages_root: genesis.messages_root.0.to_vec(),
+                    transactions_root: genesis.transactions_root.0.to_vec(),
+                })
+            }
+            Consensus::PoAConsensus(poa) => {
+                proto::consensus::Kind::Poa(proto::PoAConsensus {
+                    signature: poa.signature.0.to_vec(),
+                })
+            }
+        };
+        Self { kind: Some(kind) }
+    }
+}
+
+impl TryFrom<proto::Consensus> for Consensus {
+    type Error = ProtobufError;
+
+    fn try_from(consensus: proto::Consensus) -> Result<Self, Self::Error> {
+        Ok(match required("consensus.kind", consensus.kind)? {
+            proto::consensus::Kind::Genesis(genesis) => {
+                Consensus::Genesis(Genesis {
+                    chain_config_hash: from_bytes(
+                        "genesis.chain_config_hash",
+                        &genesis.chain_config_hash,
+                    )?,
+                    coins_root: from_bytes(
+                        "genesis.coins_root",
+                        &genesis.coins_root,
+                    )?,
+                    contracts_root: from_bytes(
+                        "genesis.contracts_root",
+                        &genesis.contracts_root,
+                    )?,
+                    messages_root: from_bytes(
+                        "genesis.messages_root",
+                        &genesis.messages_root,
+                    )?,
+                    transactions_root: from_bytes(
+                        "genesis.transactions_root",
+                        &genesis.transactions_root,
+                    )?,
+                })
+            }
+            proto::consensus::Kind::Poa(poa) => Consensus::PoAConsensus(
+                PoAConsensus::new(from_bytes("poa.signature", &poa.signature)?),
+            ),
+        })
+    }
+}
+
+impl From<&BlockHeader> for proto::BlockHeader {
+    fn from(header: &BlockHeader) -> Self {
+        let version = match header.version {
+            BlockHeaderVersion::V1 => proto::BlockHeaderVersion::V1,
+        };
+        Self {
+            application_hash: header.application_hash.0.to_vec(),
+            consensus_parameters_version: header.consensus_parameters_version,
+            da_height: header.da_height,
+            event_inbox_root: header.event_inbox_root.0.to_vec(),
+            id: header.id.0.to_vec(),
+            height: header.height,
+            message_outbox_root: header.message_outbox_root.0.to_vec(),
+            message_receipt_count: header.message_receipt_count,
+            prev_root: header.prev_root.0.to_vec(),
+            state_transition_bytecode_version: header
+                .state_transition_bytecode_version,
+            time: header.time.0 .0,
+            transactions_count: header.transactions_count.into(),
+            transactions_root: header.transactions_root.0.to_vec(),
+            version: version.into(),
+        }
+    }
+}
+
+impl TryFrom<proto::BlockHeader> for BlockHeader {
+    type Error = ProtobufError;
+
+    fn try_from(header: proto::BlockHeader) -> Result<Self, Self::Error> {
+        let version = match enum_value("header.version", header.version)? {
+            proto::BlockHeaderVersion::V1 => BlockHeaderVersion::V1,
+        };
+        Ok(Self {
+            application_hash: from_bytes(
+                "header.application_hash",
+                &header.application_hash,
+            )?,
+            consensus_parameters_version: header.consensus_parameters_version,
+            da_height: header.da_height,
+            event_inbox_root: from_bytes(
+                "header.event_inbox_root",
+                &header.event_inbox_root,
+            )?,
+            id: from_bytes("header.id", &header.id)?,
+            height: header.height,
+            message_outbox_root: from_bytes(
+                "header.message_outbox_root",
+                &header.message_outbox_root,
+            )?,
+            message_receipt_count: header.message_receipt_count,
+            prev_root: from_bytes("header.prev_root", &header.prev_root)?,
+            state_transition_bytecode_version: header
+                .state_transition_bytecode_version,
+            time: FuelCoreTai64(header.time).into(),
+            transactions_count: narrow(
+                "header.transactions_count",
+                header.transactions_count,
+            )?,
+            transactions_root: from_bytes(
+                "header.transactions_root",
+                &header.transactions_root,
+            )?,
+            version,
+        })
+    }
+}
+
+// ------------------------------------------------------------------------
+// Transactions
+// ------------------------------------------------------------------------
+
+impl From<&Transaction> for proto::Transaction {
+    fn from(tx: &Transaction) -> Self {
+        let kind = match tx.kind {
+            TransactionKind::Create => proto::TransactionKind::Create,
+            TransactionKind::Mint => proto::TransactionKind::Mint,
+            TransactionKind::Script => proto::TransactionKind::Script,
+            TransactionKind::Upgrade => proto::TransactionKind::Upgrade,
+            TransactionKind::Upload => proto::TransactionKind::Upload,
+            TransactionKind::Blob => proto::TransactionKind::Blob,
+        };
+        let status = match tx.status {
+            TransactionStatus::Failed => proto::TransactionStatus::Failed,
+            TransactionStatus::Submitted => proto::TransactionStatus::Submitted,
+            TransactionStatus::SqueezedOut => {
+                proto::TransactionStatus::SqueezedOut
+            }
+            TransactionStatus::Success => proto::TransactionStatus::Success,
+            TransactionStatus::None => proto::TransactionStatus::None,
+        };
+
+        Self {
+            id: tx.id.0.to_vec(),
+            kind: kind.into(),
+            bytecode_root: tx
+                .bytecode_root
+                .as_ref()
+                .map(|root| root.0.to_vec()),
+            bytecode_witness_index: tx.bytecode_witness_index.map(Into::into),
+            blob_id: tx.blob_id.as_ref().map(|id| id.0.to_vec()),
+            input_asset_ids: tx.input_asset_ids.as_ref().map(|ids| {
+                proto::BytesList {
+                    items: ids.iter().map(|id| id.0.to_vec()).collect(),
+                }
+            }),
+            input_contract: tx.input_contract.as_ref().map(Into::into),
+            input_contracts: tx.input_contracts.as_ref().map(|ids| {
+                proto::BytesList {
+                    items: ids.iter().map(|id| id.0.to_vec()).collect(),
+                }
+            }),
+            inputs: tx.inputs.iter().map(Into::into).collect(),
+            output_contract: tx.output_contract.as_ref().map(Into::into),
+            outputs: tx.outputs.iter().map(Into::into).collect(),
+            is_create: tx.is_create,
+            is_mint: tx.is_mint,
+            is_script: tx.is_script,
+            is_upgrade: tx.is_upgrade,
+            is_upload: tx.is_upload,
+            maturity: tx.maturity,
+            mint_amount: tx.mint_amount,
+            mint_asset_id: tx.mint_asset_id.as_ref().map(|id| id.0.to_vec()),
+            mint_gas_price: tx.mint_gas_price,
+            policies: tx.policies.as_ref().map(|policies| proto::Policies {
+                tip: policies.get(FuelCorePolicyType::Tip),
+                witness_limit: policies.get(FuelCorePolicyType::WitnessLimit),
+                maturity: policies.get(FuelCorePolicyType::Maturity),
+                max_fee: policies.get(FuelCorePolicyType::MaxFee),
+            }),
+            proof_set: tx
+                .proof_set
+                .iter()
+                .map(|proof| proof.0.to_vec())
+                .collect(),
+            raw_payload: tx.raw_payload.0.clone(),
+            receipts_root: tx
+                .receipts_root
+                .as_ref()
+                .map(|root| root.0.to_vec()),
+            salt: tx.salt.as_ref().map(|salt| salt.0.to_vec()),
+            script: tx.script.as_ref().map(|script| script.0.clone()),
+            script_data: tx.script_data.as_ref().map(|data| data.0.clone()),
+            script_gas_limit: tx.script_gas_limit,
+            status: status.into(),
+            storage_slots: tx
+                .storage_slots
+                .iter()
+                .map(|slot| proto::StorageSlot {
+                    key: slot.key.0.clone(),
+                    value: slot.value.0.clone(),
+                })
+                .collect(),
+            subsection_index: tx.subsection_index.map(Into::into),
+            subsections_number: tx.subsections_number.map(Into::into),
+            tx_pointer: tx
+                .tx_pointer
+                .map(|pointer| (&TxPointer::from(pointer)).into()),
+            upgrade_purpose: tx.upgrade_purpose.as_ref().map(|purpose| {
+                let kind = match purpose {
+                    FuelCoreUpgradePurpose::ConsensusParameters {
+                        witness_index,
+                        checksum,
+                    } => proto::upgrade_purpose::Kind::ConsensusParameters(
+                        proto::upgrade_purpose::ConsensusParameters {
+                            witness_index: (*witness_index).into(),
+                            checksum: checksum.to_vec(),
+                        },
+                    ),
+                    FuelCoreUpgradePurpose::StateTransition { root } => {
+                        proto::upgrade_purpose::Kind::StateTransition(
+                            proto::upgrade_purpose::StateTransition {
+                                root: root.to_vec(),
+                            },
+                        )
+                    }
+                };
+                proto::UpgradePurpose { kind: Some(kind) }
+            }),
+            witnesses: tx
+                .witnesses
+                .iter()
+                .map(|witness| witness.0.clone())
+                .collect(),
+            receipts: tx.receipts.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<proto::Transaction> for Transaction {
+    type Error = ProtobufError;
+
+    fn try_from(tx: proto::Transaction) -> Result<Self, Self::Error> {
+        let kind = match enum_value("transaction.kind", tx.kind)? {
+            proto::TransactionKind::Create => TransactionKind::Create,
+            proto::TransactionKind::Mint => TransactionKind::Mint,
+            proto::TransactionKind::Script => TransactionKind::Script,
+            proto::TransactionKind::Upgrade => TransactionKind::Upgrade,
+            proto::TransactionKind::Upload => TransactionKind::Upload,
+            proto::TransactionKind::Blob => TransactionKind::Blob,
+        };
+        let status = match enum_value("transaction.status", tx.status)? {
+            proto::TransactionStatus::Failed => TransactionStatus::Failed,
+            proto::TransactionStatus::Submitted => TransactionStatus::Submitted,
+            proto::TransactionStatus::SqueezedOut => {
+                TransactionStatus::SqueezedOut
+            }
+            proto::TransactionStatus::Success => TransactionStatus::Success,
+            proto::TransactionStatus::None => TransactionStatus::None,
+        };
+        let policies = tx.policies.map(|policies| {
+            let mut fuel_policies = FuelCorePolicies::new();
+            fuel_policies.set(FuelCorePolicyType::Tip, policies.tip);
+            fuel_policies
+                .set(FuelCorePolicyType::WitnessLimit, policies.witness_limit);
+            fuel_policies.set(FuelCorePolicyType::Maturity, policies.maturity);
+            fuel_policies.set(FuelCorePolicyType::MaxFee, policies.max_fee);
+            fuel_policies
+        });
+        let upgrade_purpose = tx
+            .upgrade_purpose
+            .map(|purpose| {
+                Ok::<_, ProtobufError>(
+                    match required("upgrade_purpose.kind", purpose.kind)? {
+                        proto::upgrade_purpose::Kind::ConsensusParameters(
+                            params,
+                        ) => FuelCoreUpgradePurpose::ConsensusParameters {
+                            witness_index: narrow(
+                                "upgrade_purpose.witness_index",
+                                params.witness_index,
+                            )?,
+                            checksum: from_bytes::<Bytes32>(
+                                "upgrade_purpose.checksum",
+                                &params.checksum,
+                            )?
+                            .into_inner(),
+                        },
+                        proto::upgrade_purpose::Kind::StateTransition(
+                            transition,
+                        ) => FuelCoreUpgradePurpose::StateTransition {
+                            root: from_bytes::<Bytes32>(
+                                "upgrade_purpose.root",
+                                &transition.root,
+                            )?
+                            .into_inner(),
+                        },
+                    },
+                )
+            })
+            .transpose()?;
+
+        Ok(Self {
+            id: from_bytes("transaction.id", &tx.id)?,
+            kind,
+            bytecode_root: from_optional_bytes(
+                "transaction.bytecode_root",
+                tx.bytecode_root,
+            )?,
+            bytecode_witness_index: tx
+                .bytecode_witness_index
+                .map(|index| {
+                    narrow("transaction.bytecode_witness_index", index)
+                })
+                .transpose()?,
+            blob_id: from_optional_bytes("transaction.blob_id", tx.blob_id)?,
+            input_asset_ids: tx
+                .input_asset_ids
+                .map(|ids| {
+                    from_bytes_list("transaction.input_asset_ids", ids.items)
+                })
+                .transpose()?,
+            input_contract: tx
+                .input_contract
+                .map(TryInto::try_into)
+                .transpose()?,
+            input_contracts: tx
+                .input_contracts
+                .map(|ids| {
+                    from_bytes_list("transaction.input_contracts", ids.items)
+                })
+                .transpose()?,
+            inputs: tx
+                .inputs
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            output_contract: tx
+                .output_contract
+                .map(TryInto::try_into)
+                .transpose()?,
+            outputs: tx
+                .outputs
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            is_create: tx.is_create,
+            is_mint: tx.is_mint,
+            is_script: tx.is_script,
+            is_upgrade: tx.is_upgrade,
+            is_upload: tx.is_upload,
+            maturity: tx.maturity,
+            mint_amount: tx.mint_amount,
+            mint_asset_id: from_optional_bytes(
+                "transaction.mint_asset_id",
+                tx.mint_asset_id,
+            )?,
+            mint_gas_price: tx.mint_gas_price,
+            policies,
+            proof_set: from_bytes_list("transaction.proof_set", tx.proof_set)?,
+            raw_payload: HexString(tx.raw_payload),
+            receipts_root: from_optional_bytes(
+                "transaction.receipts_root",
+                tx.receipts_root,
+            )?,
+            salt: from_optional_bytes("transaction.salt", tx.salt)?,
+            script: tx.script.map(HexString),
+            script_data: tx.script_data.map(HexString),
+            script_gas_limit: tx.script_gas_limit,
+            status,
+            storage_slots: tx
+                .storage_slots
+                .into_iter()
+                .map(|slot| StorageSlot {
+                    key: HexString(slot.key),
+                    value: HexString(slot.value),
+                })
+                .collect(),
+            subsection_index: tx
+                .subsection_index
+                .map(|index| narrow("transaction.subsection_index", index))
+                .transpose()?,
+            subsections_number: tx
+                .subsections_number
+                .map(|number| narrow("transaction.subsections_number", number))
+                .transpose()?,
+            tx_pointer: tx
+                .tx_pointer
+                .map(|pointer| {
+                    let pointer = TxPointer::try_from(pointer)?;
+                    Ok::<_, ProtobufError>(FuelCoreTxPointer::new(
+                        pointer.block_height(),
+                        pointer.tx_index(),
+                    ))
+                })
+                .transpose()?,
+            upgrade_purpose,
+            witnesses: tx.witnesses.into_iter().map(HexString).collect(),
+            receipts: tx
+                .receipts
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+// ------------------------------------------------------------------------
+// Inputs
+// ------------------------------------------------------------------------
+
+impl From<&Input> for proto::Input {
+    fn from(input: &Input) -> Self {
+        let kind = match input {
+            Input::Contract(contract) => {
+                proto::input::Kind::Contract(contract.into())
+            }
+            Input::Coin(coin) => proto::input::Kind::Coin(proto::InputCoin {
+                amount: coin.amount,
+                asset_id: coin.asset_id.0.to_vec(),
+                owner: coin.owner.0.to_vec(),
+                predicate: coin.predicate.0.clone(),
+                predicate_data: coin.predicate_data.0.clone(),
+                predicate_gas_used: coin.predicate_gas_used,
+                tx_pointer: Some((&coin.tx_pointer).into()),
+                utxo_id: Some((&coin.utxo_id).into()),
+                witness_index: coin.witness_index.into(),
+            }),
+            Input::Message(message) => {
+                proto::input::Kind::Message(proto::InputMessage {
+                    amount: message.amount,
+                    data: message.data.0.clone(),
+                    nonce: message.nonce.0.to_vec(),
+                    predicate: message.predicate.0.clone(),
+                    predicate_length: message.predicate_length as u64,
+                    predicate_data: message.predicate_data.0.clone(),
+                    predicate_gas_used: message.predicate_gas_used,
+                    predicate_data_length: message.predicate_data_length as u64,
+                    recipient: message.recipient.0.to_vec(),
+                    sender: message.sender.0.to_vec(),
+                    witness_index: message.witness_index.into(),
+                })
+            }
+        };
+        Self { kind: Some(kind) }
+    }
+}
+
+impl TryFrom<proto::Input> for Input {
+    type Error = ProtobufError;
+
+    fn try_from(input: proto::Input) -> Result<Self, Self::Error> {
+        Ok(match required("input.kind", input.kind)? {
+            proto::input::Kind::Contract(contract) => {
+                Input::Contract(contract.try_into()?)
+            }
+            proto::input::Kind::Coin(coin) => Input::Coin(InputCoin {
+                amount: coin.amount,
+                asset_id: from_bytes("input.asset_id", &coin.asset_id)?,
+                owner: from_bytes("input.owner", &coin.owner)?,
+                predicate: HexString(coin.predicate),
+                predicate_data: HexString(coin.predicate_data),
+                predicate_gas_used: coin.predicate_gas_used,
+                tx_pointer: required("input.tx_pointer", coin.tx_pointer)?
+                    .try_into()?,
+                utxo_id: required("input.utxo_id", coin.utxo_id)?.try_into()?,
+                witness_index: narrow(
+                    "input.witness_index",
+                    coin.witness_index,
+                )?,
+            }),
+            proto::input::Kind::Message(message) => {
+                Input::Message(InputMessage {
+                    amount: message.amount,
+                    data: HexString(message.data),
+                    nonce: from_bytes("input.nonce", &message.nonce)?,
+                    predicate: HexString(message.predicate),
+                    predicate_length: narrow(
+                        "input.predicate_length",
+                        message.predicate_length,
+                    )?,
+                    predicate_data: HexString(message.predicate_data),
+                    predicate_gas_used: message.predicate_gas_used,
+                    predicate_data_length: narrow(
+                        "input.predicate_data_length",
+                        message.predicate_data_length,
+                    )?,
+                    recipient: from_bytes(
+                        "input.recipient",
+                        &message.recipient,
+                    )?,
+                    sender: from_bytes("input.sender", &message.sender)?,
+                    witness_index: narrow(
+                        "input.witness_index",
+                        message.witness_index,
+                    )?,
+                })
+            }
+        })
+    }
+}
+
+impl From<&InputContract> for proto::InputContract {
+    fn from(contract: &InputContract) -> Self {
+        Self {
+            balance_root: contract.balance_root.0.to_vec(),
+            contract_id: contract.contract_id.0.to_vec(),
+            state_root: contract.state_root.0.to_vec(),
+            tx_pointer: Some((&contract.tx_pointer).into()),
+            utxo_id: Some((&contract.utxo_id).into()),
+        }
+    }
+}
+
+impl TryFrom<proto::InputContract> for InputContract {
+    type Error = ProtobufError;
+
+    fn try_from(contract: proto::InputContract) -> Result<Self, Self::Error> {
+        Ok(Self {
+            balance_root: from_bytes(
+                "input_contract.balance_root",
+                &contract.balance_root,
+            )?,
+            contract_id: from_bytes(
+                "input_contract.contract_id",
+                &contract.contract_id,
+            )?,
+            state_root: from_bytes(
+                "input_contract.state_root",
+                &contract.state_root,
+            )?,
+            tx_pointer: required(
+                "input_contract.tx_pointer",
+                contract.tx_pointer,
+            )?
+            .try_into()?,
+            utxo_id: required("input_contract.utxo_id", contract.utxo_id)?
+                .try_into()?,
+        })
+    }
+}
+
+// ------------------------------------------------------------------------
+// Outputs
+// ------------------------------------------------------------------------
+
+impl From<&Output> for proto::Output {
+    fn from(output: &Output) -> Self {
+        let kind = match output {
+            Output::Coin(coin) => {
+                proto::output::Kind::Coin(proto::CoinOutput {
+                    amount: coin.amount,
+                    asset_id: coin.asset_id.0.to_vec(),
+                    to: coin.to.0.to_vec(),
+                })
+            }
+            Output::Contract(contract) => {
+                proto::output::Kind::Contract(contract.into())
+            }
+            Output::Change(change) => {
+                proto::output::Kind::Change(proto::ChangeOutput {
+                    amount: change.amount,
+                    asset_id: change.asset_id.0.to_vec(),
+                    to: change.to.0.to_vec(),
+                })
+            }
+            Output::Variable(variable) => {
+                proto::output::Kind::Variable(proto::VariableOutput {
+                    amount: variable.amount,
+                    asset_id: variable.asset_id.0.to_vec(),
+                    to: variable.to.0.to_vec(),
+                })
+            }
+            Output::ContractCreated(created) => {
+                proto::output::Kind::ContractCreated(proto::ContractCreated {
+                    contract_id: created.contract_id.0.to_vec(),
+                    state_root: created.state_root.0.to_vec(),
+                })
+            }
+        };
+        Self { kind: Some(kind) }
+    }
+}
+
+impl TryFrom<proto::Output> for Output {
+    type Error = ProtobufError;
+
+    fn try_from(output: proto::Output) -> Result<Self, Self::Error> {
+        Ok(match required("output.kind", output.kind)? {
+            proto::output::Kind::Coin(coin) => Output::Coin(CoinOutput {
+                amount: coin.amount,
+                asset_id: from_bytes("output.asset_id", &coin.asset_id)?,
+                to: from_bytes("output.to", &coin.to)?,
+            }),
+            proto::output::Kind::Contract(contract) => {
+                Output::Contract(contract.try_into()?)
+            }
+            proto::output::Kind::Change(change) => {
+                Output::Change(ChangeOutput {
+                    amount: change.amount,
+                    asset_id: from_bytes("output.asset_id", &change.asset_id)?,
+                    to: from_bytes("output.to", &change.to)?,
+                })
+            }
+            proto::output::Kind::Variable(variable) => {
+                Output::Variable(VariableOutput {
+                    amount: variable.amount,
+                    asset_id: from_bytes(
+                        "output.asset_id",
+                        &variable.asset_id,
+                    )?,
+                    to: from_bytes("output.to", &variable.to)?,
+                })
+            }
+            proto::output::Kind::ContractCreated(created) => {
+                Output::ContractCreated(ContractCreated {
+                    contract_id: from_bytes(
+                        "output.contract_id",
+                        &created.contract_id,
+                    )?,
+                    state_root: from_bytes(
+                        "output.state_root",
+                        &created.state_root,
+                    )?,
+                })
+            }
+        })
+    }
+}
+
+impl From<&OutputContract> for proto::OutputContract {
+    fn from(contract: &OutputContract) -> Self {
+        Self {
+            balance_root: contract.balance_root.0.to_vec(),
+            input_index: contract.input_index.into(),
+            state_root: contract.state_root.0.to_vec(),
+        }
+    }
+}
+
+impl TryFrom<proto::OutputContract> for OutputContract {
+    type Error = ProtobufError;
+
+    fn try_from(contract: proto::OutputContract) -> Result<Self, Self::Error> {
+        Ok(Self {
+            balance_root: from_bytes(
+                "output_contract.balance_root",
+                &contract.balance_root,
+            )?,
+            input_index: narrow(
+                "output_contract.input_index",
+                contract.input_index,
+            )?,
+            state_root: from_bytes(
+                "output_contract.state_root",
+                &contract.state_root,
+            )?,
+        })
+    }
+}
+
+// ------------------------------------------------------------------------
+// Receipts
+// ------------------------------------------------------------------------
+
+impl From<&Receipt> for proto::Receipt {
+    fn from(receipt: &Receipt) -> Self {
+        let receipt_type = match receipt.receipt_type {
+            ReceiptType::Burn => proto::ReceiptType::Burn,
+            ReceiptType::Call => proto::ReceiptType::Call,
+            ReceiptType::Log => proto::ReceiptType::Log,
+            ReceiptType::LogData => proto::ReceiptType::LogData,
+            ReceiptType::MessageOut => proto::ReceiptType::MessageOut,
+            ReceiptType::Mint => proto::ReceiptType::Mint,
+            ReceiptType::Panic => proto::ReceiptType::Panic,
+            ReceiptType::Return => proto::ReceiptType::Return,
+            ReceiptType::ReturnData => proto::ReceiptType::ReturnData,
+            ReceiptType::Revert => proto::ReceiptType::Revert,
+            ReceiptType::ScriptResult => proto::ReceiptType::ScriptResult,
+            ReceiptType::Transfer => proto::ReceiptType::Transfer,
+            ReceiptType::TransferOut => proto::ReceiptType::TransferOut,
+        };
+        Self {
+            amount: receipt.amount,
+            asset_id: receipt.asset_id.as_ref().map(|id| id.0.to_vec()),
+            contract_id: receipt.contract_id.as_ref().map(|id| id.0.to_vec()),
+            data: receipt.data.as_ref().map(|data| data.0.clone()),
+            digest: receipt.digest.as_ref().map(|digest| digest.0.to_vec()),
+            gas: receipt.gas,
+            gas_used: receipt.gas_used,
+            id: receipt.id.as_ref().map(|id| id.0.to_vec()),
+            is: receipt.is,
+            len: receipt.len,
+            nonce: receipt.nonce.as_ref().map(|nonce| nonce.0.to_vec()),
+            param1: receipt.param1,
+            param2: receipt.param2,
+            pc: receipt.pc,
+            ptr: receipt.ptr,
+            ra: receipt.ra,
+            rb: receipt.rb,
+            rc: receipt.rc,
+            rd: receipt.rd,
+            reason: receipt.reason,
+            receipt_type: receipt_type.into(),
+            recipient: receipt.recipient.as_ref().map(|to| to.0.to_vec()),
+            result: receipt.result,
+            sender: receipt.sender.as_ref().map(|from| from.0.to_vec()),
+            sub_id: receipt.sub_id.as_ref().map(|id| id.0.to_vec()),
+            to: receipt.to.as_ref().map(|to| to.0.to_vec()),
+            to_address: receipt.to_address.as_ref().map(|to| to.0.to_vec()),
+            val: receipt.val,
+        }
+    }
+}
+
+impl TryFrom<proto::Receipt> for Receipt {
+    type Error = ProtobufError;
+
+    fn try_from(receipt: proto::Receipt) -> Result<Self, Self::Error> {
+        let receipt_type =
+            match enum_value("receipt.receipt_type", receipt.receipt_type)? {
+                proto::ReceiptType::Burn => ReceiptType::Burn,
+                proto::ReceiptType::Call => ReceiptType::Call,
+                proto::ReceiptType::Log => ReceiptType::Log,
+                proto::ReceiptType::LogData => ReceiptType::LogData,
+                proto::ReceiptType::MessageOut => ReceiptType::MessageOut,
+                proto::ReceiptType::Mint => ReceiptType::Mint,
+                proto::ReceiptType::Panic => ReceiptType::Panic,
+                proto::ReceiptType::Return => ReceiptType::Return,
+                proto::ReceiptType::ReturnData => ReceiptType::ReturnData,
+                proto::ReceiptType::Revert => ReceiptType::Revert,
+                proto::ReceiptType::ScriptResult => ReceiptType::ScriptResult,
+                proto::ReceiptType::Transfer => ReceiptType::Transfer,
+                proto::ReceiptType::TransferOut => ReceiptType::TransferOut,
+            };
+        Ok(Self {
+            amount: receipt.amount,
+            asset_id: from_optional_bytes(
+                "receipt.asset_id",
+                receipt.asset_id,
+            )?,
+            contract_id: from_optional_bytes(
+                "receipt.contract_id",
+                receipt.contract_id,
+            )?,
+            data: receipt.data.map(HexString),
+            digest: from_optional_bytes("receipt.digest", receipt.digest)?,
+            gas: receipt.gas,
+            gas_used: receipt.gas_used,
+            id: from_optional_bytes("receipt.id", receipt.id)?,
+            is: receipt.is,
+            len: receipt.len,
+            nonce: from_optional_bytes("receipt.nonce", receipt.nonce)?,
+            param1: receipt.param1,
+            param2: receipt.param2,
+            pc: receipt.pc,
+            ptr: receipt.ptr,
+            ra: receipt.ra,
+            rb: receipt.rb,
+            rc: receipt.rc,
+            rd: receipt.rd,
+            reason: receipt.reason,
+            receipt_type,
+            recipient: from_optional_bytes(
+                "receipt.recipient",
+                receipt.recipient,
+            )?,
+            result: receipt.result,
+            sender: from_optional_bytes("receipt.sender", receipt.sender)?,
+            sub_id: from_optional_bytes("receipt.sub_id", receipt.sub_id)?,
+            to: from_optional_bytes("receipt.to", receipt.to)?,
+            to_address: from_optional_bytes(
+                "receipt.to_address",
+                receipt.to_address,
+            )?,
+            val: receipt.val,
+        })
+    }
+}
+
+// ------------------------------------------------------------------------
+// UTXOs
+// ------------------------------------------------------------------------
+
+impl From<&Utxo> for proto::Utxo {
+    fn from(utxo: &Utxo) -> Self {
+        Self {
+            utxo_id: Some((&utxo.utxo_id).into()),
+            sender: utxo.sender.as_ref().map(|sender| sender.0.to_vec()),
+            recipient: utxo.recipient.as_ref().map(|to| to.0.to_vec()),
+            nonce: utxo.nonce.as_ref().map(|nonce| nonce.0.to_vec()),
+            data: utxo.data.clone(),
+            amount: utxo.amount,
+            tx_id: utxo.tx_id.0.to_vec(),
+        }
+    }
+}
+
+impl TryFrom<proto::Utxo> for Utxo {
+    type Error = ProtobufError;
+
+    fn try_from(utxo: proto::Utxo) -> Result<Self, Self::Error> {
+        Ok(Self {
+            utxo_id: required("utxo.utxo_id", utxo.utxo_id)?.try_into()?,
+            sender: from_optional_bytes("utxo.sender", utxo.sender)?,
+            recipient: from_optional_bytes("utxo.recipient", utxo.recipient)?,
+            nonce: from_optional_bytes("utxo.nonce", utxo.nonce)?,
+            data: utxo.data,
+            amount: utxo.amount,
+            tx_id: from_bytes("utxo.tx_id", &utxo.tx_id)?,
+        })
+    }
+}
+
+// ------------------------------------------------------------------------
+// Logs
+// ------------------------------------------------------------------------
+
+impl From<&Log> for proto::Log {
+    fn from(log: &Log) -> Self {
+        let kind = match log.clone() {
+            Log::WithoutData {
+                id,
+                ra,
+                rb,
+                rc,
+                rd,
+                pc,
+                is,
+            } => proto::log::Kind::WithoutData(proto::LogWithoutData {
+                id: id.0.to_vec(),
+                ra,
+                rb,
+                rc,
+                rd,
+                pc,
+                is,
+            }),
+            Log::WithData {
+                id,
+                ra,
+                rb,
+                ptr,
+                len,
+                digest,
+                pc,
+                is,
+                data,
+            } => proto::log::Kind::WithData(proto::LogWithData {
+                id: id.0.to_vec(),
+                ra,
+                rb,
+                ptr,
+                len,
+                digest: digest.0.to_vec(),
+                pc,
+                is,
+                data,
+            }),
+        };
+        Self { kind: Some(kind) }
+    }
+}
+
+impl TryFrom<proto::Log> for Log {
+    type Error = ProtobufError;
+
+    fn try_from(log: proto::Log) -> Result<Self, Self::Error> {
+        Ok(match required("log.kind", log.kind)? {
+            proto::log::Kind::WithoutData(log) => Log::WithoutData {
+                id: from_bytes("log.id", &log.id)?,
+                ra: log.ra,
+                rb: log.rb,
+                rc: log.rc,
+                rd: log.rd,
+                pc: log.pc,
+                is: log.is,
+            },
+            proto::log::Kind::WithData(log) => Log::WithData {
+                id: from_bytes("log.id", &log.id)?,
+                ra: log.ra,
+                rb: log.rb,
+                ptr: log.ptr,
+                len: log.len,
+                digest: from_bytes("log.digest", &log.digest)?,
+                pc: log.pc,
+                is: log.is,
+                data: log.data,
+            },
+        })
+    }
+}
@@ -0,0 +1,30 @@
+use std::array::TryFromSliceError;
+
+use displaydoc::Display as DisplayDoc;
+use thiserror::Error;
+
+/// Errors converting protobuf messages into streamable types.
+#[derive(Error, DisplayDoc, Debug)]
+pub enum ProtobufError {
+    /// Missing required field `{0}`
+    MissingField(&'static str),
+
+    /// Invalid byte length for `{field}`
+    InvalidBytes {
+        field: &'static str,
+        #[source]
+        source: TryFromSliceError,
+    },
+
+    /// Value of `{0}` is out of range
+    OutOfRange(&'static str),
+
+    /// Unknown value {value} for enum `{field}`
+    UnknownEnumValue { field: &'static str, value: i32 },
+
+    /// Expected a {0} payload
+    UnexpectedPayload(&'static str),
+
+    /// {0} has no protobuf representation
+    Unsupported(&'static str),
+}
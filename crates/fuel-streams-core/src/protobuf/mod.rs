@@ -0,0 +1,244 @@
+//! Protobuf representation of the streamable types, as published with
+//! `SerializationType::Protobuf`.
+//!
+//! The messages are generated from `proto/fuel_streams.proto`, which
+//! consumers in other languages can compile with their own protobuf tooling.
+
+mod conversions;
+mod error;
+
+pub use error::ProtobufError;
+
+/// Messages generated from `proto/fuel_streams.proto`.
+#[allow(clippy::all)]
+pub mod proto {
+    include!(concat!(env!("OUT_DIR"), "/fuel_streams.rs"));
+}
+
+/// Implements `StreamEncoder` for a streamable type carried in the
+/// `StreamData` payload variant of the same name.
+macro_rules! impl_protobuf_stream_encoder {
+    ($type:ident) => {
+        impl $crate::StreamEncoder for $type {
+            fn to_protobuf(
+                &self,
+            ) -> Option<$crate::protobuf::proto::stream_data::Payload> {
+                Some($crate::protobuf::proto::stream_data::Payload::$type(
+                    self.into(),
+                ))
+            }
+
+            fn from_protobuf(
+                payload: $crate::protobuf::proto::stream_data::Payload,
+            ) -> Result<Self, $crate::protobuf::ProtobufError> {
+                match payload {
+                    $crate::protobuf::proto::stream_data::Payload::$type(
+                        message,
+                    ) => message.try_into(),
+                    _ => {
+                        Err($crate::protobuf::ProtobufError::UnexpectedPayload(
+                            stringify!($type),
+                        ))
+                    }
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use impl_protobuf_stream_encoder;
+
+#[cfg(test)]
+mod tests {
+    use fuel_data_parser::{
+        CompressionRegistry,
+        DataParser,
+        EnvelopeHeader,
+        SerializationType,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{prelude::*, StreamData};
+
+    async fn assert_roundtrip<T: StreamEncoder>(payload: T) {
+        let data = StreamData::new("test.subject", payload);
+        let strategy = CompressionRegistry::global().get("zstd").unwrap();
+        let encoded = DataParser::default()
+            .with_compression_strategy(&strategy)
+            .encode_protobuf(&data.to_protobuf().unwrap())
+            .await
+            .unwrap();
+
+        let (header, _) = EnvelopeHeader::parse(&encoded).unwrap().unwrap();
+        assert_eq!(header.serialization_type, SerializationType::Protobuf);
+
+        let decoded = T::decode_raw(&encoded).await.unwrap();
+        assert_eq!(decoded.subject, data.subject);
+        assert_eq!(decoded.metadata, data.metadata);
+        assert_eq!(
+            serde_json::to_value(&decoded.payload).unwrap(),
+            serde_json::to_value(&data.payload).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_legacy_timestamp_migrates_into_metadata() {
+        let message = proto::StreamData {
+            subject: "test.subject".to_string(),
+            timestamp: "2024-11-05T10:20:30Z".to_string(),
+            payload: MockBlock::build(1).to_protobuf(),
+            metadata: None,
+        };
+
+        let data = StreamData::<Block>::from_protobuf(message).unwrap();
+        assert_eq!(data.metadata.timestamp_ms, 1_730_802_030_000);
+        assert_eq!(data.metadata.publisher_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_block_roundtrip() {
+        let mut block = MockBlock::build(42);
+        block.transaction_ids = vec![[1u8; 32].into(), [2u8; 32].into()];
+        assert_roundtrip(block.clone()).await;
+
+        block.consensus =
+            Consensus::PoAConsensus(PoAConsensus::new([3u8; 64].into()));
+        assert_roundtrip(block).await;
+    }
+
+    #[tokio::test]
+    async fn test_transaction_roundtrip() {
+        let tx = FuelCoreTransaction::default_test_tx();
+        let receipts = [FuelCoreReceipt::ret([4u8; 32].into(), 1, 2, 3)];
+        let transaction = Transaction::new(
+            &[5u8; 32].into(),
+            &tx,
+            &TransactionStatus::Success,
+            &FuelCoreAssetId::default(),
+            &receipts,
+        );
+        assert!(transaction.policies.is_some());
+        assert_roundtrip(transaction).await;
+
+        let upgrade = Transaction {
+            kind: TransactionKind::Upgrade,
+            is_upgrade: true,
+            upgrade_purpose: Some(FuelCoreUpgradePurpose::StateTransition {
+                root: [6u8; 32].into(),
+            }),
+            tx_pointer: Some(FuelCoreTxPointer::new(7.into(), 8)),
+            input_asset_ids: Some(vec![]),
+            ..Default::default()
+        };
+        assert_roundtrip(upgrade).await;
+    }
+
+    #[tokio::test]
+    async fn test_inputs_and_outputs_roundtrip() {
+        let inputs = [
+            Input::Contract(InputContract {
+                tx_pointer: FuelCoreTxPointer::new(1.into(), 2).into(),
+                utxo_id: FuelCoreUtxoId::new([3u8; 32].into(), 4).into(),
+                ..Default::default()
+            }),
+            Input::Coin(InputCoin {
+                amount: 100,
+                predicate: HexString(vec![1, 2, 3]),
+                witness_index: 1,
+                ..Default::default()
+            }),
+            Input::Message(InputMessage {
+                data: HexString(vec![4, 5]),
+                predicate_length: 3,
+                ..Default::default()
+            }),
+        ];
+        for input in inputs {
+            assert_roundtrip(input).await;
+        }
+
+        let outputs = [
+            Output::Coin(CoinOutput {
+                amount: 1,
+                ..Default::default()
+            }),
+            Output::Contract(OutputContract {
+                input_index: 2,
+                ..Default::default()
+            }),
+            Output::Change(ChangeOutput::default()),
+            Output::Variable(VariableOutput::default()),
+            Output::ContractCreated(ContractCreated {
+                contract_id: [7u8; 32].into(),
+                state_root: [8u8; 32].into(),
+            }),
+        ];
+        for output in outputs {
+            assert_roundtrip(output).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_receipt_log_and_utxo_roundtrip() {
+        let receipt = FuelCoreReceipt::log_data(
+            [1u8; 32].into(),
+            2,
+            3,
+            4,
+            5,
+            6,
+            vec![7, 8, 9],
+        );
+        assert_roundtrip(Receipt::from(&receipt)).await;
+        assert_roundtrip(Log::from(receipt)).await;
+
+        assert_roundtrip(Utxo {
+            utxo_id: FuelCoreUtxoId::new([1u8; 32].into(), 2).into(),
+            sender: Some([3u8; 32].into()),
+            data: Some(vec![]),
+            amount: Some(4),
+            ..Default::default()
+        })
+        .await;
+    }
+
+    #[test]
+    fn test_invalid_messages_are_rejected() {
+        let utxo = proto::Utxo {
+            utxo_id: None,
+            ..Default::default()
+        };
+        assert!(matches!(
+            Utxo::try_from(utxo),
+            Err(ProtobufError::MissingField("utxo.utxo_id"))
+        ));
+
+        let output = proto::Output {
+            kind: Some(proto::output::Kind::Coin(proto::CoinOutput {
+                asset_id: vec![0u8; 31],
+                ..Default::default()
+            })),
+        };
+        assert!(matches!(
+            Output::try_from(output),
+            Err(ProtobufError::InvalidBytes { .. })
+        ));
+
+        let receipt = proto::Receipt {
+            receipt_type: 99,
+            ..Default::default()
+        };
+        assert!(matches!(
+            Receipt::try_from(receipt),
+            Err(ProtobufError::UnknownEnumValue { value: 99, .. })
+        ));
+
+        let payload =
+            proto::stream_data::Payload::Log(proto::Log { kind: None });
+        assert!(matches!(
+            Block::from_protobuf(payload),
+            Err(ProtobufError::UnexpectedPayload("Block"))
+        ));
+    }
+}
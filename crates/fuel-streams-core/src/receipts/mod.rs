@@ -4,9 +4,9 @@ pub mod types;
 pub use subjects::*;
 
 use super::types::*;
-use crate::{StreamEncoder, Streamable};
+use crate::{protobuf::impl_protobuf_stream_encoder, Streamable};
 
-impl StreamEncoder for Receipt {}
+impl_protobuf_stream_encoder!(Receipt);
 impl Streamable for Receipt {
     const NAME: &'static str = "receipts";
     const WILDCARD_LIST: &'static [&'static str] =
@@ -1,6 +1,8 @@
 use crate::types::*;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct Receipt {
     pub amount: Option<u64>,
@@ -68,7 +70,9 @@ impl From<&FuelCoreReceipt> for Receipt {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema,
+)]
 pub enum ReceiptType {
     Burn,
     Call,
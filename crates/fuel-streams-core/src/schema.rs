@@ -0,0 +1,137 @@
+//! JSON Schema documents of the payloads published on every stream.
+//!
+//! The schemas describe `StreamData` as published with
+//! `SerializationType::Json`, and are committed under `schemas/` so that
+//! changes to the wire format show up in review. Run `make update-schemas`
+//! to regenerate them after changing a streamable type.
+
+use schemars::{gen::SchemaSettings, schema::RootSchema, JsonSchema};
+
+use crate::{prelude::*, primitive_types::unprefixed_bytes32_schema};
+
+/// Generates the schema of the `StreamData` published on the stream of `S`.
+pub fn stream_schema<S: Streamable + JsonSchema>() -> RootSchema {
+    SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<StreamData<S>>()
+}
+
+/// Generates the schemas of all streams, keyed by stream name.
+pub fn stream_schemas() -> Vec<(&'static str, RootSchema)> {
+    vec![
+        (Block::NAME, stream_schema::<Block>()),
+        (Transaction::NAME, stream_schema::<Transaction>()),
+        (Input::NAME, stream_schema::<Input>()),
+        (Output::NAME, stream_schema::<Output>()),
+        (Receipt::NAME, stream_schema::<Receipt>()),
+        (Log::NAME, stream_schema::<Log>()),
+        (Utxo::NAME, stream_schema::<Utxo>()),
+    ]
+}
+
+// The fuel-core types below don't implement `JsonSchema`, so they are
+// described by mirrors of their JSON shape.
+
+/// The policies of a transaction
+#[derive(JsonSchema)]
+#[schemars(rename = "Policies")]
+#[allow(dead_code)]
+pub(crate) struct PoliciesSchema {
+    /// The policies that are set, e.g. `"Tip | MaxFee"`
+    bits: String,
+    /// The values of the tip, witness limit, maturity and max fee policies
+    values: [u64; 4],
+}
+
+/// The block height and index of a transaction
+#[derive(JsonSchema)]
+#[schemars(rename = "FuelCoreTxPointer")]
+#[allow(dead_code)]
+pub(crate) struct TxPointerSchema {
+    block_height: u32,
+    tx_index: u16,
+}
+
+/// What an upgrade transaction upgrades
+#[derive(JsonSchema)]
+#[schemars(rename = "UpgradePurpose")]
+#[allow(dead_code)]
+pub(crate) enum UpgradePurposeSchema {
+    ConsensusParameters {
+        witness_index: u16,
+        #[schemars(schema_with = "unprefixed_bytes32_schema")]
+        checksum: String,
+    },
+    StateTransition {
+        #[schemars(schema_with = "unprefixed_bytes32_schema")]
+        root: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn schema_path(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("schemas")
+            .join(format!("{name}.json"))
+    }
+
+    #[test]
+    fn test_schemas_match_snapshots() {
+        let update = std::env::var_os("UPDATE_SCHEMAS").is_some();
+
+        for (name, schema) in stream_schemas() {
+            let path = schema_path(name);
+            let schema = serde_json::to_value(&schema).unwrap();
+
+            if update {
+                let json = serde_json::to_string_pretty(&schema).unwrap();
+                std::fs::write(&path, json + "\n").unwrap();
+                continue;
+            }
+
+            let snapshot = std::fs::read_to_string(&path).unwrap_or_default();
+            let snapshot: serde_json::Value =
+                serde_json::from_str(&snapshot).unwrap_or_default();
+            assert_eq!(
+                schema, snapshot,
+                "schema of {name} changed, run `make update-schemas`"
+            );
+        }
+    }
+
+    #[test]
+    fn test_payloads_match_schemas() {
+        let schema =
+            serde_json::to_value(stream_schema::<Transaction>()).unwrap();
+        let definitions = &schema["definitions"];
+
+        let transaction = Transaction {
+            tx_pointer: Some(FuelCoreTxPointer::new(1.into(), 2)),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&transaction).unwrap();
+        let properties = definitions["Transaction"]["properties"]
+            .as_object()
+            .unwrap();
+        for key in json.as_object().unwrap().keys() {
+            assert!(properties.contains_key(key), "{key} is not in schema");
+        }
+
+        let pointer = definitions["FuelCoreTxPointer"]["properties"]
+            .as_object()
+            .unwrap();
+        for key in json["txPointer"].as_object().unwrap().keys() {
+            assert!(pointer.contains_key(key), "{key} is not in schema");
+        }
+
+        let bytes32 = &definitions["Bytes32"]["pattern"];
+        assert_eq!(bytes32, "^0x[0-9a-fA-F]{64}$");
+    }
+}
@@ -0,0 +1,712 @@
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, MutexGuard, OnceLock},
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+use futures::{channel::mpsc, future, stream, StreamExt};
+
+use super::{
+    AckableMessage,
+    AckableMessageStream,
+    DeliverFrom,
+    DurableConfig,
+    MessageAcker,
+    MessageStream,
+    PublishMode,
+    SequencedMessage,
+    StreamBackend,
+    StreamMessage,
+    StreamPublisher,
+    StreamSubscriber,
+};
+use crate::stream::StreamError;
+
+static IN_MEMORY_STREAMS: OnceLock<Mutex<HashMap<String, InMemoryBackend>>> =
+    OnceLock::new();
+
+/// Backend keeping payloads in the memory of the process, for tests and
+/// local development without a NATS server.
+///
+/// Subjects are matched with the semantics of NATS, where `*` matches a
+/// single token and `>` matches one or more trailing tokens. Like
+/// `JetStreamKv`, every subject holds a single payload, which publishing on
+/// the subject replaces. Payloads are never expired, and the ids of
+/// deduplicated messages are remembered for as long as the backend lives,
+/// rather than for a duplicate window.
+///
+/// Durable consumers keep track of the messages they acknowledged, and
+/// redeliver the ones they were asked to, but they neither redeliver
+/// messages left unacknowledged for longer than `ack_wait`, other than when
+/// subscribing again, nor limit the number of pending messages.
+#[derive(Debug, Clone)]
+pub struct InMemoryBackend {
+    name: String,
+    state: Arc<Mutex<InMemoryState>>,
+}
+
+#[derive(Debug, Default)]
+struct InMemoryState {
+    /// Published messages, the sequence of each being its index plus one
+    messages: Vec<StreamMessage>,
+    /// Time each message was published at
+    published_at: Vec<SystemTime>,
+    /// Sequence of the payload each subject holds
+    latest: HashMap<String, u64>,
+    /// Ids of the deduplicated messages published so far
+    message_ids: HashSet<String>,
+    subscribers: Vec<InMemorySubscriber>,
+    consumers: HashMap<String, InMemoryConsumer>,
+}
+
+#[derive(Debug)]
+struct InMemorySubscriber {
+    filter_subjects: Vec<String>,
+    sender: mpsc::UnboundedSender<SequencedMessage>,
+}
+
+#[derive(Debug)]
+struct InMemoryConsumer {
+    filter_subjects: Vec<String>,
+    /// Sequence of the first message delivered to the consumer
+    start_sequence: u64,
+    max_deliver: Option<u64>,
+    /// Sequences of the messages acknowledged or terminated
+    acked: HashSet<u64>,
+    /// Number of deliveries of each message
+    deliveries: HashMap<u64, u64>,
+}
+
+impl InMemoryConsumer {
+    /// Counts a delivery of the message with the given sequence, unless it
+    /// mustn't be delivered anymore.
+    fn deliver(&mut self, sequence: u64) -> Option<u64> {
+        if sequence < self.start_sequence || self.acked.contains(&sequence) {
+            return None;
+        }
+        let delivered = self.deliveries.entry(sequence).or_default();
+        if self.max_deliver.is_some_and(|max| *delivered >= max) {
+            return None;
+        }
+        *delivered += 1;
+        Some(*delivered)
+    }
+}
+
+impl InMemoryBackend {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            state: Default::default(),
+        }
+    }
+
+    /// Returns the backend of the stream named `name`, which is shared by
+    /// every in-memory client of the process.
+    pub fn get_or_create(name: &str) -> Self {
+        let streams = IN_MEMORY_STREAMS.get_or_init(Default::default);
+        streams
+            .lock()
+            .expect("In-memory streams lock must not be poisoned")
+            .entry(name.to_string())
+            .or_insert_with(|| Self::new(name))
+            .clone()
+    }
+
+    fn state(&self) -> MutexGuard<'_, InMemoryState> {
+        self.state
+            .lock()
+            .expect("In-memory stream lock must not be poisoned")
+    }
+
+    fn listen(
+        &self,
+        filter_subjects: Vec<String>,
+        deliver_from: DeliverFrom,
+    ) -> Result<MessageStream, StreamError> {
+        let (messages, _) =
+            self.listen_sequenced(filter_subjects, deliver_from)?;
+        Ok(messages.map(|(_, message)| Ok(message)).boxed())
+    }
+
+    /// Returns the messages matching `filter_subjects` along with their
+    /// sequence, and a sender to deliver them again.
+    fn listen_sequenced(
+        &self,
+        filter_subjects: Vec<String>,
+        deliver_from: DeliverFrom,
+    ) -> Result<
+        (
+            stream::BoxStream<'static, SequencedMessage>,
+            mpsc::UnboundedSender<SequencedMessage>,
+        ),
+        StreamError,
+    > {
+        let mut state = self.state();
+        let history = state.stored(&filter_subjects, deliver_from)?;
+
+        // Registered under the same lock as the history is read, so that no
+        // message is missed or delivered twice
+        let (sender, receiver) = mpsc::unbounded();
+        state.subscribers.push(InMemorySubscriber {
+            filter_subjects,
+            sender: sender.clone(),
+        });
+
+        Ok((stream::iter(history).chain(receiver).boxed(), sender))
+    }
+}
+
+impl InMemoryState {
+    /// Published messages along with their sequence, without the ones whose
+    /// subject holds a later payload.
+    fn retained(&self) -> impl Iterator<Item = (&StreamMessage, u64)> {
+        self.messages
+            .iter()
+            .zip(1u64..)
+            .filter(|(message, sequence)| {
+                self.latest.get(&message.subject) == Some(sequence)
+            })
+    }
+
+    /// The retained messages matching `filter_subjects` to deliver from
+    /// `deliver_from`, along with their sequence.
+    fn stored(
+        &self,
+        filter_subjects: &[String],
+        deliver_from: DeliverFrom,
+    ) -> Result<Vec<SequencedMessage>, StreamError> {
+        let start_sequence =
+            self.start_sequence(filter_subjects, deliver_from)?;
+        Ok(self
+            .retained()
+            .filter(|(message, sequence)| {
+                *sequence >= start_sequence
+                    && matches_any(filter_subjects, &message.subject)
+            })
+            .map(|(message, sequence)| (sequence, message.clone()))
+            .collect())
+    }
+
+    /// Sequence of the first message matching `filter_subjects` to deliver
+    /// from `deliver_from`.
+    fn start_sequence(
+        &self,
+        filter_subjects: &[String],
+        deliver_from: DeliverFrom,
+    ) -> Result<u64, StreamError> {
+        let next_sequence = self.messages.len() as u64 + 1;
+        Ok(match deliver_from {
+            // Every subject holds a single payload, which is also its last
+            DeliverFrom::All | DeliverFrom::LastPerSubject => 1,
+            DeliverFrom::Last => self
+                .retained()
+                .filter(|(message, _)| {
+                    matches_any(filter_subjects, &message.subject)
+                })
+                .last()
+                .map(|(_, sequence)| sequence)
+                .unwrap_or(next_sequence),
+            DeliverFrom::New => next_sequence,
+            DeliverFrom::Sequence(start_sequence) => start_sequence,
+            DeliverFrom::StartTime(start_time) => self
+                .published_at
+                .iter()
+                .zip(1u64..)
+                .find(|(published_at, _)| **published_at >= start_time)
+                .map(|(_, sequence)| sequence)
+                .unwrap_or(next_sequence),
+            DeliverFrom::BlockHeight(height) => {
+                return Err(StreamError::UnresolvedBlockHeight(height))
+            }
+        })
+    }
+}
+
+/// Acknowledges a message delivered to a durable consumer of an
+/// `InMemoryBackend`.
+#[derive(Debug)]
+struct InMemoryAcker {
+    backend: InMemoryBackend,
+    consumer: String,
+    message: SequencedMessage,
+    sender: mpsc::UnboundedSender<SequencedMessage>,
+}
+
+#[async_trait]
+impl MessageAcker for InMemoryAcker {
+    async fn ack(&self) -> Result<(), StreamError> {
+        if let Some(consumer) =
+            self.backend.state().consumers.get_mut(&self.consumer)
+        {
+            consumer.acked.insert(self.message.0);
+        }
+        Ok(())
+    }
+
+    async fn nak(&self, delay: Option<Duration>) -> Result<(), StreamError> {
+        // The subscription may have been dropped, in which case the message
+        // is delivered again when subscribing under the same name
+        let sender = self.sender.clone();
+        let message = self.message.clone();
+        match delay {
+            // Like JetStream, the message is redelivered later without
+            // holding up the consumer in the meantime
+            Some(delay) => {
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    let _ = sender.unbounded_send(message);
+                });
+            }
+            None => {
+                let _ = sender.unbounded_send(message);
+            }
+        }
+        Ok(())
+    }
+
+    async fn term(&self) -> Result<(), StreamError> {
+        self.ack().await
+    }
+}
+
+#[async_trait]
+impl StreamPublisher for InMemoryBackend {
+    async fn publish(
+        &self,
+        message: StreamMessage,
+        mode: &PublishMode,
+    ) -> Result<(), StreamError> {
+        let mut state = self.state();
+        match mode {
+            PublishMode::Deduplicated { message_id } => {
+                if !state.message_ids.insert(message_id.clone()) {
+                    return Ok(());
+                }
+            }
+            PublishMode::CreateIfAbsent => {
+                if state.latest.contains_key(&message.subject) {
+                    return Ok(());
+                }
+            }
+            PublishMode::Upsert => {}
+        }
+
+        let subject = message.subject.clone();
+        let sequence = state.messages.len() as u64 + 1;
+        // Subscribers whose stream was dropped are removed along the way
+        state.subscribers.retain(|subscriber| {
+            if !matches_any(&subscriber.filter_subjects, &subject) {
+                return !subscriber.sender.is_closed();
+            }
+            subscriber
+                .sender
+                .unbounded_send((sequence, message.clone()))
+                .is_ok()
+        });
+        state.messages.push(message);
+        state.published_at.push(SystemTime::now());
+        state.latest.insert(subject, sequence);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StreamSubscriber for InMemoryBackend {
+    async fn watch(
+        &self,
+        wildcard: &str,
+    ) -> Result<MessageStream, StreamError> {
+        self.listen(vec![wildcard.to_string()], DeliverFrom::New)
+    }
+
+    async fn subscribe(
+        &self,
+        filter_subjects: &[String],
+        deliver_from: DeliverFrom,
+    ) -> Result<MessageStream, StreamError> {
+        self.listen(filter_subjects.to_vec(), deliver_from)
+    }
+
+    async fn subscribe_stored(
+        &self,
+        filter_subjects: &[String],
+        deliver_from: DeliverFrom,
+    ) -> Result<MessageStream, StreamError> {
+        let stored = self.state().stored(filter_subjects, deliver_from)?;
+        Ok(stream::iter(stored).map(|(_, message)| Ok(message)).boxed())
+    }
+
+    async fn subscribe_durable(
+        &self,
+        filter_subjects: &[String],
+        config: &DurableConfig,
+    ) -> Result<AckableMessageStream, StreamError> {
+        // Like JetStream, a consumer keeps the filters it was created with
+        let filter_subjects = {
+            let mut state = self.state();
+            if !state.consumers.contains_key(&config.name) {
+                let consumer = InMemoryConsumer {
+                    filter_subjects: filter_subjects.to_vec(),
+                    start_sequence: state
+                        .start_sequence(filter_subjects, config.deliver_from)?,
+                    max_deliver: config.max_deliver,
+                    acked: HashSet::new(),
+                    deliveries: HashMap::new(),
+                };
+                state.consumers.insert(config.name.clone(), consumer);
+            }
+            state.consumers[&config.name].filter_subjects.clone()
+        };
+
+        // Acknowledged messages are skipped when delivered, so that the
+        // consumer resumes after them
+        let (messages, sender) =
+            self.listen_sequenced(filter_subjects, DeliverFrom::All)?;
+        let backend = self.clone();
+        let name = config.name.clone();
+        Ok(messages
+            .filter_map(move |(sequence, message)| {
+                let delivered = backend
+                    .state()
+                    .consumers
+                    .get_mut(&name)
+                    .and_then(|consumer| consumer.deliver(sequence));
+                let message = delivered.map(|delivered| {
+                    Ok(AckableMessage {
+                        message: message.clone(),
+                        delivered,
+                        acker: Arc::new(InMemoryAcker {
+                            backend: backend.clone(),
+                            consumer: name.clone(),
+                            message: (sequence, message),
+                            sender: sender.clone(),
+                        }),
+                    })
+                });
+                future::ready(message)
+            })
+            .boxed())
+    }
+
+    async fn fetch(
+        &self,
+        filter_subjects: &[String],
+        from_sequence: u64,
+        limit: usize,
+    ) -> Result<Vec<SequencedMessage>, StreamError> {
+        Ok(self
+            .state()
+            .retained()
+            .filter(|(message, sequence)| {
+                *sequence >= from_sequence
+                    && matches_any(filter_subjects, &message.subject)
+            })
+            .take(limit)
+            .map(|(message, sequence)| (sequence, message.clone()))
+            .collect())
+    }
+
+    async fn first_sequence(
+        &self,
+        wildcard: &str,
+    ) -> Result<Option<u64>, StreamError> {
+        Ok(self
+            .state()
+            .retained()
+            .find(|(message, _)| subject_matches(wildcard, &message.subject))
+            .map(|(_, sequence)| sequence))
+    }
+
+    async fn last_published(
+        &self,
+        wildcard: &str,
+    ) -> Result<Option<StreamMessage>, StreamError> {
+        Ok(self
+            .state()
+            .retained()
+            .filter(|(message, _)| subject_matches(wildcard, &message.subject))
+            .last()
+            .map(|(message, _)| message.clone()))
+    }
+}
+
+impl StreamBackend for InMemoryBackend {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Whether `subject` matches the NATS subject `filter`, in which `*` matches
+/// a single token and `>` matches one or more trailing tokens.
+pub fn subject_matches(filter: &str, subject: &str) -> bool {
+    let mut filter_tokens = filter.split('.');
+    let mut subject_tokens = subject.split('.');
+    loop {
+        match (filter_tokens.next(), subject_tokens.next()) {
+            (Some(">"), Some(_)) => return true,
+            (Some("*"), Some(_)) => continue,
+            (Some(filter), Some(subject)) if filter == subject => continue,
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+fn matches_any(filter_subjects: &[String], subject: &str) -> bool {
+    filter_subjects.is_empty()
+        || filter_subjects
+            .iter()
+            .any(|filter| subject_matches(filter, subject))
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn message(subject: &str, payload: &[u8]) -> StreamMessage {
+        StreamMessage {
+            subject: subject.to_string(),
+            payload: Bytes::copy_from_slice(payload),
+            headers: Default::default(),
+        }
+    }
+
+    fn deduplicated(subject: &str) -> PublishMode {
+        PublishMode::Deduplicated {
+            message_id: subject.to_string(),
+        }
+    }
+
+    async fn backend_with(subjects: &[&str]) -> InMemoryBackend {
+        let backend = InMemoryBackend::new("test");
+        for subject in subjects {
+            backend
+                .publish(
+                    message(subject, subject.as_bytes()),
+                    &deduplicated(subject),
+                )
+                .await
+                .unwrap();
+        }
+        backend
+    }
+
+    async fn next_subjects(
+        messages: &mut MessageStream,
+        count: usize,
+    ) -> Vec<String> {
+        let mut subjects = vec![];
+        for _ in 0..count {
+            let message = messages.next().await.unwrap().unwrap();
+            subjects.push(message.subject);
+        }
+        subjects
+    }
+
+    #[test]
+    fn test_subject_matches() {
+        assert!(subject_matches("blocks.1.2", "blocks.1.2"));
+        assert!(subject_matches("blocks.*.2", "blocks.1.2"));
+        assert!(subject_matches("blocks.*.*", "blocks.1.2"));
+        assert!(subject_matches("blocks.>", "blocks.1.2"));
+        assert!(subject_matches(">", "blocks"));
+
+        assert!(!subject_matches("blocks.*", "blocks.1.2"));
+        assert!(!subject_matches("blocks.*.*.*", "blocks.1.2"));
+        assert!(!subject_matches("blocks.>", "blocks"));
+        assert!(!subject_matches("blocks.2.*", "blocks.1.2"));
+        assert!(!subject_matches("transactions.>", "blocks.1.2"));
+    }
+
+    #[tokio::test]
+    async fn test_deliver_policies() {
+        let backend =
+            backend_with(&["blocks.1", "transactions.1", "blocks.2"]).await;
+        let filters = vec!["blocks.*".to_string()];
+
+        let mut all =
+            backend.subscribe(&filters, DeliverFrom::All).await.unwrap();
+        assert_eq!(next_subjects(&mut all, 2).await, ["blocks.1", "blocks.2"]);
+
+        let mut last = backend
+            .subscribe(&filters, DeliverFrom::Last)
+            .await
+            .unwrap();
+        assert_eq!(next_subjects(&mut last, 1).await, ["blocks.2"]);
+
+        let mut from_sequence = backend
+            .subscribe(&filters, DeliverFrom::Sequence(2))
+            .await
+            .unwrap();
+        assert_eq!(next_subjects(&mut from_sequence, 1).await, ["blocks.2"]);
+
+        let mut unfiltered =
+            backend.subscribe(&[], DeliverFrom::All).await.unwrap();
+        assert_eq!(
+            next_subjects(&mut unfiltered, 3).await,
+            ["blocks.1", "transactions.1", "blocks.2"]
+        );
+
+        let mut new =
+            backend.subscribe(&filters, DeliverFrom::New).await.unwrap();
+        backend
+            .publish(
+                message("transactions.2", b""),
+                &deduplicated("transactions.2"),
+            )
+            .await
+            .unwrap();
+        backend
+            .publish(message("blocks.3", b""), &deduplicated("blocks.3"))
+            .await
+            .unwrap();
+        assert_eq!(next_subjects(&mut new, 1).await, ["blocks.3"]);
+        assert_eq!(next_subjects(&mut all, 1).await, ["blocks.3"]);
+
+        let start_time = SystemTime::now();
+        backend
+            .publish(message("blocks.4", b""), &deduplicated("blocks.4"))
+            .await
+            .unwrap();
+        let mut from_time = backend
+            .subscribe(&filters, DeliverFrom::StartTime(start_time))
+            .await
+            .unwrap();
+        assert_eq!(next_subjects(&mut from_time, 1).await, ["blocks.4"]);
+    }
+
+    #[tokio::test]
+    async fn test_first_sequence_and_unresolved_block_heights() {
+        let backend =
+            backend_with(&["blocks.a.1", "blocks.a.2", "blocks.b.2"]).await;
+
+        let first = backend.first_sequence("blocks.*.2").await.unwrap();
+        assert_eq!(first, Some(2));
+        let first = backend.first_sequence("blocks.*.3").await.unwrap();
+        assert_eq!(first, None);
+
+        let result = backend.subscribe(&[], DeliverFrom::BlockHeight(2)).await;
+        assert!(matches!(result, Err(StreamError::UnresolvedBlockHeight(2))));
+    }
+
+    #[tokio::test]
+    async fn test_watch_and_last_published() {
+        let backend = backend_with(&["blocks.1", "blocks.2"]).await;
+        let mut watch = backend.watch("blocks.>").await.unwrap();
+
+        backend
+            .publish(message("blocks.3", b"new"), &deduplicated("blocks.3"))
+            .await
+            .unwrap();
+        backend
+            .publish(message("blocks.1", b"dup"), &deduplicated("blocks.1"))
+            .await
+            .unwrap();
+        backend
+            .publish(message("blocks.4", b""), &deduplicated("blocks.4"))
+            .await
+            .unwrap();
+        assert_eq!(
+            next_subjects(&mut watch, 2).await,
+            ["blocks.3", "blocks.4"]
+        );
+
+        let last = backend.last_published("blocks.3").await.unwrap().unwrap();
+        assert_eq!(last.payload, &b"new"[..]);
+        let first = backend.last_published("blocks.1").await.unwrap().unwrap();
+        assert_eq!(first.payload, &b"blocks.1"[..]);
+        assert!(backend.last_published("logs.>").await.unwrap().is_none());
+
+        // upserting replaces the payload, which is delivered again
+        let mode = PublishMode::Upsert;
+        backend
+            .publish(message("blocks.1", b"new"), &mode)
+            .await
+            .unwrap();
+        assert_eq!(next_subjects(&mut watch, 1).await, ["blocks.1"]);
+        let first = backend.last_published("blocks.1").await.unwrap().unwrap();
+        assert_eq!(first.payload, &b"new"[..]);
+        let mut all = backend.subscribe(&[], DeliverFrom::All).await.unwrap();
+        assert_eq!(
+            next_subjects(&mut all, 4).await,
+            ["blocks.2", "blocks.3", "blocks.4", "blocks.1"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_durable_consumers_resume_after_acked_messages() {
+        let backend = backend_with(&["blocks.1", "blocks.2", "blocks.3"]).await;
+        let filters = vec!["blocks.*".to_string()];
+        let config = DurableConfig::new("consumer").with_max_deliver(Some(2));
+
+        let mut durable =
+            backend.subscribe_durable(&filters, &config).await.unwrap();
+        let first = durable.next().await.unwrap().unwrap();
+        assert_eq!(first.message.subject, "blocks.1");
+        first.acker.ack().await.unwrap();
+        let second = durable.next().await.unwrap().unwrap();
+        assert_eq!(second.message.subject, "blocks.2");
+        second.acker.nak(None).await.unwrap();
+        drop(durable);
+
+        // Unacknowledged messages are delivered again, at most `max_deliver`
+        // times
+        let mut resumed =
+            backend.subscribe_durable(&filters, &config).await.unwrap();
+        let second = resumed.next().await.unwrap().unwrap();
+        assert_eq!(second.message.subject, "blocks.2");
+        assert_eq!(second.delivered, 2);
+        let third = resumed.next().await.unwrap().unwrap();
+        assert_eq!(third.message.subject, "blocks.3");
+        assert_eq!(third.delivered, 1);
+        third.acker.ack().await.unwrap();
+
+        backend
+            .publish(message("blocks.4", b""), &deduplicated("blocks.4"))
+            .await
+            .unwrap();
+        let fourth = resumed.next().await.unwrap().unwrap();
+        assert_eq!(fourth.message.subject, "blocks.4");
+    }
+
+    #[tokio::test]
+    async fn test_nak_with_delay_redelivers_later_without_blocking() {
+        let backend = backend_with(&["blocks.1", "blocks.2"]).await;
+        let filters = vec!["blocks.*".to_string()];
+        let config = DurableConfig::new("consumer");
+
+        let mut durable =
+            backend.subscribe_durable(&filters, &config).await.unwrap();
+        let first = durable.next().await.unwrap().unwrap();
+        let delay = Duration::from_millis(200);
+        tokio::time::timeout(delay / 2, first.acker.nak(Some(delay)))
+            .await
+            .expect("nak must not wait for the delay")
+            .unwrap();
+
+        let second = durable.next().await.unwrap().unwrap();
+        assert_eq!(second.message.subject, "blocks.2");
+        let redelivered = durable.next().await.unwrap().unwrap();
+        assert_eq!(redelivered.message.subject, "blocks.1");
+        assert_eq!(redelivered.delivered, 2);
+    }
+
+    #[test]
+    fn test_backends_are_shared_by_name() {
+        let backend = InMemoryBackend::get_or_create("shared");
+        let same = InMemoryBackend::get_or_create("shared");
+        assert!(Arc::ptr_eq(&backend.state, &same.state));
+        let other = InMemoryBackend::get_or_create("other");
+        assert!(!Arc::ptr_eq(&backend.state, &other.state));
+    }
+}
@@ -0,0 +1,617 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use async_nats::{
+    header,
+    jetstream::{
+        self,
+        consumer::AckPolicy,
+        context::PublishErrorKind,
+        kv,
+        stream::{DirectGetErrorKind, LastRawMessageErrorKind, State},
+        AckKind,
+    },
+    HeaderMap,
+    RequestErrorKind,
+};
+use async_trait::async_trait;
+use futures::{
+    future,
+    stream::{self, FuturesUnordered},
+    StreamExt,
+    TryStreamExt,
+};
+
+use super::{
+    AckableMessage,
+    AckableMessageStream,
+    DeliverFrom,
+    DurableConfig,
+    MessageAcker,
+    MessageStream,
+    PublishMode,
+    SequencedMessage,
+    StreamBackend,
+    StreamMessage,
+    StreamPublisher,
+    StreamSubscriber,
+};
+use crate::{
+    nats::types::*,
+    prelude::NatsClient,
+    stream::{to_nats_limit, RetentionConfig, StreamError, StreamHeaders},
+};
+
+/// Backend storing payloads in a NATS JetStream Key-Value store, with one
+/// bucket per streamable type and subjects as keys.
+///
+/// The bucket is created with the given `RetentionConfig`, or the default
+/// one, and the limits of an existing bucket are only updated to match a
+/// given one.
+#[derive(Debug, Clone)]
+pub struct JetStreamKv {
+    store: kv::Store,
+    jetstream: JetStreamContext,
+    pages: Arc<Mutex<HashMap<PageKey, PageConsumer>>>,
+}
+
+/// How long the consumer of a page of `fetch` is kept for the next page.
+const PAGE_CONSUMER_IDLE: Duration = Duration::from_secs(30);
+
+/// The filter subjects of a page of `fetch`, and the sequence it starts at.
+type PageKey = (Vec<String>, u64);
+
+/// A consumer that delivered a page of `fetch`, positioned at the next one.
+#[derive(Debug)]
+struct PageConsumer {
+    consumer: NatsConsumer<PullConsumerConfig>,
+    used_at: Instant,
+}
+
+impl JetStreamKv {
+    pub async fn new(
+        client: &NatsClient,
+        name: &str,
+        retention: Option<&RetentionConfig>,
+    ) -> Result<Self, StreamError> {
+        let bucket_name = client.namespace.stream_name(name);
+        let defaults = RetentionConfig::default();
+        let creation = retention.unwrap_or(&defaults);
+        let store = client
+            .get_or_create_kv_store(kv::Config {
+                bucket: bucket_name,
+                storage: creation.storage.into(),
+                history: i64::from(creation.history),
+                compression: true,
+                max_age: creation.max_age.unwrap_or_default(),
+                max_bytes: to_nats_limit(creation.max_bytes),
+                num_replicas: creation.replicas,
+                ..Default::default()
+            })
+            .await?;
+
+        // The KV config has no limit on the number of messages nor duplicate
+        // window, and an existing bucket keeps the limits it was created with
+        if let Some(retention) = retention {
+            let mut config = store.stream.cached_info().config.clone();
+            retention.apply_limits(&mut config);
+            if config != store.stream.cached_info().config {
+                client.jetstream()?.update_stream(&config).await?;
+            }
+            if config.storage != retention.storage.into() {
+                tracing::warn!(
+                    "Stream {} keeps its {:?} storage, which can't be changed",
+                    config.name,
+                    config.storage
+                );
+            }
+        }
+
+        Ok(Self {
+            store,
+            jetstream: client.jetstream()?.clone(),
+            pages: Default::default(),
+        })
+    }
+
+    pub async fn get_consumers_and_state(
+        &self,
+    ) -> Result<(String, Vec<String>, State), RequestErrorKind> {
+        let mut consumers = vec![];
+        while let Ok(Some(consumer)) =
+            self.store.stream.consumer_names().try_next().await
+        {
+            consumers.push(consumer);
+        }
+
+        let state = self.store.stream.cached_info().state;
+        let stream_name = self.store.stream_name.to_string();
+        Ok((stream_name, consumers, state))
+    }
+
+    pub async fn create_consumer(
+        &self,
+        config: PullConsumerConfig,
+    ) -> Result<NatsConsumer<PullConsumerConfig>, StreamError> {
+        let config = Self::prefix_filter_subjects(config);
+        Ok(self.store.stream.create_consumer(config).await?)
+    }
+
+    pub fn store(&self) -> &kv::Store {
+        &self.store
+    }
+
+    fn pages(&self) -> MutexGuard<'_, HashMap<PageKey, PageConsumer>> {
+        self.pages
+            .lock()
+            .expect("Page consumers lock must not be poisoned")
+    }
+
+    /// Fetches up to `limit` messages from `consumer`, only the ones already
+    /// stored, without waiting for new ones.
+    async fn fetch_page(
+        &self,
+        consumer: &NatsConsumer<PullConsumerConfig>,
+        limit: usize,
+    ) -> Result<Vec<SequencedMessage>, StreamError> {
+        let mut batch = consumer
+            .fetch()
+            .max_messages(limit)
+            .messages()
+            .await
+            .map_err(|error| StreamError::Fetch(error.into()))?;
+        let mut messages = vec![];
+        while let Some(message) =
+            batch.try_next().await.map_err(StreamError::Fetch)?
+        {
+            let sequence =
+                message.info().map_err(StreamError::Fetch)?.stream_sequence;
+            messages.push((sequence, self.stream_message(&message)));
+        }
+        Ok(messages)
+    }
+
+    fn prefix_filter_subjects(
+        mut config: PullConsumerConfig,
+    ) -> PullConsumerConfig {
+        config.filter_subjects = config
+            .filter_subjects
+            .iter()
+            .map(Self::prefix_filter_subject)
+            .collect();
+        config
+    }
+
+    fn prefix_filter_subject(subject: impl Into<String>) -> String {
+        // An hack to ensure we keep the KV namespace when reading
+        // from the KV store's stream
+        let subject = subject.into();
+        format!("$KV.*.{subject}")
+    }
+
+    /// Turns a subject of the KV store's stream back into the key it was
+    /// published with.
+    fn strip_subject_prefix(&self, subject: &str) -> String {
+        subject
+            .strip_prefix(&self.store.prefix)
+            .unwrap_or(subject)
+            .to_string()
+    }
+
+    fn stream_message(&self, message: &jetstream::Message) -> StreamMessage {
+        StreamMessage {
+            subject: self.strip_subject_prefix(message.subject.as_str()),
+            payload: message.payload.clone(),
+            headers: stream_headers(message.headers.as_ref()),
+        }
+    }
+}
+
+fn header_map(headers: &StreamHeaders) -> HeaderMap {
+    let mut header_map = HeaderMap::new();
+    for (name, value) in headers.to_pairs() {
+        header_map.insert(name, value);
+    }
+    header_map
+}
+
+fn stream_headers(header_map: Option<&HeaderMap>) -> StreamHeaders {
+    let Some(header_map) = header_map else {
+        return StreamHeaders::default();
+    };
+    StreamHeaders::from_pairs(header_map.iter().filter_map(|(name, values)| {
+        Some((name.as_ref(), values.first()?.as_str()))
+    }))
+}
+
+/// Acknowledges a message delivered to a durable consumer of JetStream.
+#[derive(Debug)]
+struct JetStreamAcker(jetstream::Message);
+
+impl JetStreamAcker {
+    async fn ack_with(&self, kind: AckKind) -> Result<(), StreamError> {
+        self.0.ack_with(kind).await.map_err(StreamError::Ack)
+    }
+}
+
+#[async_trait]
+impl MessageAcker for JetStreamAcker {
+    async fn ack(&self) -> Result<(), StreamError> {
+        self.ack_with(AckKind::Ack).await
+    }
+
+    async fn nak(&self, delay: Option<Duration>) -> Result<(), StreamError> {
+        self.ack_with(AckKind::Nak(delay)).await
+    }
+
+    async fn term(&self) -> Result<(), StreamError> {
+        self.ack_with(AckKind::Term).await
+    }
+}
+
+impl TryFrom<DeliverFrom> for DeliverPolicy {
+    type Error = StreamError;
+
+    fn try_from(deliver_from: DeliverFrom) -> Result<Self, Self::Error> {
+        Ok(match deliver_from {
+            DeliverFrom::All => DeliverPolicy::All,
+            DeliverFrom::Last => DeliverPolicy::Last,
+            DeliverFrom::LastPerSubject => DeliverPolicy::LastPerSubject,
+            DeliverFrom::New => DeliverPolicy::New,
+            DeliverFrom::Sequence(start_sequence) => {
+                DeliverPolicy::ByStartSequence { start_sequence }
+            }
+            DeliverFrom::StartTime(start_time) => DeliverPolicy::ByStartTime {
+                start_time: start_time.into(),
+            },
+            DeliverFrom::BlockHeight(height) => {
+                return Err(StreamError::UnresolvedBlockHeight(height))
+            }
+        })
+    }
+}
+
+impl JetStreamKv {
+    /// Sends a message to JetStream, returning the future of its ack.
+    async fn send(
+        &self,
+        message: StreamMessage,
+        mode: &PublishMode,
+    ) -> Result<impl Future<Output = Result<(), StreamError>>, StreamError>
+    {
+        // Published on the stream of the store, like `kv::Store::put` does,
+        // so that JetStream can deduplicate it by its message id and keep
+        // its headers
+        let key_prefix =
+            self.store.put_prefix.as_ref().unwrap_or(&self.store.prefix);
+        let mut headers = header_map(&message.headers);
+        match mode {
+            PublishMode::Deduplicated { message_id } => {
+                headers.insert(header::NATS_MESSAGE_ID, message_id.as_str());
+            }
+            // Like `kv::Store::create`, without the extra round trip it
+            // takes to tell an existing payload from another error
+            PublishMode::CreateIfAbsent => {
+                headers
+                    .insert(header::NATS_EXPECTED_LAST_SUBJECT_SEQUENCE, "0");
+            }
+            PublishMode::Upsert => {}
+        }
+        let create_if_absent = matches!(mode, PublishMode::CreateIfAbsent);
+
+        let subject = message.subject;
+        let ack = self
+            .jetstream
+            .publish_with_headers(
+                format!("{key_prefix}{subject}"),
+                headers,
+                message.payload,
+            )
+            .await
+            .map_err(|source| StreamError::PublishFailed {
+                subject_name: subject.clone(),
+                source,
+            })?;
+
+        // Duplicates are acknowledged too, without being stored again, while
+        // payloads of subjects already holding one are rejected
+        Ok(async move {
+            match ack.await {
+                Ok(_) => Ok(()),
+                Err(error)
+                    if create_if_absent
+                        && error.kind()
+                            == PublishErrorKind::WrongLastSequence =>
+                {
+                    Ok(())
+                }
+                Err(source) => Err(StreamError::PublishFailed {
+                    subject_name: subject,
+                    source,
+                }),
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl StreamPublisher for JetStreamKv {
+    async fn publish(
+        &self,
+        message: StreamMessage,
+        mode: &PublishMode,
+    ) -> Result<(), StreamError> {
+        self.send(message, mode).await?.await
+    }
+
+    async fn publish_batch(
+        &self,
+        messages: Vec<(StreamMessage, PublishMode)>,
+        window: usize,
+    ) -> Result<(), StreamError> {
+        let mut acks = FuturesUnordered::new();
+        for (message, mode) in messages {
+            if acks.len() >= window.max(1) {
+                acks.next().await.transpose()?;
+            }
+            let ack = self.send(message, &mode).await?;
+            acks.push(ack);
+        }
+        while let Some(ack) = acks.next().await {
+            ack?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StreamSubscriber for JetStreamKv {
+    async fn watch(
+        &self,
+        wildcard: &str,
+    ) -> Result<MessageStream, StreamError> {
+        let watch = self.store.watch(wildcard).await?;
+        Ok(watch
+            .map(|entry| {
+                entry
+                    .map(|entry| StreamMessage {
+                        subject: entry.key,
+                        payload: entry.value,
+                        // Entries don't carry the headers of their message
+                        headers: StreamHeaders::default(),
+                    })
+                    .map_err(StreamError::from)
+            })
+            .boxed())
+    }
+
+    async fn subscribe(
+        &self,
+        filter_subjects: &[String],
+        deliver_from: DeliverFrom,
+    ) -> Result<MessageStream, StreamError> {
+        let consumer = self
+            .create_consumer(PullConsumerConfig {
+                filter_subjects: filter_subjects.to_vec(),
+                deliver_policy: deliver_from.try_into()?,
+                ack_policy: AckPolicy::None,
+                ..Default::default()
+            })
+            .await?;
+
+        let backend = self.clone();
+        Ok(consumer
+            .messages()
+            .await?
+            .map(move |message| Ok(backend.stream_message(&message?)))
+            .boxed())
+    }
+
+    async fn subscribe_stored(
+        &self,
+        filter_subjects: &[String],
+        deliver_from: DeliverFrom,
+    ) -> Result<MessageStream, StreamError> {
+        // Read before creating the consumer, so that any message after it
+        // was stored after subscribing
+        let last_sequence =
+            self.store.stream.clone().info().await?.state.last_sequence;
+        let consumer = self
+            .create_consumer(PullConsumerConfig {
+                filter_subjects: filter_subjects.to_vec(),
+                deliver_policy: deliver_from.try_into()?,
+                ack_policy: AckPolicy::None,
+                ..Default::default()
+            })
+            .await?;
+        if consumer.cached_info().num_pending == 0 {
+            return Ok(stream::empty().boxed());
+        }
+
+        // Ends with the last stored message matching the filters, which no
+        // other one is pending after, or with the last one of the stream
+        let backend = self.clone();
+        Ok(consumer
+            .messages()
+            .await?
+            .scan(false, move |done, message| {
+                if *done {
+                    return future::ready(None);
+                }
+                let message =
+                    message.map_err(StreamError::from).and_then(|message| {
+                        let info =
+                            message.info().map_err(StreamError::Fetch)?;
+                        *done = info.pending == 0
+                            || info.stream_sequence >= last_sequence;
+                        Ok(backend.stream_message(&message))
+                    });
+                future::ready(Some(message))
+            })
+            .boxed())
+    }
+
+    async fn subscribe_durable(
+        &self,
+        filter_subjects: &[String],
+        config: &DurableConfig,
+    ) -> Result<AckableMessageStream, StreamError> {
+        let consumer = self
+            .store
+            .stream
+            .get_or_create_consumer(
+                &config.name,
+                Self::prefix_filter_subjects(PullConsumerConfig {
+                    durable_name: Some(config.name.clone()),
+                    filter_subjects: filter_subjects.to_vec(),
+                    deliver_policy: config.deliver_from.try_into()?,
+                    ack_policy: AckPolicy::Explicit,
+                    ack_wait: config.ack_wait,
+                    max_deliver: to_nats_limit(config.max_deliver),
+                    max_ack_pending: to_nats_limit(config.max_ack_pending),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+
+        let backend = self.clone();
+        Ok(consumer
+            .messages()
+            .await?
+            .map(move |message| {
+                let message = message?;
+                let delivered = message
+                    .info()
+                    .map(|info| info.delivered.unsigned_abs())
+                    .unwrap_or(1);
+                Ok(AckableMessage {
+                    message: backend.stream_message(&message),
+                    delivered,
+                    acker: Arc::new(JetStreamAcker(message)),
+                })
+            })
+            .boxed())
+    }
+
+    async fn fetch(
+        &self,
+        filter_subjects: &[String],
+        from_sequence: u64,
+        limit: usize,
+    ) -> Result<Vec<SequencedMessage>, StreamError> {
+        let start_sequence = from_sequence.max(1);
+        let key = (filter_subjects.to_vec(), start_sequence);
+
+        // The page following a previous one reuses its consumer, which
+        // delivers from where that page ended, unless it expired meanwhile
+        let cached = self.pages().remove(&key);
+        let reused = match cached {
+            Some(page) => self
+                .fetch_page(&page.consumer, limit)
+                .await
+                .ok()
+                .map(|messages| (page.consumer, messages)),
+            None => None,
+        };
+        let (consumer, messages) = match reused {
+            Some(reused) => reused,
+            None => {
+                let consumer = self
+                    .create_consumer(PullConsumerConfig {
+                        filter_subjects: filter_subjects.to_vec(),
+                        deliver_policy: DeliverPolicy::ByStartSequence {
+                            start_sequence,
+                        },
+                        ack_policy: AckPolicy::None,
+                        inactive_threshold: PAGE_CONSUMER_IDLE,
+                        ..Default::default()
+                    })
+                    .await?;
+                let messages = self.fetch_page(&consumer, limit).await?;
+                (consumer, messages)
+            }
+        };
+
+        // A full page is likely to be followed by another one
+        if let Some((sequence, _)) =
+            messages.last().filter(|_| messages.len() == limit)
+        {
+            let mut pages = self.pages();
+            pages.retain(|_, page| page.used_at.elapsed() < PAGE_CONSUMER_IDLE);
+            pages.insert(
+                (filter_subjects.to_vec(), sequence + 1),
+                PageConsumer {
+                    consumer,
+                    used_at: Instant::now(),
+                },
+            );
+        }
+        Ok(messages)
+    }
+
+    async fn first_sequence(
+        &self,
+        wildcard: &str,
+    ) -> Result<Option<u64>, StreamError> {
+        let subject_name = &Self::prefix_filter_subject(wildcard);
+
+        let message = self
+            .store
+            .stream
+            .direct_get_first_for_subject(subject_name)
+            .await;
+
+        match message {
+            Ok(message) => {
+                let message = jetstream::message::StreamMessage::try_from(
+                    message.message,
+                )?;
+                Ok(Some(message.sequence))
+            }
+            Err(error) => match &error.kind() {
+                DirectGetErrorKind::NotFound => Ok(None),
+                _ => Err(error.into()),
+            },
+        }
+    }
+
+    async fn last_published(
+        &self,
+        wildcard: &str,
+    ) -> Result<Option<StreamMessage>, StreamError> {
+        let subject_name = &Self::prefix_filter_subject(wildcard);
+
+        let message = self
+            .store
+            .stream
+            .get_last_raw_message_by_subject(subject_name)
+            .await;
+
+        match message {
+            Ok(message) => Ok(Some(StreamMessage {
+                subject: self.strip_subject_prefix(message.subject.as_str()),
+                payload: message.payload,
+                headers: stream_headers(Some(&message.headers)),
+            })),
+            Err(error) => match &error.kind() {
+                LastRawMessageErrorKind::NoMessageFound => Ok(None),
+                _ => Err(error.into()),
+            },
+        }
+    }
+}
+
+impl StreamBackend for JetStreamKv {
+    fn name(&self) -> &str {
+        self.store.stream_name.as_str()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
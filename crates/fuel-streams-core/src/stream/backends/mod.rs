@@ -0,0 +1,270 @@
+//! Transports a `Stream` can publish to and subscribe from.
+//!
+//! The traits here only deal in subjects, headers and encoded payloads, so
+//! that they don't depend on NATS. `JetStreamKv` is the backend used in production,
+//! and `InMemoryBackend`, behind the `in-memory` feature, one for tests and
+//! local development.
+
+#[cfg(feature = "in-memory")]
+mod in_memory;
+mod jetstream_kv;
+
+use std::{
+    any::Any,
+    fmt::Debug,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+#[cfg(feature = "in-memory")]
+pub use in_memory::*;
+pub use jetstream_kv::*;
+
+use super::{error::StreamError, headers::StreamHeaders};
+
+/// An encoded payload along with the subject it was published on and its
+/// headers.
+///
+/// The payload is shared with the buffer it was received in, so that it can
+/// be decoded without copying it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamMessage {
+    pub subject: String,
+    pub payload: Bytes,
+    pub headers: StreamHeaders,
+}
+
+/// A message along with its backend sequence.
+pub type SequencedMessage = (u64, StreamMessage);
+
+/// Messages yielded by a subscription to a backend.
+pub type MessageStream = BoxStream<'static, Result<StreamMessage, StreamError>>;
+
+/// Where a subscription starts delivering messages from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DeliverFrom {
+    /// Every message still retained by the backend
+    #[default]
+    All,
+    /// The last message published on any of the subjects
+    Last,
+    /// The last message published on each of the subjects
+    LastPerSubject,
+    /// Only the messages published after subscribing
+    New,
+    /// The message with the given backend sequence, and the ones after it
+    Sequence(u64),
+    /// The first message stored by the backend at or after the given time,
+    /// and the ones after it
+    StartTime(SystemTime),
+    /// The first message of the block at the given height, and the ones
+    /// after it. Backends only deliver from sequences, so it is resolved to
+    /// one by `Stream::resolve_deliver_from` before subscribing.
+    BlockHeight(u32),
+}
+
+/// Settings of a durable consumer, which keeps track of the messages it
+/// acknowledged, so that subscribing again under the same name resumes
+/// after the last of them.
+///
+/// Only the name is used once the consumer exists, its other settings and
+/// filter subjects being the ones it was created with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DurableConfig {
+    /// Name of the consumer, shared by the subscriptions resuming it
+    pub name: String,
+    /// Where the consumer starts from when it is created
+    pub deliver_from: DeliverFrom,
+    /// How long a message can stay unacknowledged before being redelivered
+    pub ack_wait: Duration,
+    /// Number of times a message is delivered at most, unlimited if `None`
+    pub max_deliver: Option<u64>,
+    /// Number of messages delivered and not yet acknowledged at most,
+    /// unlimited if `None`
+    pub max_ack_pending: Option<u64>,
+}
+
+impl DurableConfig {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            deliver_from: DeliverFrom::All,
+            ack_wait: Duration::from_secs(30),
+            max_deliver: None,
+            max_ack_pending: Some(1000),
+        }
+    }
+
+    pub fn with_deliver_from(self, deliver_from: DeliverFrom) -> Self {
+        Self {
+            deliver_from,
+            ..self
+        }
+    }
+
+    pub fn with_ack_wait(self, ack_wait: Duration) -> Self {
+        Self { ack_wait, ..self }
+    }
+
+    pub fn with_max_deliver(self, max_deliver: Option<u64>) -> Self {
+        Self {
+            max_deliver,
+            ..self
+        }
+    }
+
+    pub fn with_max_ack_pending(self, max_ack_pending: Option<u64>) -> Self {
+        Self {
+            max_ack_pending,
+            ..self
+        }
+    }
+}
+
+/// Acknowledges a message delivered to a durable consumer.
+#[async_trait]
+pub trait MessageAcker: Debug + Send + Sync {
+    /// Marks the message as processed, so that it isn't delivered again.
+    async fn ack(&self) -> Result<(), StreamError>;
+
+    /// Asks for the message to be delivered again, after `delay` if any.
+    async fn nak(&self, delay: Option<Duration>) -> Result<(), StreamError>;
+
+    /// Marks the message as one that can't be processed, so that it isn't
+    /// delivered again.
+    async fn term(&self) -> Result<(), StreamError>;
+}
+
+/// A message delivered to a durable consumer, to be acknowledged once
+/// processed.
+#[derive(Debug, Clone)]
+pub struct AckableMessage {
+    pub message: StreamMessage,
+    /// Number of times the message was delivered, starting at 1
+    pub delivered: u64,
+    pub acker: Arc<dyn MessageAcker>,
+}
+
+/// Messages yielded by a subscription of a durable consumer.
+pub type AckableMessageStream =
+    BoxStream<'static, Result<AckableMessage, StreamError>>;
+
+/// How a payload is published on a subject, which holds a single payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PublishMode {
+    /// Publishing a payload with the id of one published within the
+    /// duplicate window of the stream is a no-op, so that the same payload
+    /// published twice is only stored and delivered once. Once the window
+    /// is over, which is at most the max age of the stream, the id is
+    /// forgotten and publishing it again stores the payload again.
+    Deduplicated { message_id: String },
+    /// Publishing a payload on a subject already holding one is a no-op, for
+    /// as long as the subject holds it.
+    CreateIfAbsent,
+    /// The payload replaces the one the subject holds, for mutable entities.
+    Upsert,
+}
+
+/// Publishes encoded payloads.
+#[async_trait]
+pub trait StreamPublisher: Debug + Send + Sync {
+    /// Publishes a message on its subject, replacing the one it holds unless
+    /// deduplicated by `mode`.
+    async fn publish(
+        &self,
+        message: StreamMessage,
+        mode: &PublishMode,
+    ) -> Result<(), StreamError>;
+
+    /// Publishes messages in order, with up to `window` of them awaiting
+    /// their ack at once, rather than waiting for the ack of each before
+    /// publishing the next.
+    ///
+    /// Backends publish them one at a time unless they implement it.
+    async fn publish_batch(
+        &self,
+        messages: Vec<(StreamMessage, PublishMode)>,
+        _window: usize,
+    ) -> Result<(), StreamError> {
+        for (message, mode) in messages {
+            self.publish(message, &mode).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads encoded payloads, as they are published or from the history.
+#[async_trait]
+pub trait StreamSubscriber: Debug + Send + Sync {
+    /// Watches the messages published on the subjects matching `wildcard`.
+    async fn watch(&self, wildcard: &str)
+        -> Result<MessageStream, StreamError>;
+
+    /// Subscribes to the messages published on the subjects matching any of
+    /// `filter_subjects`, or on any subject if there are none, starting from
+    /// `deliver_from`.
+    async fn subscribe(
+        &self,
+        filter_subjects: &[String],
+        deliver_from: DeliverFrom,
+    ) -> Result<MessageStream, StreamError>;
+
+    /// Subscribes like `subscribe`, to the messages stored when subscribing
+    /// only, so that the subscription ends after the last of them instead of
+    /// waiting for new ones.
+    async fn subscribe_stored(
+        &self,
+        filter_subjects: &[String],
+        deliver_from: DeliverFrom,
+    ) -> Result<MessageStream, StreamError>;
+
+    /// Subscribes to the messages published on the subjects matching any of
+    /// `filter_subjects` through the durable consumer of `config`, which is
+    /// created if it doesn't exist yet.
+    ///
+    /// Backends don't support durable consumers unless they implement it.
+    async fn subscribe_durable(
+        &self,
+        _filter_subjects: &[String],
+        _config: &DurableConfig,
+    ) -> Result<AckableMessageStream, StreamError> {
+        Err(StreamError::DurableUnsupported)
+    }
+
+    /// Returns up to `limit` of the messages published on the subjects
+    /// matching any of `filter_subjects`, or on any subject if there are
+    /// none, starting from the sequence `from_sequence`. Messages come with
+    /// their sequence, so that the next ones can be fetched after them.
+    async fn fetch(
+        &self,
+        filter_subjects: &[String],
+        from_sequence: u64,
+        limit: usize,
+    ) -> Result<Vec<SequencedMessage>, StreamError>;
+
+    /// Returns the sequence of the first message published on the subjects
+    /// matching `wildcard`, if any.
+    async fn first_sequence(
+        &self,
+        wildcard: &str,
+    ) -> Result<Option<u64>, StreamError>;
+
+    /// Returns the last message published on the subjects matching
+    /// `wildcard`, if any.
+    async fn last_published(
+        &self,
+        wildcard: &str,
+    ) -> Result<Option<StreamMessage>, StreamError>;
+}
+
+/// A backend able to both publish and subscribe, as held by `Stream`.
+pub trait StreamBackend: StreamPublisher + StreamSubscriber {
+    /// Name of the underlying stream, e.g. for health reports.
+    fn name(&self) -> &str;
+
+    /// Allows backend specific APIs to be reached through a `Stream`.
+    fn as_any(&self) -> &dyn Any;
+}
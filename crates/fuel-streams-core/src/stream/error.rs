@@ -1,22 +1,115 @@
 use async_nats::{
     error,
     jetstream::{
-        consumer::StreamErrorKind,
-        context::{CreateKeyValueErrorKind, CreateStreamErrorKind},
-        kv::{CreateError, CreateErrorKind, PutError, WatchErrorKind},
-        stream::{ConsumerErrorKind, LastRawMessageErrorKind},
+        consumer::{pull::MessagesError, StreamErrorKind},
+        context::{
+            CreateKeyValueErrorKind,
+            CreateStreamErrorKind,
+            PublishError,
+            RequestErrorKind,
+        },
+        kv::{CreateError, PutError, WatchErrorKind, WatcherError},
+        message::StreamMessageError,
+        stream::{
+            ConsumerErrorKind,
+            DirectGetErrorKind,
+            LastRawMessageErrorKind,
+        },
     },
 };
 use displaydoc::Display as DisplayDoc;
 use thiserror::Error;
 
+use crate::{nats::NatsError, protobuf::ProtobufError};
+
+/// Errors of the data parser or protobuf conversion used by `StreamEncoder`.
+#[derive(Error, DisplayDoc, Debug)]
+pub enum EncodingError {
+    /// {0}
+    DataParser(#[from] fuel_data_parser::Error),
+
+    /// {0}
+    Protobuf(#[from] ProtobufError),
+
+    /// Payload schema version {0} is not supported
+    UnsupportedSchemaVersion(u16),
+
+    /// {0}
+    Signature(#[from] SignatureError),
+}
+
+/// Errors of signing published payloads or verifying their signature.
+#[derive(Error, DisplayDoc, Debug)]
+pub enum SignatureError {
+    /// Payload is not signed
+    Unsigned,
+
+    /// Signature header is truncated, only {0} bytes were read
+    Truncated(usize),
+
+    /// Invalid signature: {0}
+    Invalid(#[from] ed25519_dalek::SignatureError),
+
+    /// Key must be 32 hex encoded bytes
+    InvalidKey,
+}
+
+/// Failed to encode payload for subject `{subject}`
+#[derive(Error, DisplayDoc, Debug)]
+pub struct EncodeError {
+    pub subject: String,
+    #[source]
+    pub source: EncodingError,
+}
+
+/// Error decoding a published payload, which keeps the raw bytes around so
+/// that consumers can log, skip or dead-letter the message.
+#[derive(Error, Debug)]
+pub struct DecodeError {
+    /// The subject the payload was read from, if known
+    pub subject: Option<String>,
+    /// The raw bytes that failed to decode
+    pub payload: Vec<u8>,
+    #[source]
+    pub source: EncodingError,
+}
+
+impl DecodeError {
+    pub fn new(payload: &[u8], source: impl Into<EncodingError>) -> Self {
+        Self {
+            subject: None,
+            payload: payload.to_vec(),
+            source: source.into(),
+        }
+    }
+
+    pub fn with_subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Failed to decode payload of {} bytes",
+            self.payload.len()
+        )?;
+        match &self.subject {
+            Some(subject) => write!(f, " from subject `{subject}`"),
+            None => Ok(()),
+        }
+    }
+}
+
 #[derive(Error, DisplayDoc, Debug)]
 pub enum StreamError {
     /// Failed to publish to stream: {subject_name}
     PublishFailed {
         subject_name: String,
         #[source]
-        source: error::Error<CreateErrorKind>,
+        source: PublishError,
     },
 
     /// Failed to retrieve last published message from stream
@@ -42,4 +135,49 @@ pub enum StreamError {
 
     /// Failed to consume messages from stream
     ConsumerMessages(#[from] error::Error<StreamErrorKind>),
+
+    /// Failed to receive message from stream
+    ConsumerMessage(#[from] MessagesError),
+
+    /// Failed to receive entry from Key-Value Store
+    StoreWatch(#[from] WatcherError),
+
+    /// Operation is only supported by the JetStream KV backend
+    JetStreamRequired,
+
+    /// Backend doesn't support durable consumers
+    DurableUnsupported,
+
+    /// Stream `{stream}` can't deliver from a block height, as its subjects don't hold one
+    BlockHeightUnsupported { stream: &'static str },
+
+    /// No message of block {height} is retained by stream `{stream}`
+    BlockHeightNotFound { stream: &'static str, height: u32 },
+
+    /// Block height {0} must be resolved to a sequence before subscribing
+    UnresolvedBlockHeight(u32),
+
+    /// Failed to get the state of the stream
+    StreamInfo(#[from] error::Error<RequestErrorKind>),
+
+    /// Failed to fetch messages from stream
+    Fetch(#[source] async_nats::Error),
+
+    /// Failed to get message from stream
+    DirectGet(#[from] error::Error<DirectGetErrorKind>),
+
+    /// Invalid message received from stream
+    InvalidStreamMessage(#[from] StreamMessageError),
+
+    /// Failed to acknowledge message
+    Ack(#[source] async_nats::Error),
+
+    /// {0}
+    Nats(#[from] NatsError),
+
+    /// {0}
+    Encode(#[from] EncodeError),
+
+    /// {0}
+    Decode(#[from] DecodeError),
 }
@@ -0,0 +1,187 @@
+/// Serializations of payloads, as published in `CONTENT_TYPE_HEADER`.
+pub use fuel_data_parser::SerializationType;
+
+use super::{metadata::StreamMetadata, stream_encoding::StreamEncoder};
+
+/// Header holding the serialization of a payload, as
+/// `application/{serialization}`, e.g. `application/json`.
+pub const CONTENT_TYPE_HEADER: &str = "Content-Type";
+
+/// Header holding the name of the compression strategy of a payload, or
+/// `NO_COMPRESSION`.
+pub const COMPRESSION_HEADER: &str = "Fuel-Compression";
+
+/// Header holding the `StreamEncoder::SCHEMA_VERSION` of a payload.
+pub const SCHEMA_VERSION_HEADER: &str = "Fuel-Schema-Version";
+
+/// Header holding the id of the chain of the block a payload belongs to.
+pub const CHAIN_ID_HEADER: &str = "Fuel-Chain-Id";
+
+/// Header holding the height of the block a payload belongs to.
+pub const BLOCK_HEIGHT_HEADER: &str = "Fuel-Block-Height";
+
+/// Header holding the index of the transaction a payload belongs to within
+/// its block.
+pub const TX_INDEX_HEADER: &str = "Fuel-Tx-Index";
+
+/// Value of the compression header of uncompressed payloads.
+pub const NO_COMPRESSION: &str = "none";
+
+const CONTENT_TYPE_PREFIX: &str = "application/";
+
+/// Headers published along with every payload, so that consumers can route
+/// and filter payloads without decoding them.
+///
+/// Every header is optional, as payloads published before headers were
+/// added come without them, and so do the ones read from a `JetStreamKv`
+/// watch. Headers aren't covered by payload signatures.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamHeaders {
+    /// Serialization of the payload
+    pub serialization: Option<SerializationType>,
+    /// Name of the compression strategy of the payload, `NO_COMPRESSION` if
+    /// it isn't compressed
+    pub compression: Option<String>,
+    /// `StreamEncoder::SCHEMA_VERSION` of the payload
+    pub schema_version: Option<u16>,
+    /// Id of the chain of the block the payload belongs to
+    pub chain_id: Option<u64>,
+    /// Height of the block the payload belongs to
+    pub block_height: Option<u32>,
+    /// Index of the transaction the payload belongs to within its block
+    pub tx_index: Option<u32>,
+}
+
+impl StreamHeaders {
+    /// Sets the encoding headers of a payload of `S`, along with the block
+    /// headers of its `metadata`, keeping the other headers.
+    pub fn with_payload<S: StreamEncoder>(
+        mut self,
+        metadata: &StreamMetadata,
+    ) -> Self {
+        let parser = S::data_parser();
+        let compression = match parser.compression_strategy() {
+            Some(strategy) => strategy.name(),
+            None => NO_COMPRESSION,
+        };
+        self.serialization = Some(parser.serialization_type);
+        self.compression = Some(compression.to_string());
+        self.schema_version = Some(S::SCHEMA_VERSION);
+        self.chain_id = metadata.chain_id;
+        self.block_height = metadata.block_height;
+        self
+    }
+
+    pub fn with_tx_index(mut self, tx_index: Option<u32>) -> Self {
+        self.tx_index = tx_index;
+        self
+    }
+
+    /// Content type of the payload, as published in `CONTENT_TYPE_HEADER`.
+    pub fn content_type(&self) -> Option<String> {
+        self.serialization.map(|serialization| {
+            format!("{CONTENT_TYPE_PREFIX}{serialization}")
+        })
+    }
+
+    /// Names and values of the headers that are set.
+    pub fn to_pairs(&self) -> Vec<(&'static str, String)> {
+        [
+            (CONTENT_TYPE_HEADER, self.content_type()),
+            (COMPRESSION_HEADER, self.compression.clone()),
+            (
+                SCHEMA_VERSION_HEADER,
+                self.schema_version.map(|version| version.to_string()),
+            ),
+            (CHAIN_ID_HEADER, self.chain_id.map(|id| id.to_string())),
+            (
+                BLOCK_HEIGHT_HEADER,
+                self.block_height.map(|height| height.to_string()),
+            ),
+            (
+                TX_INDEX_HEADER,
+                self.tx_index.map(|index| index.to_string()),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value?)))
+        .collect()
+    }
+
+    /// Reads headers from their names and values, skipping unknown names
+    /// and values that can't be parsed.
+    pub fn from_pairs<'a>(
+        pairs: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Self {
+        let mut headers = Self::default();
+        for (name, value) in pairs {
+            match name {
+                CONTENT_TYPE_HEADER => {
+                    headers.serialization = value
+                        .strip_prefix(CONTENT_TYPE_PREFIX)
+                        .and_then(|serialization| serialization.parse().ok());
+                }
+                COMPRESSION_HEADER => {
+                    headers.compression = Some(value.to_string());
+                }
+                SCHEMA_VERSION_HEADER => {
+                    headers.schema_version = value.parse().ok();
+                }
+                CHAIN_ID_HEADER => headers.chain_id = value.parse().ok(),
+                BLOCK_HEIGHT_HEADER => {
+                    headers.block_height = value.parse().ok();
+                }
+                TX_INDEX_HEADER => headers.tx_index = value.parse().ok(),
+                _ => {}
+            }
+        }
+        headers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+    struct TestStreamable;
+
+    impl StreamEncoder for TestStreamable {}
+
+    #[test]
+    fn test_headers_round_trip_through_pairs() {
+        let metadata = StreamMetadata {
+            chain_id: Some(0),
+            block_height: Some(42),
+            ..StreamMetadata::now()
+        };
+        let headers = StreamHeaders::default()
+            .with_tx_index(Some(3))
+            .with_payload::<TestStreamable>(&metadata);
+        assert_eq!(headers.content_type().as_deref(), Some("application/json"));
+        assert_eq!(headers.compression.as_deref(), Some(NO_COMPRESSION));
+        assert_eq!(headers.block_height, Some(42));
+        assert_eq!(headers.tx_index, Some(3));
+
+        let pairs = headers.to_pairs();
+        assert_eq!(pairs.len(), 6);
+        let parsed = StreamHeaders::from_pairs(
+            pairs.iter().map(|(name, value)| (*name, value.as_str())),
+        );
+        assert_eq!(parsed, headers);
+    }
+
+    #[test]
+    fn test_unknown_and_invalid_headers_are_skipped() {
+        let headers = StreamHeaders::from_pairs([
+            ("Nats-Msg-Id", "blocks.1@1"),
+            (CONTENT_TYPE_HEADER, "text/plain"),
+            (BLOCK_HEIGHT_HEADER, "-1"),
+            (TX_INDEX_HEADER, "7"),
+        ]);
+        assert_eq!(headers, StreamHeaders::default().with_tx_index(Some(7)));
+        assert!(StreamHeaders::default().to_pairs().is_empty());
+    }
+}
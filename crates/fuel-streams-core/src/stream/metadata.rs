@@ -0,0 +1,106 @@
+use std::{
+    collections::HashMap,
+    sync::{atomic::AtomicU64, Arc, Mutex, OnceLock},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static PUBLISHER_ID: OnceLock<String> = OnceLock::new();
+
+static PUBLISHER_SEQUENCES: OnceLock<Mutex<HashMap<String, Arc<AtomicU64>>>> =
+    OnceLock::new();
+
+/// Id of this publisher instance, drawn at random the first time it is
+/// read, so that the sequences of different instances can be told apart.
+pub fn publisher_id() -> &'static str {
+    PUBLISHER_ID.get_or_init(|| {
+        use rand::Rng;
+        format!("publisher-{:08x}", rand::thread_rng().gen::<u32>())
+    })
+}
+
+/// Number of payloads this publisher instance published on the stream named
+/// `stream_name`, shared by every `Stream` of it, so that their sequences
+/// don't repeat.
+pub(crate) fn publisher_sequence(stream_name: &str) -> Arc<AtomicU64> {
+    let sequences = PUBLISHER_SEQUENCES.get_or_init(Default::default);
+    sequences
+        .lock()
+        .expect("Publisher sequences lock must not be poisoned")
+        .entry(stream_name.to_string())
+        .or_default()
+        .clone()
+}
+
+/// The block a published payload belongs to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockContext {
+    pub chain_id: u64,
+    pub height: u32,
+    /// Time of the block, in milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+}
+
+/// Metadata published along with every payload, so that consumers can
+/// measure latency and order payloads without looking into them.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamMetadata {
+    /// Time of publication, in milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    /// Id of the chain of the block the payload belongs to
+    pub chain_id: Option<u64>,
+    /// Height of the block the payload belongs to
+    pub block_height: Option<u32>,
+    /// Time of the block the payload belongs to, in milliseconds since the
+    /// Unix epoch
+    pub block_timestamp_ms: Option<u64>,
+    /// Id of the publisher instance that published the payload
+    pub publisher_id: Option<String>,
+    /// Position of the payload among the ones published on the same stream
+    /// by the same publisher instance, starting at 1. Payloads that failed
+    /// to publish or were deduplicated leave gaps.
+    pub sequence: Option<u64>,
+}
+
+impl StreamMetadata {
+    /// Metadata of a payload published now.
+    pub fn now() -> Self {
+        Self {
+            timestamp_ms: chrono::Utc::now().timestamp_millis() as u64,
+            ..Default::default()
+        }
+    }
+
+    /// Metadata of a payload published before the metadata was added, which
+    /// only carried an RFC 3339 timestamp.
+    pub(crate) fn from_rfc3339(timestamp: &str) -> Self {
+        let timestamp_ms = chrono::DateTime::parse_from_rfc3339(timestamp)
+            .map(|timestamp| timestamp.timestamp_millis() as u64)
+            .unwrap_or_default();
+        Self {
+            timestamp_ms,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_block(mut self, block: &BlockContext) -> Self {
+        self.chain_id = Some(block.chain_id);
+        self.block_height = Some(block.height);
+        self.block_timestamp_ms = Some(block.timestamp_ms);
+        self
+    }
+
+    pub fn with_publisher(
+        mut self,
+        publisher_id: impl Into<String>,
+        sequence: u64,
+    ) -> Self {
+        self.publisher_id = Some(publisher_id.into());
+        self.sequence = Some(sequence);
+        self
+    }
+}
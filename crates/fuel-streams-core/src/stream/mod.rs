@@ -1,7 +1,18 @@
+mod backends;
 mod error;
+mod headers;
+mod hex_bytes;
+mod metadata;
+mod retention;
+mod signing;
 mod stream_encoding;
 mod stream_impl;
 
+pub use backends::*;
 pub use error::*;
+pub use headers::*;
+pub use metadata::*;
+pub use retention::*;
+pub use signing::*;
 pub use stream_encoding::*;
 pub use stream_impl::*;
@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use async_nats::jetstream::stream::{self, StorageType};
+
+/// Number of seconds between two Fuel blocks
+pub const FUEL_BLOCK_TIME_SECS: u64 = 1;
+/// Number of blocks a stream retains by default
+pub const MAX_RETENTION_BLOCKS: u64 = 100;
+/// How long a stream remembers the ids of deduplicated messages by default
+pub const DUPLICATE_WINDOW_SECS: u64 = 120;
+
+/// Where the messages of a stream are stored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StreamStorage {
+    #[default]
+    File,
+    Memory,
+}
+
+impl From<StreamStorage> for StorageType {
+    fn from(storage: StreamStorage) -> Self {
+        match storage {
+            StreamStorage::File => StorageType::File,
+            StreamStorage::Memory => StorageType::Memory,
+        }
+    }
+}
+
+/// Retention settings of a stream.
+///
+/// Limits set to `None` are unlimited. By default, streams are stored in
+/// files, without replicas, and retain the payloads of the last
+/// `MAX_RETENTION_BLOCKS` blocks, one per subject, deduplicating the ones
+/// published again within `DUPLICATE_WINDOW_SECS`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use fuel_streams_core::prelude::*;
+///
+/// // An archive keeping every block, replicated over three servers
+/// let retention = RetentionConfig::default()
+///     .with_max_age(None)
+///     .with_replicas(3);
+/// let opts = NatsClientOpts::new("nats://localhost:4222")
+///     .with_stream_retention(Block::NAME, retention);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetentionConfig {
+    /// How long messages are kept
+    pub max_age: Option<Duration>,
+    /// Size in bytes the stream can grow to before old messages are removed
+    pub max_bytes: Option<u64>,
+    /// Number of messages kept before old ones are removed
+    pub max_messages: Option<u64>,
+    /// Number of servers the stream is replicated to
+    pub replicas: usize,
+    /// Where messages are stored
+    pub storage: StreamStorage,
+    /// Number of messages kept per subject, at most 64
+    pub history: u8,
+    /// How long the ids of deduplicated messages are remembered, at most
+    /// `max_age`
+    pub duplicate_window: Duration,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            max_age: Some(Duration::from_secs(
+                FUEL_BLOCK_TIME_SECS * MAX_RETENTION_BLOCKS,
+            )),
+            max_bytes: None,
+            max_messages: None,
+            replicas: 1,
+            storage: StreamStorage::default(),
+            history: 1,
+            duplicate_window: Duration::from_secs(DUPLICATE_WINDOW_SECS),
+        }
+    }
+}
+
+impl RetentionConfig {
+    pub fn with_max_age(self, max_age: Option<Duration>) -> Self {
+        Self { max_age, ..self }
+    }
+
+    pub fn with_max_bytes(self, max_bytes: Option<u64>) -> Self {
+        Self { max_bytes, ..self }
+    }
+
+    pub fn with_max_messages(self, max_messages: Option<u64>) -> Self {
+        Self {
+            max_messages,
+            ..self
+        }
+    }
+
+    pub fn with_replicas(self, replicas: usize) -> Self {
+        Self { replicas, ..self }
+    }
+
+    pub fn with_storage(self, storage: StreamStorage) -> Self {
+        Self { storage, ..self }
+    }
+
+    pub fn with_history(self, history: u8) -> Self {
+        Self { history, ..self }
+    }
+
+    pub fn with_duplicate_window(self, duplicate_window: Duration) -> Self {
+        Self {
+            duplicate_window,
+            ..self
+        }
+    }
+
+    /// Applies the limits to the configuration of a JetStream stream. The
+    /// storage is left unchanged, as it can't be changed once the stream
+    /// exists.
+    pub(crate) fn apply_limits(&self, config: &mut stream::Config) {
+        config.max_age = self.max_age.unwrap_or_default();
+        config.max_bytes = to_nats_limit(self.max_bytes);
+        config.max_messages = to_nats_limit(self.max_messages);
+        config.max_messages_per_subject = i64::from(self.history);
+        config.num_replicas = self.replicas;
+        // JetStream rejects duplicate windows longer than the max age
+        config.duplicate_window = match self.max_age {
+            Some(max_age) => self.duplicate_window.min(max_age),
+            None => self.duplicate_window,
+        };
+    }
+}
+
+/// NATS represents unlimited limits with -1
+pub(crate) fn to_nats_limit(limit: Option<u64>) -> i64 {
+    limit
+        .and_then(|limit| i64::try_from(limit).ok())
+        .unwrap_or(-1)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::nats::{NatsClientOpts, NatsUserRole};
+
+    #[test]
+    fn test_apply_limits() {
+        let retention = RetentionConfig::default()
+            .with_max_age(None)
+            .with_max_bytes(Some(1024))
+            .with_replicas(3)
+            .with_history(5);
+
+        let mut config = stream::Config::default();
+        retention.apply_limits(&mut config);
+        assert_eq!(config.max_age, Duration::ZERO);
+        assert_eq!(config.max_bytes, 1024);
+        assert_eq!(config.max_messages, -1);
+        assert_eq!(config.max_messages_per_subject, 5);
+        assert_eq!(config.num_replicas, 3);
+        assert_eq!(config.duplicate_window, Duration::from_secs(120));
+
+        let retention = retention.with_max_age(Some(Duration::from_secs(60)));
+        retention.apply_limits(&mut config);
+        assert_eq!(config.duplicate_window, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_only_admins_given_a_retention_manage_it() {
+        let retention = RetentionConfig::default().with_replicas(3);
+        let opts = NatsClientOpts::new("nats://localhost:4222");
+        assert_eq!(opts.managed_retention("blocks"), None);
+
+        let opts = opts.with_stream_retention("blocks", retention.clone());
+        assert_eq!(opts.managed_retention("blocks"), None);
+        assert_eq!(opts.retention("blocks"), retention);
+
+        let opts = opts.with_role(NatsUserRole::Admin);
+        assert_eq!(opts.managed_retention("blocks"), Some(&retention));
+        assert_eq!(opts.managed_retention("transactions"), None);
+        assert_eq!(opts.retention("transactions"), RetentionConfig::default());
+    }
+}
@@ -0,0 +1,160 @@
+use std::sync::OnceLock;
+
+/// An ed25519 signature of a published payload, named apart from the
+/// `Signature` of Fuel transactions.
+pub use ed25519_dalek::Signature as PayloadSignature;
+use ed25519_dalek::{Signer, SIGNATURE_LENGTH};
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+
+use super::error::SignatureError;
+
+/// Magic byte marking the start of a signed payload.
+pub const SIGNATURE_MAGIC: u8 = 0xF6;
+
+/// Length in bytes of the signature header prepended to signed payloads.
+pub const SIGNATURE_HEADER_LEN: usize = 1 + SIGNATURE_LENGTH;
+
+static DEFAULT_SIGNING_KEY: OnceLock<SigningKey> = OnceLock::new();
+
+/// Sets the key returned by `StreamEncoder::signing_key` for the whole
+/// process, so that every published payload gets signed with it.
+///
+/// This can only be done once, and should be done before anything is
+/// published. If a key was already set, the given one is handed back.
+#[allow(clippy::result_large_err)]
+pub fn set_signing_key(key: SigningKey) -> Result<(), SigningKey> {
+    DEFAULT_SIGNING_KEY.set(key)
+}
+
+pub(crate) fn default_signing_key() -> Option<&'static SigningKey> {
+    DEFAULT_SIGNING_KEY.get()
+}
+
+/// Parses an ed25519 signing key from its hex encoded 32 bytes seed.
+pub fn parse_signing_key(hex: &str) -> Result<SigningKey, SignatureError> {
+    Ok(SigningKey::from_bytes(&parse_key_bytes(hex)?))
+}
+
+/// Parses an ed25519 verifying key from its hex encoded 32 bytes.
+pub fn parse_verifying_key(hex: &str) -> Result<VerifyingKey, SignatureError> {
+    Ok(VerifyingKey::from_bytes(&parse_key_bytes(hex)?)?)
+}
+
+fn parse_key_bytes(hex: &str) -> Result<[u8; 32], SignatureError> {
+    let hex = hex.trim();
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    hex::decode(hex)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(SignatureError::InvalidKey)
+}
+
+/// Signs an encoded payload, prepending a header made of `SIGNATURE_MAGIC`
+/// followed by the ed25519 signature of the payload.
+///
+/// The signature covers the whole encoded `StreamData`, that is its subject,
+/// metadata and payload, along with the envelope header.
+pub fn sign_payload(key: &SigningKey, encoded: &[u8]) -> Vec<u8> {
+    let signature = key.sign(encoded);
+    let mut signed = Vec::with_capacity(SIGNATURE_HEADER_LEN + encoded.len());
+    signed.push(SIGNATURE_MAGIC);
+    signed.extend_from_slice(&signature.to_bytes());
+    signed.extend_from_slice(encoded);
+    signed
+}
+
+/// Splits a payload into its signature, if it is signed, and the encoded
+/// `StreamData`.
+pub fn split_signature(
+    encoded: &[u8],
+) -> Result<(Option<PayloadSignature>, &[u8]), SignatureError> {
+    if encoded.first() != Some(&SIGNATURE_MAGIC) {
+        return Ok((None, encoded));
+    }
+    if encoded.len() < SIGNATURE_HEADER_LEN {
+        return Err(SignatureError::Truncated(encoded.len()));
+    }
+
+    let (signature, body) = encoded[1..].split_at(SIGNATURE_LENGTH);
+    let signature = PayloadSignature::from_slice(signature)?;
+    Ok((Some(signature), body))
+}
+
+/// Verifies that a payload was signed with the key of `verifying_key`, and
+/// returns the encoded `StreamData` without the signature header.
+///
+/// Unsigned payloads are rejected.
+pub fn verify_payload<'a>(
+    verifying_key: &VerifyingKey,
+    encoded: &'a [u8],
+) -> Result<&'a [u8], SignatureError> {
+    match split_signature(encoded)? {
+        (Some(signature), body) => {
+            verifying_key.verify_strict(body, &signature)?;
+            Ok(body)
+        }
+        (None, _) => Err(SignatureError::Unsigned),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    #[test]
+    fn test_sign_and_verify_payload() {
+        let key = signing_key(1);
+        let signed = sign_payload(&key, b"payload");
+
+        assert_eq!(signed.len(), SIGNATURE_HEADER_LEN + 7);
+        let body = verify_payload(&key.verifying_key(), &signed).unwrap();
+        assert_eq!(body, b"payload");
+    }
+
+    #[test]
+    fn test_verify_rejects_invalid_payloads() {
+        let key = signing_key(1);
+        let verifying_key = key.verifying_key();
+
+        let mut tampered = sign_payload(&key, b"payload");
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            verify_payload(&verifying_key, &tampered),
+            Err(SignatureError::Invalid(_))
+        ));
+
+        let other = sign_payload(&signing_key(2), b"payload");
+        assert!(matches!(
+            verify_payload(&verifying_key, &other),
+            Err(SignatureError::Invalid(_))
+        ));
+
+        assert!(matches!(
+            verify_payload(&verifying_key, b"payload"),
+            Err(SignatureError::Unsigned)
+        ));
+        assert!(matches!(
+            verify_payload(&verifying_key, &[SIGNATURE_MAGIC, 1, 2]),
+            Err(SignatureError::Truncated(3))
+        ));
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        let key = signing_key(7);
+        let seed = format!("0x{}", hex::encode(key.to_bytes()));
+        assert_eq!(parse_signing_key(&seed).unwrap(), key);
+
+        let public = hex::encode(key.verifying_key().to_bytes());
+        assert_eq!(parse_verifying_key(&public).unwrap(), key.verifying_key());
+
+        assert!(matches!(
+            parse_signing_key("0x1234"),
+            Err(SignatureError::InvalidKey)
+        ));
+    }
+}
@@ -1,13 +1,61 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::OnceLock};
 
 use async_trait::async_trait;
-use fuel_data_parser::{DataParseable, DataParser};
+use fuel_data_parser::{
+    DataParseable,
+    DataParser,
+    SerializationType,
+    UNVERSIONED_SCHEMA,
+};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use super::{
+    error::{DecodeError, EncodeError, EncodingError},
+    hex_bytes::HexBytes,
+    metadata::StreamMetadata,
+    signing::{
+        default_signing_key,
+        sign_payload,
+        split_signature,
+        verify_payload,
+        SigningKey,
+        VerifyingKey,
+    },
+};
+use crate::protobuf::{proto, ProtobufError};
+
+/// Schema version of payloads whose byte-based types (`Bytes32`, `Address`,
+/// `HexString`...) were serialized as hex strings in binary formats, as
+/// published before schema versions were recorded.
+pub const HEX_BYTES_SCHEMA_VERSION: u16 = 1;
+
+/// Schema version of payloads whose byte-based types are serialized as raw
+/// bytes in binary formats.
+pub const RAW_BYTES_SCHEMA_VERSION: u16 = 2;
+
+/// Schema version of payloads whose `StreamData` carries `StreamMetadata`
+/// instead of an RFC 3339 timestamp, which is the current layout of all
+/// streamable types.
+pub const METADATA_SCHEMA_VERSION: u16 = 3;
+
+static DEFAULT_DATA_PARSER: OnceLock<DataParser> = OnceLock::new();
+
+/// Sets the `DataParser` returned by `StreamEncoder::data_parser` for the
+/// whole process, e.g. to pick the compression strategy and level from
+/// configuration.
+///
+/// This can only be done once, and should be done before anything is
+/// published. If a parser was already set, the given one is handed back.
+pub fn set_default_data_parser(parser: DataParser) -> Result<(), DataParser> {
+    DEFAULT_DATA_PARSER.set(parser)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StreamData<T> {
     pub subject: String,
-    pub timestamp: String,
+    /// When, where and by whom the payload was published
+    pub metadata: StreamMetadata,
     /// The payload published for the subject
     pub payload: T,
 }
@@ -16,51 +64,561 @@ impl<T> StreamData<T>
 where
     T: serde::de::DeserializeOwned + Clone,
 {
+    /// Converts the payload, e.g. from a frozen copy of an older schema
+    /// version into the current type.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> StreamData<U> {
+        StreamData {
+            subject: self.subject,
+            metadata: self.metadata,
+            payload: f(self.payload),
+        }
+    }
+
     pub fn new(subject: &str, payload: T) -> Self {
-        let now: chrono::DateTime<chrono::Utc> = chrono::Utc::now();
-        // Formatting the datetime as an ISO 8601 string
-        let timestamp = now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
         Self {
             subject: subject.to_string(),
-            timestamp,
+            metadata: StreamMetadata::now(),
             payload,
         }
     }
 
-    #[cfg(feature = "bench-helpers")]
-    pub fn ts_as_millis(&self) -> u128 {
-        use chrono::{DateTime, Utc};
+    pub fn with_metadata(mut self, metadata: StreamMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+}
+
+/// Layout of `StreamData` up to `RAW_BYTES_SCHEMA_VERSION`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LegacyStreamData<T> {
+    subject: String,
+    /// RFC 3339 timestamp of publication, with seconds precision
+    timestamp: String,
+    payload: T,
+}
 
-        DateTime::parse_from_rfc3339(&self.timestamp)
-            .ok()
-            .map(|ts| ts.timestamp_millis() as u128)
-            .unwrap_or_else(|| Utc::now().timestamp_millis() as u128)
+impl<T> From<LegacyStreamData<T>> for StreamData<T> {
+    fn from(data: LegacyStreamData<T>) -> Self {
+        Self {
+            subject: data.subject,
+            metadata: StreamMetadata::from_rfc3339(&data.timestamp),
+            payload: data.payload,
+        }
     }
 }
 
+/// Encodes and decodes `StreamData` payloads of a streamable type.
+///
+/// Every payload records the `SCHEMA_VERSION` of the type in its envelope
+/// header. Payloads of older versions are decoded through `migrate`, while
+/// payloads of newer versions are decoded on a best effort basis in
+/// self-describing formats, where fields added since are skipped.
 #[async_trait]
 pub trait StreamEncoder: DataParseable {
-    async fn encode(&self, subject: &str) -> Vec<u8> {
-        let data = StreamData::new(subject, self.clone());
+    /// Version of the serialized layout of this type.
+    ///
+    /// Bump it whenever the layout changes, e.g. when a field is added, and
+    /// teach `migrate` to decode the previous version.
+    const SCHEMA_VERSION: u16 = METADATA_SCHEMA_VERSION;
 
-        Self::data_parser()
-            .encode(&data)
+    async fn encode(&self, subject: &str) -> Result<Vec<u8>, EncodeError> {
+        self.encode_with_metadata(subject, StreamMetadata::now())
             .await
-            .expect("Streamable must encode correctly")
     }
 
-    async fn decode(encoded: Vec<u8>) -> Self {
-        Self::decode_raw(encoded).await.payload
+    async fn encode_with_metadata(
+        &self,
+        subject: &str,
+        metadata: StreamMetadata,
+    ) -> Result<Vec<u8>, EncodeError> {
+        let data =
+            StreamData::new(subject, self.clone()).with_metadata(metadata);
+        let parser =
+            Self::data_parser().with_schema_version(Self::SCHEMA_VERSION);
+        let encoded = if parser.serialization_type
+            == SerializationType::Protobuf
+        {
+            match data.to_protobuf() {
+                Some(message) => parser
+                    .encode_protobuf(&message)
+                    .await
+                    .map_err(EncodingError::from),
+                None => Err(ProtobufError::Unsupported(std::any::type_name::<
+                    Self,
+                >())
+                .into()),
+            }
+        } else {
+            parser.encode(&data).await.map_err(EncodingError::from)
+        };
+
+        let encoded = encoded.map_err(|source| EncodeError {
+            subject: subject.to_string(),
+            source,
+        })?;
+
+        Ok(match Self::signing_key() {
+            Some(key) => sign_payload(key, &encoded),
+            None => encoded,
+        })
+    }
+
+    /// Accepts any byte container (`Vec<u8>`, `bytes::Bytes`, `&[u8]`), so
+    /// NATS payloads can be decoded without copying them first.
+    async fn decode(
+        encoded: impl AsRef<[u8]> + Send,
+    ) -> Result<Self, DecodeError> {
+        Ok(Self::decode_raw(encoded).await?.payload)
+    }
+
+    /// Decodes a payload along with its subject and metadata.
+    ///
+    /// Signed payloads are decoded without checking their signature, see
+    /// `decode_verified` for that.
+    async fn decode_raw(
+        encoded: impl AsRef<[u8]> + Send,
+    ) -> Result<StreamData<Self>, DecodeError> {
+        let encoded = encoded.as_ref();
+        let (_, body) = split_signature(encoded)
+            .map_err(|source| DecodeError::new(encoded, source))?;
+        Self::decode_body(body)
+            .await
+            .map_err(|source| DecodeError::new(encoded, source))
     }
 
-    async fn decode_raw(encoded: Vec<u8>) -> StreamData<Self> {
-        Self::data_parser()
-            .decode(&encoded)
+    /// Decodes a payload like `decode_raw`, after checking that it was
+    /// signed with the key of `verifying_key`.
+    ///
+    /// Unsigned payloads and payloads with an invalid signature are rejected.
+    async fn decode_verified(
+        encoded: impl AsRef<[u8]> + Send,
+        verifying_key: &VerifyingKey,
+    ) -> Result<StreamData<Self>, DecodeError> {
+        let encoded = encoded.as_ref();
+        let body = verify_payload(verifying_key, encoded)
+            .map_err(|source| DecodeError::new(encoded, source))?;
+        Self::decode_body(body)
             .await
-            .expect("Streamable must decode correctly")
+            .map_err(|source| DecodeError::new(encoded, source))
     }
 
+    /// Decodes the encoded `StreamData` of a payload, without its signature.
+    async fn decode_body(
+        encoded: &[u8],
+    ) -> Result<StreamData<Self>, EncodingError> {
+        let parser = Self::data_parser();
+        let serialization_type = parser.serialization_type_of(encoded)?;
+
+        // Protobuf messages evolve through their field numbers instead
+        if serialization_type == SerializationType::Protobuf {
+            let message = parser.decode_protobuf(encoded).await?;
+            return Ok(StreamData::from_protobuf(message)?);
+        }
+
+        match parser.schema_version_of(encoded)? {
+            version if version == Self::SCHEMA_VERSION => {
+                Ok(parser.decode(encoded).await?)
+            }
+            // Either layout may have been published before versions were
+            // recorded, and they can't be told apart up front
+            UNVERSIONED_SCHEMA => {
+                match Self::migrate(RAW_BYTES_SCHEMA_VERSION, encoded) {
+                    Ok(data) => Ok(data),
+                    Err(error) => {
+                        Self::migrate(HEX_BYTES_SCHEMA_VERSION, encoded)
+                            .map_err(|_| error)
+                    }
+                }
+            }
+            version if version > Self::SCHEMA_VERSION => {
+                if serialization_type.is_self_describing() {
+                    Ok(parser.decode(encoded).await?)
+                } else {
+                    Err(EncodingError::UnsupportedSchemaVersion(version))
+                }
+            }
+            version => Self::migrate(version, encoded),
+        }
+    }
+
+    /// Decodes a payload published with the given `SCHEMA_VERSION`.
+    ///
+    /// The default implementation decodes the three layouts every streamable
+    /// type went through. Types that change their layout bump their
+    /// `SCHEMA_VERSION` and override this to decode older versions through a
+    /// frozen copy of the type with the previous `SCHEMA_VERSION`, converting
+    /// the result with `StreamData::map`.
+    fn migrate(
+        version: u16,
+        encoded: &[u8],
+    ) -> Result<StreamData<Self>, EncodingError> {
+        let parser = Self::data_parser();
+        match version {
+            METADATA_SCHEMA_VERSION => Ok(parser.decode_blocking(encoded)?),
+            RAW_BYTES_SCHEMA_VERSION => Ok(parser
+                .decode_blocking::<LegacyStreamData<Self>>(encoded)?
+                .into()),
+            HEX_BYTES_SCHEMA_VERSION => Ok(StreamData::from(
+                parser.decode_blocking::<LegacyStreamData<HexBytes<Self>>>(
+                    encoded,
+                )?,
+            )
+            .map(|HexBytes(payload)| payload)),
+            _ => Err(EncodingError::UnsupportedSchemaVersion(version)),
+        }
+    }
+
+    /// Converts the payload into its `StreamData` protobuf variant, used
+    /// when publishing with `SerializationType::Protobuf`.
+    ///
+    /// Types without a protobuf representation return `None` and can only be
+    /// published with serde based serializations.
+    fn to_protobuf(&self) -> Option<proto::stream_data::Payload> {
+        None
+    }
+
+    /// Converts a `StreamData` protobuf payload back into this type.
+    fn from_protobuf(
+        _payload: proto::stream_data::Payload,
+    ) -> Result<Self, ProtobufError> {
+        Err(ProtobufError::Unsupported(std::any::type_name::<Self>()))
+    }
+
+    /// Decoding reads the serialization and compression from the envelope
+    /// header of each payload, so this parser only matters for encoding.
     fn data_parser() -> DataParser {
-        DataParser::default()
+        DEFAULT_DATA_PARSER.get().cloned().unwrap_or_default()
+    }
+
+    /// The key published payloads are signed with, if any.
+    fn signing_key() -> Option<&'static SigningKey> {
+        default_signing_key()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fuel_data_parser::{ENVELOPE_HEADER_LEN, ENVELOPE_MAGIC};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::prelude::*;
+
+    /// Encodes a payload like `StreamEncoder::encode` with the given parser.
+    fn encode<T: StreamEncoder>(parser: &DataParser, payload: &T) -> Vec<u8> {
+        let data = StreamData::new("test.subject", payload.clone());
+        parser.encode_blocking(&data).unwrap()
+    }
+
+    /// Encodes a payload in the `StreamData` layout that predates
+    /// `StreamMetadata`.
+    fn encode_legacy<T: DataParseable>(
+        parser: &DataParser,
+        payload: &T,
+    ) -> Vec<u8> {
+        let data = LegacyStreamData {
+            subject: "test.subject".to_string(),
+            timestamp: "2024-11-05T10:20:30Z".to_string(),
+            payload: payload.clone(),
+        };
+        parser.encode_blocking(&data).unwrap()
+    }
+
+    /// Replaces the envelope header of a payload with a version 1 header,
+    /// as published before schema versions were recorded.
+    fn with_v1_header(encoded: &[u8]) -> Vec<u8> {
+        let serialization_id = encoded[2];
+        let body = &encoded[ENVELOPE_HEADER_LEN..];
+        [&[ENVELOPE_MAGIC, 1, serialization_id, 0][..], body].concat()
+    }
+
+    async fn assert_decodes<T: StreamEncoder>(encoded: &[u8], payload: &T) {
+        let decoded = T::decode_raw(encoded).await.unwrap();
+        assert_eq!(decoded.subject, "test.subject");
+        assert_eq!(
+            serde_json::to_value(&decoded.payload).unwrap(),
+            serde_json::to_value(payload).unwrap()
+        );
+    }
+
+    const SERDE: &[SerializationType] = &[
+        SerializationType::Bincode,
+        SerializationType::Postcard,
+        SerializationType::Json,
+        SerializationType::MessagePack,
+        SerializationType::Cbor,
+    ];
+
+    /// Types with internally tagged enums (`#[serde(tag = "type")]`) can only
+    /// be decoded from these.
+    const SELF_DESCRIBING: &[SerializationType] = &[
+        SerializationType::Json,
+        SerializationType::MessagePack,
+        SerializationType::Cbor,
+    ];
+
+    /// Decodes the payload as published with every historical schema
+    /// version, in the given serializations.
+    async fn assert_decodes_history<T: StreamEncoder>(
+        payload: T,
+        serialization_types: &[SerializationType],
+    ) {
+        for &serialization_type in serialization_types {
+            let parser = DataParser::default()
+                .with_serialization_type(serialization_type);
+
+            let hex_bytes = encode_legacy(&parser, &HexBytes(payload.clone()));
+            assert_decodes(&with_v1_header(&hex_bytes), &payload).await;
+            let hex_bytes = encode_legacy(
+                &parser.clone().with_schema_version(HEX_BYTES_SCHEMA_VERSION),
+                &HexBytes(payload.clone()),
+            );
+            assert_decodes(&hex_bytes, &payload).await;
+
+            let raw_bytes = encode_legacy(&parser, &payload);
+            assert_decodes(&with_v1_header(&raw_bytes), &payload).await;
+            let raw_bytes = encode_legacy(
+                &parser.clone().with_schema_version(RAW_BYTES_SCHEMA_VERSION),
+                &payload,
+            );
+            assert_decodes(&raw_bytes, &payload).await;
+
+            let parser = parser.with_schema_version(METADATA_SCHEMA_VERSION);
+            assert_decodes(&encode(&parser, &payload), &payload).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decode_historical_schema_versions() {
+        assert_decodes_history(MockBlock::build(1), SELF_DESCRIBING).await;
+        let transaction = Transaction {
+            raw_payload: HexString(vec![1, 2, 3]),
+            witnesses: vec![HexString(vec![4, 5])],
+            tx_pointer: Some(FuelCoreTxPointer::new(6.into(), 7)),
+            ..Default::default()
+        };
+        assert_decodes_history(transaction, SERDE).await;
+        let input = Input::Coin(InputCoin {
+            predicate: HexString(vec![8, 9]),
+            utxo_id: FuelCoreUtxoId::new([1u8; 32].into(), 2).into(),
+            ..Default::default()
+        });
+        assert_decodes_history(input, SELF_DESCRIBING).await;
+        let output = Output::ContractCreated(ContractCreated {
+            contract_id: [2u8; 32].into(),
+            state_root: [3u8; 32].into(),
+        });
+        assert_decodes_history(output, SELF_DESCRIBING).await;
+
+        let receipt = FuelCoreReceipt::log_data(
+            [4u8; 32].into(),
+            1,
+            2,
+            3,
+            4,
+            5,
+            vec![6, 7],
+        );
+        assert_decodes_history(Receipt::from(&receipt), SERDE).await;
+        assert_decodes_history(Log::from(receipt), SELF_DESCRIBING).await;
+        let utxo = Utxo {
+            sender: Some([5u8; 32].into()),
+            data: Some(vec![1, 2]),
+            ..Default::default()
+        };
+        assert_decodes_history(utxo, SERDE).await;
+    }
+
+    /// A type that added `balance` in schema version 4.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Account {
+        address: Address,
+        balance: u64,
+    }
+
+    /// Frozen copy of `Account` at schema version 3.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct AccountV3 {
+        address: Address,
+    }
+
+    impl StreamEncoder for AccountV3 {}
+
+    impl StreamEncoder for Account {
+        const SCHEMA_VERSION: u16 = 4;
+
+        fn migrate(
+            version: u16,
+            encoded: &[u8],
+        ) -> Result<StreamData<Self>, EncodingError> {
+            Ok(
+                AccountV3::migrate(version, encoded)?.map(|account| Account {
+                    address: account.address,
+                    balance: 0,
+                }),
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_migrate_added_field() {
+        let address: Address = [1u8; 32].into();
+        let parser = DataParser::default()
+            .with_serialization_type(SerializationType::Postcard);
+
+        // Older payloads are migrated into the current type
+        let v3 = AccountV3 {
+            address: address.clone(),
+        };
+        for encoded in [
+            with_v1_header(&encode_legacy(&parser, &HexBytes(v3.clone()))),
+            encode_legacy(&parser.clone().with_schema_version(2), &v3),
+            encode(&parser.clone().with_schema_version(3), &v3),
+        ] {
+            let decoded = Account::decode(&encoded).await.unwrap();
+            assert_eq!(
+                decoded,
+                Account {
+                    address: address.clone(),
+                    balance: 0,
+                }
+            );
+        }
+
+        // Newer payloads are rejected by older types in binary formats...
+        let account = Account {
+            address: address.clone(),
+            balance: 10,
+        };
+        let v4 = encode(&parser.with_schema_version(4), &account);
+        let error = AccountV3::decode(&v4).await.unwrap_err();
+        assert!(matches!(
+            error.source,
+            EncodingError::UnsupportedSchemaVersion(4)
+        ));
+
+        // ...but decoded in self-describing ones, skipping the new fields
+        let parser = DataParser::default().with_schema_version(4);
+        let decoded =
+            AccountV3::decode(encode(&parser, &account)).await.unwrap();
+        assert_eq!(decoded.address, address);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_timestamp_migrates_into_metadata() {
+        let parser =
+            DataParser::default().with_schema_version(RAW_BYTES_SCHEMA_VERSION);
+        let encoded = encode_legacy(&parser, &MockBlock::build(1));
+
+        let decoded = Block::decode_raw(&encoded).await.unwrap();
+        assert_eq!(
+            decoded.metadata,
+            StreamMetadata {
+                timestamp_ms: 1_730_802_030_000,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_encode_decode_roundtrip() {
+        let block = MockBlock::build(1);
+        let encoded = block.encode("blocks.1.0x00").await.unwrap();
+        let parser = DataParser::default();
+        assert_eq!(
+            parser.schema_version_of(&encoded).unwrap(),
+            Block::SCHEMA_VERSION
+        );
+
+        let decoded = Block::decode_raw(&encoded).await.unwrap();
+        assert_eq!(decoded.subject, "blocks.1.0x00");
+        assert_eq!(decoded.payload, block);
+
+        let metadata = StreamMetadata::now()
+            .with_block(&BlockContext {
+                chain_id: 0,
+                height: 1,
+                timestamp_ms: 1_730_802_030_000,
+            })
+            .with_publisher("publisher-1", 7);
+        let encoded = block
+            .encode_with_metadata("blocks.1.0x00", metadata.clone())
+            .await
+            .unwrap();
+        let decoded = Block::decode_raw(&encoded).await.unwrap();
+        assert_eq!(decoded.metadata, metadata);
+    }
+
+    /// A type published with its own signing key.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct SignedAccount {
+        address: Address,
+    }
+
+    impl StreamEncoder for SignedAccount {
+        fn signing_key() -> Option<&'static SigningKey> {
+            static KEY: OnceLock<SigningKey> = OnceLock::new();
+            Some(KEY.get_or_init(|| SigningKey::from_bytes(&[1; 32])))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_signed_payloads() {
+        let account = SignedAccount {
+            address: [1u8; 32].into(),
+        };
+        let encoded = account.encode("accounts.1").await.unwrap();
+        assert_eq!(encoded[0], SIGNATURE_MAGIC);
+
+        let verifying_key =
+            SignedAccount::signing_key().unwrap().verifying_key();
+        let decoded = SignedAccount::decode_verified(&encoded, &verifying_key)
+            .await
+            .unwrap();
+        assert_eq!(decoded.subject, "accounts.1");
+        assert_eq!(decoded.payload, account);
+        // The signature is only checked on request
+        let decoded = SignedAccount::decode_raw(&encoded).await.unwrap();
+        assert_eq!(decoded.payload, account);
+
+        let other_key = SigningKey::from_bytes(&[2; 32]).verifying_key();
+        let error = SignedAccount::decode_verified(&encoded, &other_key)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.source,
+            EncodingError::Signature(SignatureError::Invalid(_))
+        ));
+        assert_eq!(error.payload, encoded);
+
+        let unsigned = AccountV3 {
+            address: [1u8; 32].into(),
+        }
+        .encode("accounts.1")
+        .await
+        .unwrap();
+        let error = SignedAccount::decode_verified(&unsigned, &verifying_key)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.source,
+            EncodingError::Signature(SignatureError::Unsigned)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_decode_error_keeps_subject_and_payload() {
+        let encoded = b"not a block".to_vec();
+        let error = Block::decode(&encoded)
+            .await
+            .unwrap_err()
+            .with_subject("blocks.1.0x00");
+
+        assert_eq!(error.payload, encoded);
+        assert_eq!(error.subject.as_deref(), Some("blocks.1.0x00"));
+        assert_eq!(
+            error.to_string(),
+            "Failed to decode payload of 11 bytes from subject `blocks.1.0x00`"
+        );
     }
 }
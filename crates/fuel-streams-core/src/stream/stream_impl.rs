@@ -1,30 +1,57 @@
-#[cfg(any(test, feature = "test-helpers"))]
+#[cfg(feature = "test-helpers")]
 use std::pin::Pin;
-use std::{fmt::Debug, sync::Arc, time::Duration};
-
-use async_nats::{
-    jetstream::{
-        consumer::AckPolicy,
-        kv::{self, CreateErrorKind},
-        stream::{self, LastRawMessageErrorKind, State},
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
     },
-    RequestErrorKind,
 };
+
+use async_nats::{jetstream::stream::State, RequestErrorKind};
 use async_trait::async_trait;
+use bytes::Bytes;
 use fuel_streams_macros::subject::IntoSubject;
-use futures::{StreamExt, TryStreamExt};
-use tokio::sync::OnceCell;
-
-use super::{error::StreamError, stream_encoding::StreamEncoder};
+use futures::StreamExt;
+
+use super::{
+    backends::{
+        DeliverFrom,
+        JetStreamKv,
+        PublishMode,
+        StreamBackend,
+        StreamMessage,
+    },
+    error::StreamError,
+    headers::StreamHeaders,
+    metadata::{publisher_id, publisher_sequence, StreamMetadata},
+    stream_encoding::StreamEncoder,
+};
 use crate::{nats::types::*, prelude::NatsClient};
 
-pub const FUEL_BLOCK_TIME_SECS: u64 = 1;
-pub const MAX_RETENTION_BLOCKS: u64 = 100;
+/// Deduplicates the publications of the payload of a subject for the same
+/// block, e.g. when a publisher replays blocks after a restart. The height
+/// identifies the block, as Fuel blocks are final once produced. Without a
+/// height, nothing tells a replay from a new payload, so only the first
+/// payload of the subject is kept.
+fn publish_mode(subject_name: &str, metadata: &StreamMetadata) -> PublishMode {
+    match metadata.block_height {
+        Some(height) => PublishMode::Deduplicated {
+            message_id: format!("{subject_name}@{height}"),
+        },
+        None => PublishMode::CreateIfAbsent,
+    }
+}
+
+/// Number of publishes of a batch awaiting their ack at once, unless set
+/// with `NatsClientOpts::with_publish_window`.
+pub const DEFAULT_PUBLISH_WINDOW: usize = 256;
 
 #[derive(Clone)]
 pub struct PublishPacket<T: Streamable> {
     pub subject: Arc<dyn IntoSubject>,
     pub payload: Arc<T>,
+    pub headers: StreamHeaders,
 }
 
 impl<T: Streamable> PublishPacket<T> {
@@ -32,8 +59,16 @@ impl<T: Streamable> PublishPacket<T> {
         Self {
             payload: Arc::new(payload),
             subject,
+            headers: StreamHeaders::default(),
         }
     }
+
+    /// Sets the index of the transaction the payload belongs to within its
+    /// block, published as a header.
+    pub fn with_tx_index(mut self, tx_index: u32) -> Self {
+        self.headers = self.headers.with_tx_index(Some(tx_index));
+        self
+    }
 }
 
 /// Trait for types that can be streamed.
@@ -65,10 +100,21 @@ pub trait Streamable: StreamEncoder {
     fn to_packet(&self, subject: Arc<dyn IntoSubject>) -> PublishPacket<Self> {
         PublishPacket::new(self.clone(), subject)
     }
+
+    /// Subject matching the messages of the block at `height`, for streams
+    /// whose subjects hold the block height. Subscriptions to other streams
+    /// can't start from a block height.
+    fn block_height_subject(_height: u32) -> Option<String> {
+        None
+    }
 }
 
 /// Houses nats-agnostic APIs for publishing and consuming a streamable type
 ///
+/// Payloads are encoded here and handed to a `StreamBackend`, which is
+/// a `JetStreamKv` store, or an `InMemoryBackend` for in-memory clients,
+/// unless another one is given to `with_backend`.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -105,76 +151,250 @@ pub trait Streamable: StreamEncoder {
 ///     }
 /// }
 /// ```
-///
-/// TODO: Split this into two traits StreamPublisher + StreamSubscriber
 #[derive(Debug, Clone)]
 pub struct Stream<S: Streamable> {
-    store: kv::Store,
+    backend: Arc<dyn StreamBackend>,
+    sequence: Arc<AtomicU64>,
+    publish_window: usize,
     _marker: std::marker::PhantomData<S>,
 }
 
 impl<S: Streamable> Stream<S> {
-    #[allow(clippy::declare_interior_mutable_const)]
-    const INSTANCE: OnceCell<Self> = OnceCell::const_new();
-
+    /// Returns the stream of `client`, creating it unless it exists.
     pub async fn get_or_init(client: &NatsClient) -> Self {
-        let cell = Self::INSTANCE;
-        cell.get_or_init(|| async { Self::new(client).await.to_owned() })
-            .await
-            .to_owned()
+        Self::new(client).await
     }
 
+    /// Every `Stream` of the same stream, i.e. of the same `Streamable` and
+    /// namespace, shares the sequence of the payloads published on it.
     pub async fn new(client: &NatsClient) -> Self {
-        let namespace = &client.namespace;
-        let bucket_name = namespace.stream_name(S::NAME);
-        let store = client
-            .get_or_create_kv_store(kv::Config {
-                bucket: bucket_name.to_owned(),
-                storage: stream::StorageType::File,
-                history: 1,
-                compression: true,
-                max_age: Duration::from_secs(
-                    FUEL_BLOCK_TIME_SECS * MAX_RETENTION_BLOCKS,
-                ),
-                ..Default::default()
-            })
+        let name = client.namespace.stream_name(S::NAME);
+        let sequence = publisher_sequence(&name);
+
+        #[cfg(feature = "in-memory")]
+        if client.is_in_memory() {
+            let backend = super::InMemoryBackend::get_or_create(&name);
+            return Self {
+                sequence,
+                ..Self::with_backend(Arc::new(backend))
+            }
+            .with_publish_window(client.opts.publish_window);
+        }
+
+        let retention = client.opts.managed_retention(S::NAME);
+        let backend = JetStreamKv::new(client, S::NAME, retention)
             .await
             .expect("Streams must be created");
 
         Self {
-            store,
+            sequence,
+            ..Self::with_backend(Arc::new(backend))
+        }
+        .with_publish_window(client.opts.publish_window)
+    }
+
+    /// Creates a stream publishing to and subscribing from `backend`, with a
+    /// sequence of its own.
+    pub fn with_backend(backend: Arc<dyn StreamBackend>) -> Self {
+        Self {
+            backend,
+            sequence: Arc::new(AtomicU64::new(0)),
+            publish_window: DEFAULT_PUBLISH_WINDOW,
             _marker: std::marker::PhantomData,
         }
     }
 
+    /// Sets the number of publishes of a batch awaiting their ack at once.
+    pub fn with_publish_window(self, publish_window: usize) -> Self {
+        Self {
+            publish_window,
+            ..self
+        }
+    }
+
+    pub fn backend(&self) -> &dyn StreamBackend {
+        self.backend.as_ref()
+    }
+
+    /// Returns the backend as a `JetStreamKv`, if it is one.
+    pub fn jetstream(&self) -> Option<&JetStreamKv> {
+        self.backend.as_any().downcast_ref()
+    }
+
+    /// Publishes a payload without a block height, which is a no-op if the
+    /// subject already holds one, like publishing the same block twice.
+    /// Publishes with the height of a block, e.g. with
+    /// `publish_with_metadata`, are instead deduplicated by subject and
+    /// height within the duplicate window of the stream, so that the payload
+    /// of a block published again after the window is stored again. Use
+    /// `upsert` to replace the payload of a subject.
     pub async fn publish(
         &self,
         subject: &dyn IntoSubject,
         payload: &S,
+    ) -> Result<usize, StreamError> {
+        self.publish_with_metadata(subject, payload, StreamMetadata::now())
+            .await
+    }
+
+    /// Publish with metadata, e.g. the block the payload belongs to. The
+    /// publisher id and sequence are set by the stream.
+    pub async fn publish_with_metadata(
+        &self,
+        subject: &dyn IntoSubject,
+        payload: &S,
+        metadata: StreamMetadata,
+    ) -> Result<usize, StreamError> {
+        let subject_name = &subject.parse();
+        self.publish_raw(subject_name, payload, metadata).await
+    }
+
+    /// Publish with metadata and headers, e.g. the index of the transaction
+    /// the payload belongs to. The encoding and block headers are set by the
+    /// stream, from the payload type and the metadata.
+    pub async fn publish_with_headers(
+        &self,
+        subject: &dyn IntoSubject,
+        payload: &S,
+        metadata: StreamMetadata,
+        headers: StreamHeaders,
     ) -> Result<usize, StreamError> {
         let subject_name = &subject.parse();
-        self.publish_raw(subject_name, payload).await
+        let mode = publish_mode(subject_name, &metadata);
+        self.publish_with_mode(subject_name, payload, metadata, headers, &mode)
+            .await
     }
 
-    /// Publish with subject name with no static guarantees of the subject
+    /// Publish with subject name with no static guarantees of the subject,
+    /// deduplicated like with `publish`.
     pub async fn publish_raw(
         &self,
         subject_name: &str,
         payload: &S,
+        metadata: StreamMetadata,
     ) -> Result<usize, StreamError> {
-        let data = payload.encode(subject_name).await;
-        let data_size = data.len();
-        let result = self.store.create(subject_name, data.into()).await;
-
-        match result {
-            Ok(_) => Ok(data_size),
-            Err(e) if e.kind() == CreateErrorKind::AlreadyExists => {
-                // This is a workaround to avoid publish two times the same message
-                Ok(data_size)
-            }
-            Err(e) => Err(StreamError::PublishFailed {
-                subject_name: subject_name.to_string(),
-                source: e,
+        let mode = publish_mode(subject_name, &metadata);
+        let headers = StreamHeaders::default();
+        self.publish_with_mode(subject_name, payload, metadata, headers, &mode)
+            .await
+    }
+
+    pub async fn upsert(
+        &self,
+        subject: &dyn IntoSubject,
+        payload: &S,
+    ) -> Result<usize, StreamError> {
+        self.upsert_with_metadata(subject, payload, StreamMetadata::now())
+            .await
+    }
+
+    /// Publish replacing the payload the subject holds, without
+    /// deduplication, for mutable entities.
+    pub async fn upsert_with_metadata(
+        &self,
+        subject: &dyn IntoSubject,
+        payload: &S,
+        metadata: StreamMetadata,
+    ) -> Result<usize, StreamError> {
+        let subject_name = &subject.parse();
+        self.publish_with_mode(
+            subject_name,
+            payload,
+            metadata,
+            StreamHeaders::default(),
+            &PublishMode::Upsert,
+        )
+        .await
+    }
+
+    async fn publish_with_mode(
+        &self,
+        subject_name: &str,
+        payload: &S,
+        metadata: StreamMetadata,
+        headers: StreamHeaders,
+        mode: &PublishMode,
+    ) -> Result<usize, StreamError> {
+        let message = self
+            .encode_message(subject_name, payload, metadata, headers)
+            .await?;
+        let data_size = message.payload.len();
+        self.backend.publish(message, mode).await?;
+        Ok(data_size)
+    }
+
+    /// Publishes packets with the given metadata, deduplicated like with
+    /// `publish_with_headers`. Publishes are pipelined: up to the publish
+    /// window of the stream await their ack at once, instead of a round
+    /// trip per packet.
+    ///
+    /// Returns the size of the encoded payloads, in the order of the packets.
+    pub async fn publish_batch(
+        &self,
+        packets: &[PublishPacket<S>],
+        metadata: StreamMetadata,
+    ) -> Result<Vec<usize>, StreamError> {
+        let mut messages = Vec::with_capacity(packets.len());
+        let mut data_sizes = Vec::with_capacity(packets.len());
+        for packet in packets {
+            let subject_name = &packet.subject.parse();
+            let mode = publish_mode(subject_name, &metadata);
+            let message = self
+                .encode_message(
+                    subject_name,
+                    &packet.payload,
+                    metadata.clone(),
+                    packet.headers.clone(),
+                )
+                .await?;
+            data_sizes.push(message.payload.len());
+            messages.push((message, mode));
+        }
+
+        self.backend
+            .publish_batch(messages, self.publish_window)
+            .await?;
+        Ok(data_sizes)
+    }
+
+    /// Encodes a payload into a message, with the publisher id and sequence
+    /// set in its metadata, and the encoding and block headers set.
+    async fn encode_message(
+        &self,
+        subject_name: &str,
+        payload: &S,
+        metadata: StreamMetadata,
+        headers: StreamHeaders,
+    ) -> Result<StreamMessage, StreamError> {
+        let sequence = self.sequence.fetch_add(1, Ordering::Relaxed) + 1;
+        let metadata = metadata.with_publisher(publisher_id(), sequence);
+        let headers = headers.with_payload::<S>(&metadata);
+        let data = payload.encode_with_metadata(subject_name, metadata).await?;
+        Ok(StreamMessage {
+            subject: subject_name.to_string(),
+            payload: data.into(),
+            headers,
+        })
+    }
+
+    /// Resolves `DeliverFrom::BlockHeight` to the sequence of the first
+    /// message of the block at that height, for backends to deliver from it.
+    /// Other variants are returned as they are.
+    pub async fn resolve_deliver_from(
+        &self,
+        deliver_from: DeliverFrom,
+    ) -> Result<DeliverFrom, StreamError> {
+        let DeliverFrom::BlockHeight(height) = deliver_from else {
+            return Ok(deliver_from);
+        };
+
+        let subject = S::block_height_subject(height)
+            .ok_or(StreamError::BlockHeightUnsupported { stream: S::NAME })?;
+        match self.backend.first_sequence(&subject).await? {
+            Some(sequence) => Ok(DeliverFrom::Sequence(sequence)),
+            None => Err(StreamError::BlockHeightNotFound {
+                stream: S::NAME,
+                height,
             }),
         }
     }
@@ -182,20 +402,14 @@ impl<S: Streamable> Stream<S> {
     pub async fn get_consumers_and_state(
         &self,
     ) -> Result<(String, Vec<String>, State), RequestErrorKind> {
-        let mut consumers = vec![];
-        while let Ok(Some(consumer)) =
-            self.store.stream.consumer_names().try_next().await
-        {
-            consumers.push(consumer);
+        match self.jetstream() {
+            Some(jetstream) => jetstream.get_consumers_and_state().await,
+            None => Err(RequestErrorKind::Other),
         }
-
-        let state = self.store.stream.cached_info().state;
-        let stream_name = self.get_stream_name().to_string();
-        Ok((stream_name, consumers, state))
     }
 
     pub fn get_stream_name(&self) -> &str {
-        self.store.stream_name.as_str()
+        self.backend.name()
     }
 
     // TODO: This should probably be `subscribe_raw` since it returns pure bytes
@@ -203,12 +417,15 @@ impl<S: Streamable> Stream<S> {
         &self,
         // TODO: Allow encapsulating Subject to return wildcard token type
         wildcard: &str,
-    ) -> Result<impl futures::Stream<Item = Option<Vec<u8>>>, StreamError> {
-        Ok(self.store.watch(&wildcard).await.map(|stream| {
-            stream.map(|entry| {
-                entry.ok().map(|entry_item| entry_item.value.to_vec())
-            })
-        })?)
+    ) -> Result<
+        impl futures::Stream<Item = Result<Bytes, StreamError>>,
+        StreamError,
+    > {
+        Ok(self
+            .backend
+            .watch(wildcard)
+            .await?
+            .map(|message| message.map(|message| message.payload)))
     }
 
     #[cfg(feature = "test-helpers")]
@@ -217,27 +434,20 @@ impl<S: Streamable> Stream<S> {
         &self,
         number_of_messages: usize,
     ) -> Result<
-        Pin<Box<dyn futures::Stream<Item = Option<S>> + Send>>,
+        Pin<Box<dyn futures::Stream<Item = Result<S, StreamError>> + Send>>,
         StreamError,
     > {
-        let config = PullConsumerConfig {
-            filter_subjects: self.all_filter_subjects(),
-            deliver_policy: DeliverPolicy::All,
-            ack_policy: AckPolicy::None,
-            ..Default::default()
-        };
-        let config = self.prefix_filter_subjects(config);
-        let consumer = self.store.stream.create_consumer(config).await?;
-
-        let stream = consumer.messages().await?.take(number_of_messages).then(
-            |message| async {
-                if let Ok(message) = message {
-                    Some(S::decode(message.payload.to_vec()).await)
-                } else {
-                    None
-                }
-            },
-        );
+        let messages = self
+            .backend
+            .subscribe(&self.all_filter_subjects(), DeliverFrom::All)
+            .await?;
+
+        let stream = messages.take(number_of_messages).then(|message| async {
+            let message = message?;
+            S::decode(&message.payload)
+                .await
+                .map_err(|error| error.with_subject(message.subject).into())
+        });
 
         // Use Box::pin to pin the stream on the heap
         Ok(Box::pin(stream))
@@ -256,8 +466,7 @@ impl<S: Streamable> Stream<S> {
             ..Default::default()
         };
 
-        let config = self.prefix_filter_subjects(config);
-        let consumer = self.store.stream.create_consumer(config).await?;
+        let consumer = self.create_consumer(config).await?;
         Ok(consumer.messages().await?)
     }
 
@@ -266,8 +475,10 @@ impl<S: Streamable> Stream<S> {
         &self,
         config: PullConsumerConfig,
     ) -> Result<NatsConsumer<PullConsumerConfig>, StreamError> {
-        let config = self.prefix_filter_subjects(config);
-        Ok(self.store.stream.create_consumer(config).await?)
+        self.jetstream()
+            .ok_or(StreamError::JetStreamRequired)?
+            .create_consumer(config)
+            .await
     }
 
     #[cfg(feature = "test-helpers")]
@@ -289,24 +500,16 @@ impl<S: Streamable> Stream<S> {
         &self,
         wildcard: &str,
     ) -> Result<Option<S>, StreamError> {
-        let subject_name = &Self::prefix_filter_subject(wildcard);
-
-        let message = self
-            .store
-            .stream
-            .get_last_raw_message_by_subject(subject_name)
-            .await;
-
-        match message {
-            Ok(message) => {
-                let payload = S::decode(message.payload.to_vec()).await;
+        match self.backend.last_published(wildcard).await? {
+            Some(message) => {
+                let payload =
+                    S::decode(message.payload).await.map_err(|error| {
+                        error.with_subject(message.subject.as_str())
+                    })?;
 
                 Ok(Some(payload))
             }
-            Err(error) => match &error.kind() {
-                LastRawMessageErrorKind::NoMessageFound => Ok(None),
-                _ => Err(error.into()),
-            },
+            None => Ok(None),
         }
     }
 
@@ -315,34 +518,17 @@ impl<S: Streamable> Stream<S> {
         &self,
         names: &std::collections::HashSet<String>,
     ) {
-        let mut stream = self.store.stream.clone();
+        let mut stream = self.store().stream.clone();
         let info = stream.info().await.unwrap();
         let has_stream = names.iter().any(|n| n.eq(&info.config.name));
         assert!(has_stream)
     }
 
-    fn prefix_filter_subjects(
-        &self,
-        mut config: PullConsumerConfig,
-    ) -> PullConsumerConfig {
-        config.filter_subjects = config
-            .filter_subjects
-            .iter()
-            .map(Self::prefix_filter_subject)
-            .collect();
-        config
-    }
-
-    fn prefix_filter_subject(subject: impl Into<String>) -> String {
-        // An hack to ensure we keep the KV namespace when reading
-        // from the KV store's stream
-        let subject = subject.into();
-        format!("$KV.*.{subject}")
-    }
-
     #[cfg(any(test, feature = "test-helpers"))]
-    pub fn store(&self) -> &kv::Store {
-        &self.store
+    pub fn store(&self) -> &async_nats::jetstream::kv::Store {
+        self.jetstream()
+            .expect("Stream must use the JetStream KV backend")
+            .store()
     }
 }
 
@@ -364,3 +550,298 @@ pub struct SubscribeConsumerConfig {
     pub filter_subjects: Vec<String>,
     pub deliver_policy: DeliverPolicy,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{any::Any, sync::Mutex};
+
+    use futures::stream;
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::{
+        blocks::BlocksSubject,
+        prelude::SubjectBuildable,
+        stream::{
+            BlockContext,
+            DeliverFrom,
+            MessageStream,
+            SequencedMessage,
+            StreamPublisher,
+            StreamSubscriber,
+        },
+    };
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Counter {
+        count: u64,
+    }
+
+    impl StreamEncoder for Counter {}
+
+    impl Streamable for Counter {
+        const NAME: &'static str = "counters";
+        const WILDCARD_LIST: &'static [&'static str] = &["counters.>"];
+    }
+
+    /// A backend recording what gets published, in place of NATS.
+    #[derive(Debug, Default)]
+    struct RecordingBackend {
+        messages: Mutex<Vec<StreamMessage>>,
+    }
+
+    #[async_trait]
+    impl StreamPublisher for RecordingBackend {
+        async fn publish(
+            &self,
+            message: StreamMessage,
+            _mode: &PublishMode,
+        ) -> Result<(), StreamError> {
+            self.messages.lock().unwrap().push(message);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl StreamSubscriber for RecordingBackend {
+        async fn watch(
+            &self,
+            _wildcard: &str,
+        ) -> Result<MessageStream, StreamError> {
+            let messages = self.messages.lock().unwrap().clone();
+            Ok(stream::iter(messages.into_iter().map(Ok)).boxed())
+        }
+
+        async fn subscribe(
+            &self,
+            _filter_subjects: &[String],
+            _deliver_from: DeliverFrom,
+        ) -> Result<MessageStream, StreamError> {
+            self.watch("").await
+        }
+
+        async fn subscribe_stored(
+            &self,
+            _filter_subjects: &[String],
+            _deliver_from: DeliverFrom,
+        ) -> Result<MessageStream, StreamError> {
+            self.watch("").await
+        }
+
+        async fn fetch(
+            &self,
+            _filter_subjects: &[String],
+            from_sequence: u64,
+            limit: usize,
+        ) -> Result<Vec<SequencedMessage>, StreamError> {
+            let messages = self.messages.lock().unwrap().clone();
+            Ok((1u64..)
+                .zip(messages)
+                .skip_while(|(sequence, _)| *sequence < from_sequence)
+                .take(limit)
+                .collect())
+        }
+
+        async fn first_sequence(
+            &self,
+            _wildcard: &str,
+        ) -> Result<Option<u64>, StreamError> {
+            let is_empty = self.messages.lock().unwrap().is_empty();
+            Ok((!is_empty).then_some(1))
+        }
+
+        async fn last_published(
+            &self,
+            _wildcard: &str,
+        ) -> Result<Option<StreamMessage>, StreamError> {
+            Ok(self.messages.lock().unwrap().last().cloned())
+        }
+    }
+
+    impl StreamBackend for RecordingBackend {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_publishes_through_backend() {
+        let backend = Arc::new(RecordingBackend::default());
+        let stream = Stream::<Counter>::with_backend(backend.clone());
+        assert_eq!(stream.get_stream_name(), "recording");
+        assert!(stream.jetstream().is_none());
+
+        for count in 1..=2 {
+            let subject = format!("counters.{count}");
+            stream
+                .publish_raw(
+                    &subject,
+                    &Counter { count },
+                    StreamMetadata::now(),
+                )
+                .await
+                .unwrap();
+        }
+
+        let last = stream.get_last_published("counters.>").await.unwrap();
+        assert_eq!(last, Some(Counter { count: 2 }));
+
+        let subject = BlocksSubject::new().with_height(Some(3.into()));
+        let metadata = StreamMetadata::now().with_block(&BlockContext {
+            chain_id: 0,
+            height: 3,
+            timestamp_ms: 0,
+        });
+        let headers = StreamHeaders::default().with_tx_index(Some(1));
+        stream
+            .publish_with_headers(
+                &subject,
+                &Counter { count: 3 },
+                metadata,
+                headers,
+            )
+            .await
+            .unwrap();
+        let headers = backend.messages.lock().unwrap()[2].headers.clone();
+        assert_eq!(headers.schema_version, Some(Counter::SCHEMA_VERSION));
+        assert_eq!(headers.block_height, Some(3));
+        assert_eq!(headers.tx_index, Some(1));
+        let headers = backend.messages.lock().unwrap()[0].headers.clone();
+        assert_eq!((headers.block_height, headers.tx_index), (None, None));
+
+        let payloads: Vec<Bytes> = stream
+            .subscribe("counters.>")
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        let decoded = Counter::decode_raw(&payloads[0]).await.unwrap();
+        assert_eq!(decoded.subject, "counters.1");
+        assert_eq!(decoded.payload, Counter { count: 1 });
+        assert_eq!(decoded.metadata.sequence, Some(1));
+
+        assert!(matches!(
+            stream.create_consumer(Default::default()).await,
+            Err(StreamError::JetStreamRequired)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stream_publishes_batches_in_order() {
+        let backend = Arc::new(RecordingBackend::default());
+        let stream = Stream::<Counter>::with_backend(backend.clone())
+            .with_publish_window(2);
+
+        let packets: Vec<PublishPacket<Counter>> = (1..=5)
+            .map(|count| {
+                let subject =
+                    BlocksSubject::new().with_height(Some(count.into()));
+                Counter {
+                    count: u64::from(count),
+                }
+                .to_packet(subject.arc())
+                .with_tx_index(count)
+            })
+            .collect();
+        let data_sizes = stream
+            .publish_batch(&packets, StreamMetadata::now())
+            .await
+            .unwrap();
+
+        let messages = backend.messages.lock().unwrap().clone();
+        assert_eq!(messages.len(), 5);
+        for (count, message) in (1..=5).zip(&messages) {
+            assert_eq!(message.subject, format!("blocks.*.{count}"));
+            assert_eq!(message.headers.tx_index, Some(count));
+        }
+        let sizes: Vec<usize> = messages
+            .iter()
+            .map(|message| message.payload.len())
+            .collect();
+        assert_eq!(data_sizes, sizes);
+    }
+
+    #[cfg(feature = "in-memory")]
+    #[tokio::test]
+    async fn test_stream_deduplicates_by_block_height() {
+        let backend = Arc::new(crate::stream::InMemoryBackend::new("dedup"));
+        let stream = Stream::<Counter>::with_backend(backend.clone());
+        let mut watch = backend.watch("counters.>").await.unwrap();
+
+        // only the first payload of a subject without a height is published
+        for count in 1..=2 {
+            stream
+                .publish_raw(
+                    "counters.1",
+                    &Counter { count },
+                    StreamMetadata::now(),
+                )
+                .await
+                .unwrap();
+        }
+        // replaying the payload of a subject for the same block is not
+        let metadata = StreamMetadata::now().with_block(&BlockContext {
+            chain_id: 0,
+            height: 3,
+            timestamp_ms: 0,
+        });
+        for count in 3..=4 {
+            stream
+                .publish_raw("counters.3", &Counter { count }, metadata.clone())
+                .await
+                .unwrap();
+        }
+        // while upserting replaces it
+        stream
+            .publish_with_mode(
+                "counters.1",
+                &Counter { count: 5 },
+                StreamMetadata::now(),
+                StreamHeaders::default(),
+                &PublishMode::Upsert,
+            )
+            .await
+            .unwrap();
+
+        let mut counts = Vec::new();
+        for _ in 0..3 {
+            let message = watch.next().await.unwrap().unwrap();
+            let decoded = Counter::decode_raw(&message.payload).await.unwrap();
+            counts.push(decoded.payload.count);
+        }
+        assert_eq!(counts, [1, 3, 5]);
+        let last = stream.get_last_published("counters.1").await.unwrap();
+        assert_eq!(last, Some(Counter { count: 5 }));
+    }
+
+    #[cfg(feature = "in-memory")]
+    #[tokio::test]
+    async fn test_streams_share_their_sequence() {
+        let opts =
+            crate::nats::NatsClientOpts::in_memory().with_rdn_namespace();
+        let client = NatsClient::connect(&opts).await.unwrap();
+        let mut sequences = Vec::new();
+        for count in 1..=2 {
+            let stream = Stream::<Counter>::get_or_init(&client).await;
+            let mut watch = stream.backend().watch("counters.>").await.unwrap();
+            stream
+                .publish_raw(
+                    &format!("counters.{count}"),
+                    &Counter { count },
+                    StreamMetadata::now(),
+                )
+                .await
+                .unwrap();
+            let message = watch.next().await.unwrap().unwrap();
+            let decoded = Counter::decode_raw(&message.payload).await.unwrap();
+            sequences.push(decoded.metadata.sequence);
+        }
+        assert_eq!(sequences, [Some(1), Some(2)]);
+    }
+}
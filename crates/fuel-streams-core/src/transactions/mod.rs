@@ -1,16 +1,25 @@
 pub mod subjects;
 pub mod types;
 
+use fuel_streams_macros::subject::{IntoSubject, SubjectBuildable};
 pub use subjects::*;
 
 use super::types::*;
-use crate::{StreamEncoder, Streamable};
+use crate::{protobuf::impl_protobuf_stream_encoder, Streamable};
 
-impl StreamEncoder for Transaction {}
+impl_protobuf_stream_encoder!(Transaction);
 impl Streamable for Transaction {
     const NAME: &'static str = "transactions";
     const WILDCARD_LIST: &'static [&'static str] = &[
         TransactionsSubject::WILDCARD,
         TransactionsByIdSubject::WILDCARD,
     ];
+
+    fn block_height_subject(height: u32) -> Option<String> {
+        Some(
+            TransactionsSubject::new()
+                .with_block_height(Some(height.into()))
+                .parse(),
+        )
+    }
 }
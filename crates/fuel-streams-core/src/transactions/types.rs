@@ -2,7 +2,9 @@ use fuel_core_types::fuel_tx;
 
 use crate::types::*;
 
-#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug, Default, Clone, PartialEq, Serialize, Deserialize, JsonSchema,
+)]
 pub struct StorageSlot {
     pub key: HexString,
     pub value: HexString,
@@ -23,7 +25,9 @@ impl From<&FuelCoreStorageSlot> for StorageSlot {
     }
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug, Default, Clone, PartialEq, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct Transaction {
     pub id: Bytes32,
@@ -47,6 +51,7 @@ pub struct Transaction {
     pub mint_amount: Option<u64>,
     pub mint_asset_id: Option<AssetId>,
     pub mint_gas_price: Option<u64>,
+    #[schemars(with = "Option<crate::schema::PoliciesSchema>")]
     pub policies: Option<FuelCorePolicies>,
     pub proof_set: Vec<Bytes32>,
     pub raw_payload: HexString,
@@ -59,7 +64,9 @@ pub struct Transaction {
     pub storage_slots: Vec<StorageSlot>,
     pub subsection_index: Option<u16>,
     pub subsections_number: Option<u16>,
+    #[schemars(with = "Option<crate::schema::TxPointerSchema>")]
     pub tx_pointer: Option<FuelCoreTxPointer>,
+    #[schemars(with = "Option<crate::schema::UpgradePurposeSchema>")]
     pub upgrade_purpose: Option<FuelCoreUpgradePurpose>,
     pub witnesses: Vec<HexString>,
     pub receipts: Vec<Receipt>,
@@ -410,7 +417,9 @@ impl Transaction {
     }
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug, Default, Clone, PartialEq, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "PascalCase")]
 pub enum TransactionKind {
     #[default]
@@ -460,7 +469,9 @@ impl MockTransaction {
     }
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug, Default, Clone, PartialEq, Serialize, Deserialize, JsonSchema,
+)]
 pub enum TransactionStatus {
     Failed,
     Submitted,
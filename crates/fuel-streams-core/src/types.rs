@@ -5,7 +5,6 @@ pub use crate::{
     fuel_core_types::*,
     inputs::types::*,
     logs::types::*,
-    nats::types::*,
     outputs::types::*,
     primitive_types::*,
     receipts::types::*,
@@ -17,3 +16,15 @@ pub use crate::{
 // General
 // ------------------------------------------------------------------------
 pub type BoxedResult<T> = Result<T, Box<dyn Error>>;
+
+// ------------------------------------------------------------------------
+// Compression
+// ------------------------------------------------------------------------
+/// Payloads published with the `zstd-dict` compression strategy can only be
+/// decoded once the dictionary they were compressed with is loaded into the
+/// global `ZstdDictionaryStore`.
+pub use fuel_data_parser::{
+    ZstdDictionary,
+    ZstdDictionaryStore,
+    DEFAULT_ZSTD_DICTIONARY_SIZE,
+};
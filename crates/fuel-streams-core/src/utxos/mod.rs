@@ -4,9 +4,9 @@ pub mod types;
 pub use subjects::*;
 
 use super::types::*;
-use crate::{StreamEncoder, Streamable};
+use crate::{protobuf::impl_protobuf_stream_encoder, Streamable};
 
-impl StreamEncoder for Utxo {}
+impl_protobuf_stream_encoder!(Utxo);
 impl Streamable for Utxo {
     const NAME: &'static str = "utxos";
     const WILDCARD_LIST: &'static [&'static str] = &[UtxosSubject::WILDCARD];
@@ -1,13 +1,50 @@
 //! This binary subscribes to events emitted from a Fuel client or node
 //! to publish streams that can consumed via the `fuel-streams` SDK.
-use std::net::SocketAddr;
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use clap::Parser;
+use fuel_data_parser::{
+    parse_compression_level,
+    CompressionRegistry,
+    CompressionStrategy,
+    DataParser,
+    SerializationType,
+    ZstdDictionary,
+    ZstdDictionaryCompressionStrategy,
+    ZstdDictionaryStore,
+};
+use fuel_streams_core::{
+    nats::{NatsClientOpts, NatsUserRole},
+    parse_signing_key,
+    RetentionConfig,
+    SigningKey,
+    StreamStorage,
+    DEFAULT_PUBLISH_WINDOW,
+    DUPLICATE_WINDOW_SECS,
+    FUEL_BLOCK_TIME_SECS,
+    MAX_RETENTION_BLOCKS,
+};
+
+use crate::publisher::Streams;
+
+/// The `--compression` value publishing payloads uncompressed.
+pub const NO_COMPRESSION: &str = "none";
 
 /// CLI structure for parsing command-line arguments.
 ///
 /// - `nats_url`: The URL of the NATS server to connect to.
 /// - `fuel_core_config`: Configuration for the Fuel Core service, parsed using a flattened command.
+/// - `serialization`: The serialization of published payloads.
+/// - `compression`, `compression_level`: The compression applied to published payloads.
+/// - `signing_key`: The ed25519 key published payloads are signed with.
+/// - `retention_*`: The retention of the streams published to.
+/// - `stream_retention`: The retention of specific streams, overriding the `retention_*` options.
+/// - `publish_window`: The number of publishes of a batch awaiting their ack at once.
 #[derive(Clone, Parser)]
 pub struct Cli {
     /// Nats connection url
@@ -30,4 +67,353 @@ pub struct Cli {
         help = "Address for the Actix Web server to bind to."
     )]
     pub server_addr: SocketAddr,
+    /// Serialization of published payloads
+    #[arg(
+        long,
+        value_name = "NAME",
+        env = "PUBLISHER_SERIALIZATION",
+        default_value = "json",
+        help = "Serialization of published payloads: json, bincode, postcard, messagepack, cbor or protobuf."
+    )]
+    pub serialization: String,
+    /// Compression strategy for published payloads
+    #[arg(
+        long,
+        value_name = "NAME",
+        env = "PUBLISHER_COMPRESSION",
+        default_value = NO_COMPRESSION,
+        help = "Compression strategy for published payloads, by registered name (e.g. zlib, zstd, brotli, lz4), or none to publish them uncompressed."
+    )]
+    pub compression: String,
+    /// Compression level for published payloads
+    #[arg(
+        long,
+        value_name = "LEVEL",
+        env = "PUBLISHER_COMPRESSION_LEVEL",
+        default_value = "fastest",
+        help = "Compression level for published payloads: fastest, best, default or an algorithm specific integer."
+    )]
+    pub compression_level: String,
+    /// Trained zstd dictionary for the `zstd-dict` compression strategy
+    #[arg(
+        long,
+        value_name = "PATH",
+        env = "PUBLISHER_COMPRESSION_DICTIONARY",
+        help = "Path to a trained zstd dictionary, required by the zstd-dict compression strategy. Consumers must load the same dictionary."
+    )]
+    pub compression_dictionary: Option<PathBuf>,
+    /// Ed25519 key published payloads are signed with
+    #[arg(
+        long,
+        value_name = "HEX",
+        env = "PUBLISHER_SIGNING_KEY",
+        hide_env_values = true,
+        help = "Hex encoded 32 bytes seed of the ed25519 key published payloads are signed with. Payloads are not signed when unset."
+    )]
+    pub signing_key: Option<String>,
+    /// Max age of stream messages
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        env = "PUBLISHER_RETENTION_MAX_AGE",
+        default_value_t = FUEL_BLOCK_TIME_SECS * MAX_RETENTION_BLOCKS,
+        help = "Number of seconds messages are kept in streams, 0 keeping them forever. Blocks older than that are not published."
+    )]
+    pub retention_max_age: u64,
+    /// Max size of streams
+    #[arg(
+        long,
+        value_name = "BYTES",
+        env = "PUBLISHER_RETENTION_MAX_BYTES",
+        help = "Size in bytes streams can grow to before their oldest messages are removed. Unlimited when unset."
+    )]
+    pub retention_max_bytes: Option<u64>,
+    /// Max number of messages of streams
+    #[arg(
+        long,
+        value_name = "COUNT",
+        env = "PUBLISHER_RETENTION_MAX_MESSAGES",
+        help = "Number of messages streams keep before their oldest messages are removed. Unlimited when unset."
+    )]
+    pub retention_max_messages: Option<u64>,
+    /// Number of replicas of streams
+    #[arg(
+        long,
+        value_name = "COUNT",
+        env = "PUBLISHER_RETENTION_REPLICAS",
+        default_value_t = 1,
+        help = "Number of NATS servers streams are replicated to."
+    )]
+    pub retention_replicas: usize,
+    /// Storage of streams
+    #[arg(
+        long,
+        value_name = "NAME",
+        env = "PUBLISHER_RETENTION_STORAGE",
+        default_value = "file",
+        help = "Storage of streams: file or memory. The storage of existing streams can't be changed."
+    )]
+    pub retention_storage: String,
+    /// History depth of streams
+    #[arg(
+        long,
+        value_name = "COUNT",
+        env = "PUBLISHER_RETENTION_HISTORY",
+        default_value_t = 1,
+        help = "Number of messages kept per subject, at most 64."
+    )]
+    pub retention_history: u8,
+    /// Duplicate window of streams
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        env = "PUBLISHER_RETENTION_DUPLICATE_WINDOW",
+        default_value_t = DUPLICATE_WINDOW_SECS,
+        help = "Number of seconds within which a payload published again for the same block is deduplicated, at most the max age."
+    )]
+    pub retention_duplicate_window: u64,
+    /// Retention of specific streams
+    #[arg(
+        long,
+        value_name = "STREAM:KEY=VALUE,...",
+        env = "PUBLISHER_STREAM_RETENTION",
+        value_delimiter = ';',
+        help = "Retention of a specific stream, overriding the retention options for it, e.g. blocks:max-age=0,replicas=3. Keys are max-age, max-bytes, max-messages, replicas, storage, history and duplicate-window, where 0 is unlimited for max-bytes and max-messages."
+    )]
+    pub stream_retention: Vec<String>,
+    /// Publishes of a batch awaiting their ack at once
+    #[arg(
+        long,
+        value_name = "COUNT",
+        env = "PUBLISHER_PUBLISH_WINDOW",
+        default_value_t = DEFAULT_PUBLISH_WINDOW,
+        help = "Number of publishes of a batch awaiting their ack at once, where 1 publishes one message at a time."
+    )]
+    pub publish_window: usize,
+}
+
+impl Cli {
+    /// Builds the `DataParser` used to encode published payloads from the
+    /// serialization and compression options.
+    pub fn data_parser(&self) -> anyhow::Result<DataParser> {
+        let strategy = compression_strategy(
+            &self.compression,
+            &self.compression_level,
+            self.compression_dictionary.as_deref(),
+        )?;
+
+        let serialization_type: SerializationType =
+            self.serialization.parse().map_err(|_| {
+                anyhow::anyhow!(
+                    "Unknown serialization {}, expected one of: json, bincode, postcard, messagepack, cbor, protobuf",
+                    self.serialization
+                )
+            })?;
+
+        let parser =
+            DataParser::default().with_serialization_type(serialization_type);
+        Ok(match strategy {
+            Some(strategy) => parser.with_compression_strategy(&strategy),
+            None => parser,
+        })
+    }
+
+    /// Builds the options of the NATS client streams are published with,
+    /// including their retention.
+    pub fn nats_client_opts(&self) -> anyhow::Result<NatsClientOpts> {
+        let retention = RetentionConfig::default()
+            .with_max_age(
+                non_zero(self.retention_max_age).map(Duration::from_secs),
+            )
+            .with_max_bytes(self.retention_max_bytes)
+            .with_max_messages(self.retention_max_messages)
+            .with_replicas(self.retention_replicas)
+            .with_storage(parse_storage(&self.retention_storage)?)
+            .with_history(parse_history(self.retention_history)?)
+            .with_duplicate_window(Duration::from_secs(
+                self.retention_duplicate_window,
+            ));
+
+        let mut opts = NatsClientOpts::new(&self.nats_url)
+            .with_role(NatsUserRole::Admin)
+            .with_retention(retention.clone())
+            .with_publish_window(self.publish_window);
+        for stream_retention in &self.stream_retention {
+            let (name, retention) =
+                parse_stream_retention(stream_retention, &retention)?;
+            opts = opts.with_stream_retention(name, retention);
+        }
+
+        Ok(opts)
+    }
+
+    /// Parses the key published payloads are signed with, if any.
+    pub fn signing_key(&self) -> anyhow::Result<Option<SigningKey>> {
+        self.signing_key
+            .as_deref()
+            .map(parse_signing_key)
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("Invalid signing key: {e}"))
+    }
+}
+
+/// Parses the retention of a stream, given as `STREAM:KEY=VALUE,...`, on
+/// top of the retention of every stream.
+fn parse_stream_retention(
+    value: &str,
+    retention: &RetentionConfig,
+) -> anyhow::Result<(String, RetentionConfig)> {
+    let (name, options) = value.split_once(':').ok_or_else(|| {
+        anyhow::anyhow!(
+            "Invalid stream retention {value}, expected STREAM:KEY=VALUE,..."
+        )
+    })?;
+    if !Streams::NAMES.contains(&name) {
+        anyhow::bail!(
+            "Unknown stream {name}, expected one of: {}",
+            Streams::NAMES.join(", ")
+        );
+    }
+
+    let mut retention = retention.clone();
+    for option in options.split(',').filter(|option| !option.is_empty()) {
+        let (key, value) = option.split_once('=').ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid retention option {option}, expected KEY=VALUE"
+            )
+        })?;
+        retention = match key {
+            "max-age" => retention.with_max_age(
+                non_zero(value.parse()?).map(Duration::from_secs),
+            ),
+            "max-bytes" => retention.with_max_bytes(non_zero(value.parse()?)),
+            "max-messages" => {
+                retention.with_max_messages(non_zero(value.parse()?))
+            }
+            "replicas" => retention.with_replicas(value.parse()?),
+            "storage" => retention.with_storage(parse_storage(value)?),
+            "history" => retention.with_history(parse_history(value.parse()?)?),
+            "duplicate-window" => retention
+                .with_duplicate_window(Duration::from_secs(value.parse()?)),
+            _ => anyhow::bail!(
+                "Unknown retention option {key}, expected one of: max-age, max-bytes, max-messages, replicas, storage, history, duplicate-window"
+            ),
+        };
+    }
+
+    Ok((name.to_string(), retention))
+}
+
+/// Resolves the compression strategy of published payloads by name, or
+/// `None` for `NO_COMPRESSION`, loading the zstd dictionary it uses if any.
+fn compression_strategy(
+    name: &str,
+    level: &str,
+    dictionary: Option<&Path>,
+) -> anyhow::Result<Option<Arc<dyn CompressionStrategy>>> {
+    if name == NO_COMPRESSION {
+        return Ok(None);
+    }
+
+    match dictionary {
+        Some(path) => {
+            let dictionary = ZstdDictionary::from_bytes(std::fs::read(path)?)?;
+            tracing::info!(
+                "Loaded zstd dictionary {} from {}",
+                dictionary.id(),
+                path.display()
+            );
+            ZstdDictionaryStore::global().load(dictionary);
+        }
+        None if name == ZstdDictionaryCompressionStrategy::NAME => {
+            anyhow::bail!(
+                "The {name} compression strategy requires --compression-dictionary"
+            )
+        }
+        None => {}
+    }
+
+    let level = parse_compression_level(level)?;
+    let registry = CompressionRegistry::global();
+    let strategy = registry.get_with_level(name, level).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown compression strategy {name}, expected {NO_COMPRESSION} or one of: {}",
+            registry.names().join(", ")
+        )
+    })?;
+    Ok(Some(strategy))
+}
+
+fn parse_storage(value: &str) -> anyhow::Result<StreamStorage> {
+    match value {
+        "file" => Ok(StreamStorage::File),
+        "memory" => Ok(StreamStorage::Memory),
+        _ => anyhow::bail!("Unknown storage {value}, expected file or memory"),
+    }
+}
+
+fn parse_history(history: u8) -> anyhow::Result<u8> {
+    match history {
+        1..=64 => Ok(history),
+        _ => anyhow::bail!("History must be between 1 and 64, got {history}"),
+    }
+}
+
+fn non_zero(value: u64) -> Option<u64> {
+    (value > 0).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use fuel_streams_core::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_stream_retention() {
+        let retention = RetentionConfig::default();
+
+        let (name, blocks_retention) = parse_stream_retention(
+            "blocks:max-age=0,replicas=3,storage=memory,duplicate-window=600",
+            &retention,
+        )
+        .unwrap();
+        assert_eq!(name, Block::NAME);
+        assert_eq!(
+            blocks_retention,
+            retention
+                .clone()
+                .with_max_age(None)
+                .with_replicas(3)
+                .with_storage(StreamStorage::Memory)
+                .with_duplicate_window(Duration::from_secs(600))
+        );
+
+        assert!(parse_stream_retention("blocks", &retention).is_err());
+        assert!(
+            parse_stream_retention("unknown:history=2", &retention).is_err()
+        );
+        assert!(
+            parse_stream_retention("blocks:history=65", &retention).is_err()
+        );
+        assert!(parse_stream_retention("blocks:depth=2", &retention).is_err());
+    }
+
+    #[test]
+    fn test_compression_strategy() {
+        assert!(compression_strategy(NO_COMPRESSION, "fastest", None)
+            .unwrap()
+            .is_none());
+        let strategy = compression_strategy("zlib", "fastest", None).unwrap();
+        assert_eq!(strategy.unwrap().name(), "zlib");
+
+        assert!(compression_strategy("unknown", "fastest", None).is_err());
+        let error = compression_strategy(
+            ZstdDictionaryCompressionStrategy::NAME,
+            "fastest",
+            None,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("--compression-dictionary"));
+    }
 }
@@ -14,6 +14,20 @@ use fuel_streams_publisher::{
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
+    let data_parser = cli.data_parser()?;
+    if fuel_streams_core::set_default_data_parser(data_parser).is_err() {
+        anyhow::bail!("Data parser was already set");
+    }
+    if let Some(signing_key) = cli.signing_key()? {
+        tracing::info!(
+            "Signing payloads with public key {}",
+            hex::encode(signing_key.verifying_key().to_bytes())
+        );
+        if fuel_streams_core::set_signing_key(signing_key).is_err() {
+            anyhow::bail!("Signing key was already set");
+        }
+    }
+
     let fuel_core: Arc<dyn FuelCoreLike> =
         FuelCore::new(cli.fuel_core_config).await?;
     fuel_core.start().await;
@@ -21,9 +35,10 @@ async fn main() -> anyhow::Result<()> {
     let telemetry = Telemetry::new().await?;
     telemetry.start().await?;
 
+    let nats_client_opts = cli.nats_client_opts()?;
     let publisher = fuel_streams_publisher::Publisher::new(
         Arc::clone(&fuel_core),
-        &cli.nats_url,
+        &nats_client_opts,
         telemetry.clone(),
     )
     .await?;
@@ -31,11 +31,10 @@ pub struct Publisher {
 impl Publisher {
     pub async fn new(
         fuel_core: Arc<dyn FuelCoreLike>,
-        nats_url: &str,
+        nats_client_opts: &NatsClientOpts,
         telemetry: Arc<Telemetry>,
     ) -> anyhow::Result<Self> {
-        let nats_client_opts = NatsClientOpts::admin_opts(nats_url);
-        let nats_client = NatsClient::connect(&nats_client_opts).await?;
+        let nats_client = NatsClient::connect(nats_client_opts).await?;
         let streams = Arc::new(Streams::new(&nats_client).await);
 
         telemetry.record_streams_count(
@@ -90,8 +89,13 @@ impl Publisher {
     }
 
     async fn flush_await_all_streams(nats_client: &NatsClient) {
+        // In-memory streams have nothing in flight
+        let Ok(nats_client) = nats_client.nats_client() else {
+            return;
+        };
+
         tracing::info!("Flushing in-flight messages to nats ...");
-        match nats_client.nats_client.flush().await {
+        match nats_client.flush().await {
             Ok(_) => {
                 tracing::info!("Flushed all streams successfully!");
             }
@@ -126,6 +130,10 @@ impl Publisher {
                 .fuel_core
                 .get_sealed_block_time_by_height(latest_fuel_core_height as u32)
                 .to_unix();
+            // Blocks the blocks stream wouldn't retain are not published
+            let retention = self.nats_client.opts.retention(Block::NAME);
+            let max_age_secs =
+                retention.max_age.map(|max_age| max_age.as_secs() as i64);
 
             let mut height = last_published_height;
             while height <= latest_fuel_core_height {
@@ -144,9 +152,9 @@ impl Publisher {
                         .get_sealed_block_time_by_height(height as u32)
                         .to_unix();
 
-                        if fuel_block_time_unix < latest_fuel_block_time_unix - (FUEL_BLOCK_TIME_SECS  * MAX_RETENTION_BLOCKS) as i64 {
+                        if let Some(max_age_secs) = max_age_secs.filter(|max_age_secs| fuel_block_time_unix < latest_fuel_block_time_unix - max_age_secs) {
                             // Skip publishing for this block and move to the next height
-                            tracing::warn!("Block {} with time: {} is more than 100 blocks behind chain tip, skipped publishing", height, fuel_block_time_unix);
+                            tracing::warn!("Block {} with time: {} is more than {} seconds behind chain tip, skipped publishing", height, fuel_block_time_unix, max_age_secs);
                             return (Ok(()), None);
                         }
 
@@ -211,6 +219,11 @@ impl Publisher {
         let base_asset_id = Arc::new(*self.fuel_core.base_asset_id());
         let block_producer = Arc::new(block_producer.clone());
         let block_height = block.header().consensus().height;
+        let block_context = BlockContext {
+            chain_id: (*chain_id).into(),
+            height: *block_height,
+            timestamp_ms: block.header().time().to_unix() as u64 * 1000,
+        };
         let txs = block.transactions();
         let transaction_ids = txs
             .iter()
@@ -231,6 +244,7 @@ impl Publisher {
             base_asset_id,
             block_producer: Arc::clone(&block_producer),
             block_height: Arc::new(block_height.into()),
+            block_context: Arc::new(block_context),
             telemetry: self.telemetry.clone(),
             consensus: Arc::new(consensus),
             offchain_database,
@@ -272,6 +286,7 @@ pub struct PublishOpts {
     pub base_asset_id: Arc<FuelCoreAssetId>,
     pub block_producer: Arc<Address>,
     pub block_height: Arc<BlockHeight>,
+    pub block_context: Arc<BlockContext>,
     pub telemetry: Arc<Telemetry>,
     pub consensus: Arc<Consensus>,
     pub offchain_database: Arc<OffchainDatabase>,
@@ -286,13 +301,18 @@ pub fn publish<S: Streamable + 'static>(
     let opts = Arc::clone(opts);
     let payload = Arc::clone(&packet.payload);
     let subject = Arc::clone(&packet.subject);
+    let headers = packet.headers.clone();
     let telemetry = Arc::clone(&opts.telemetry);
     let wildcard = packet.subject.wildcard();
 
     tokio::spawn(async move {
         let _permit = opts.semaphore.acquire().await?;
 
-        match stream.publish(&*subject, &payload).await {
+        let metadata = StreamMetadata::now().with_block(&opts.block_context);
+        match stream
+            .publish_with_headers(&*subject, &payload, metadata, headers)
+            .await
+        {
             Ok(published_data_size) => {
                 telemetry.log_info(&format!(
                     "Successfully published for stream: {}",
@@ -321,3 +341,56 @@ pub fn publish<S: Streamable + 'static>(
         }
     })
 }
+
+/// Publishes packets as a pipelined batch, see `Stream::publish_batch`.
+pub fn publish_batch<S: Streamable + 'static>(
+    packets: Vec<PublishPacket<S>>,
+    stream: &Stream<S>,
+    opts: &Arc<PublishOpts>,
+) -> JoinHandle<anyhow::Result<()>> {
+    let stream = stream.to_owned();
+    let opts = Arc::clone(opts);
+    let telemetry = Arc::clone(&opts.telemetry);
+
+    tokio::spawn(async move {
+        let _permit = opts.semaphore.acquire().await?;
+
+        let metadata = StreamMetadata::now().with_block(&opts.block_context);
+        match stream.publish_batch(&packets, metadata).await {
+            Ok(published_data_sizes) => {
+                telemetry.log_info(&format!(
+                    "Successfully published {} messages for stream: {}",
+                    packets.len(),
+                    S::NAME
+                ));
+                for (packet, published_data_size) in
+                    packets.iter().zip(published_data_sizes)
+                {
+                    telemetry.update_publisher_success_metrics(
+                        packet.subject.wildcard(),
+                        published_data_size,
+                        &opts.chain_id,
+                        &opts.block_producer,
+                    );
+                }
+
+                Ok(())
+            }
+            Err(e) => {
+                telemetry.log_error(&e.to_string());
+                // The messages published before the failing one can't be
+                // told apart, so the whole batch counts as failed
+                for packet in &packets {
+                    telemetry.update_publisher_error_metrics(
+                        packet.subject.wildcard(),
+                        &opts.chain_id,
+                        &opts.block_producer,
+                        &e.to_string(),
+                    );
+                }
+
+                anyhow::bail!("Failed to publish: {}", e.to_string())
+            }
+        }
+    })
+}
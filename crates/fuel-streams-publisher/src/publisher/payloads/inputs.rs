@@ -11,18 +11,12 @@ use fuel_core_types::fuel_tx::input::{
 };
 use fuel_streams_core::prelude::*;
 use rayon::prelude::*;
-use tokio::task::JoinHandle;
 
-use crate::{publish, PublishOpts};
-
-pub fn publish_tasks(
+pub fn packets(
     tx: &FuelCoreTransaction,
     tx_id: &Bytes32,
-    stream: &Stream<Input>,
-    opts: &Arc<PublishOpts>,
-) -> Vec<JoinHandle<anyhow::Result<()>>> {
-    let packets = tx
-        .inputs()
+) -> Vec<PublishPacket<Input>> {
+    tx.inputs()
         .par_iter()
         .enumerate()
         .flat_map(move |(index, input)| {
@@ -44,11 +38,6 @@ pub fn publish_tasks(
 
             packets
         })
-        .collect::<Vec<_>>();
-
-    packets
-        .iter()
-        .map(|packet| publish(packet, Arc::new(stream.to_owned()), opts))
         .collect()
 }
 
@@ -1,19 +1,12 @@
-use std::sync::Arc;
-
 use fuel_streams_core::prelude::*;
 use rayon::prelude::*;
-use tokio::task::JoinHandle;
-
-use crate::{publish, PublishOpts};
 
-pub fn publish_tasks(
+pub fn packets(
     tx_id: &Bytes32,
-    stream: &Stream<Log>,
-    opts: &Arc<PublishOpts>,
+    block_height: &BlockHeight,
     receipts: &Vec<FuelCoreReceipt>,
-) -> Vec<JoinHandle<anyhow::Result<()>>> {
-    let block_height = (*opts.block_height).clone();
-    let packets = receipts
+) -> Vec<PublishPacket<Log>> {
+    receipts
         .par_iter()
         .enumerate()
         .filter_map(|(index, receipt)| match receipt {
@@ -30,10 +23,5 @@ pub fn publish_tasks(
             )),
             _ => None,
         })
-        .collect::<Vec<_>>();
-
-    packets
-        .iter()
-        .map(|packet| publish(packet, Arc::new(stream.to_owned()), opts))
         .collect()
 }
@@ -2,18 +2,12 @@ use std::sync::Arc;
 
 use fuel_streams_core::prelude::*;
 use rayon::prelude::*;
-use tokio::task::JoinHandle;
 
-use crate::{publish, PublishOpts};
-
-pub fn publish_tasks(
+pub fn packets(
     tx: &FuelCoreTransaction,
     tx_id: &Bytes32,
-    stream: &Stream<Output>,
-    opts: &Arc<PublishOpts>,
-) -> Vec<JoinHandle<anyhow::Result<()>>> {
-    let packets: Vec<PublishPacket<Output>> = tx
-        .outputs()
+) -> Vec<PublishPacket<Output>> {
+    tx.outputs()
         .par_iter()
         .enumerate()
         .flat_map(|(index, output)| {
@@ -36,11 +30,6 @@ pub fn publish_tasks(
 
             packets
         })
-        .collect();
-
-    packets
-        .iter()
-        .map(|packet| publish(packet, Arc::new(stream.to_owned()), opts))
         .collect()
 }
 
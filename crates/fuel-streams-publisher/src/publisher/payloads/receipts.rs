@@ -2,17 +2,12 @@ use std::sync::Arc;
 
 use fuel_streams_core::prelude::*;
 use rayon::prelude::*;
-use tokio::task::JoinHandle;
 
-use crate::{publish, PublishOpts};
-
-pub fn publish_tasks(
+pub fn packets(
     tx_id: &Bytes32,
-    stream: &Stream<Receipt>,
-    opts: &Arc<PublishOpts>,
     receipts: &Vec<FuelCoreReceipt>,
-) -> Vec<JoinHandle<anyhow::Result<()>>> {
-    let packets: Vec<PublishPacket<Receipt>> = receipts
+) -> Vec<PublishPacket<Receipt>> {
+    receipts
         .par_iter()
         .enumerate()
         .flat_map(|(index, receipt)| {
@@ -34,11 +29,6 @@ pub fn publish_tasks(
 
             packets
         })
-        .collect();
-
-    packets
-        .iter()
-        .map(|packet| publish(packet, Arc::new(stream.to_owned()), opts))
         .collect()
 }
 
@@ -5,16 +5,11 @@ use fuel_streams_core::prelude::*;
 use rayon::prelude::*;
 use tokio::task::JoinHandle;
 
-use super::{
-    inputs::{self, publish_tasks as publish_inputs},
-    logs::publish_tasks as publish_logs,
-    outputs::{self, publish_tasks as publish_outputs},
-    receipts::{self, publish_tasks as publish_receipts},
-    sha256,
-    utxos::publish_tasks as publish_utxos,
-};
-use crate::{publish, FuelCoreLike, PublishOpts, Streams};
+use super::{inputs, logs, outputs, receipts, sha256, utxos};
+use crate::{publish_batch, FuelCoreLike, PublishOpts, Streams};
 
+/// Publishes the payloads of the transactions of a block, with a batch per
+/// stream.
 pub fn publish_all_tasks(
     transactions: &[FuelCoreTransaction],
     streams: Streams,
@@ -22,9 +17,17 @@ pub fn publish_all_tasks(
     fuel_core: &dyn FuelCoreLike,
 ) -> anyhow::Result<Vec<JoinHandle<anyhow::Result<()>>>> {
     let offchain_database = Arc::clone(&opts.offchain_database);
-    let mut tasks = vec![];
-
-    for tx_item @ (_, tx) in transactions.iter().enumerate() {
+    let block_height = &opts.block_height;
+    let base_asset_id = &opts.base_asset_id;
+    let mut transaction_packets = vec![];
+    let mut input_packets = vec![];
+    let mut output_packets = vec![];
+    let mut receipt_packets = vec![];
+    let mut log_packets = vec![];
+    let mut utxo_packets = vec![];
+
+    for tx_item @ (tx_index, tx) in transactions.iter().enumerate() {
+        let tx_index = tx_index as u32;
         let tx_id = tx.id(&opts.chain_id);
         let tx_status: TransactionStatus = offchain_database
             .get_tx_status(&tx_id)?
@@ -35,52 +38,50 @@ pub fn publish_all_tasks(
 
         let tx_id = tx_id.into();
 
-        tasks.extend(publish_tasks(
-            tx_item,
-            &tx_id,
-            &tx_status,
-            &streams.transactions,
-            opts,
-            &receipts,
+        transaction_packets.extend(with_tx_index(
+            packets_from_tx(
+                tx_item,
+                &tx_id,
+                &tx_status,
+                base_asset_id,
+                block_height,
+                &receipts,
+            ),
+            tx_index,
         ));
-        tasks.extend(publish_inputs(tx, &tx_id, &streams.inputs, opts));
-        tasks.extend(publish_outputs(tx, &tx_id, &streams.outputs, opts));
-        tasks.extend(publish_receipts(
-            &tx_id,
-            &streams.receipts,
-            opts,
-            &receipts,
+        input_packets
+            .extend(with_tx_index(inputs::packets(tx, &tx_id), tx_index));
+        output_packets
+            .extend(with_tx_index(outputs::packets(tx, &tx_id), tx_index));
+        receipt_packets.extend(with_tx_index(
+            receipts::packets(&tx_id, &receipts),
+            tx_index,
         ));
-        tasks.extend(publish_outputs(tx, &tx_id, &streams.outputs, opts));
-        tasks.extend(publish_logs(&tx_id, &streams.logs, opts, &receipts));
-        tasks.extend(publish_utxos(tx, &tx_id, &streams.utxos, opts));
+        log_packets.extend(with_tx_index(
+            logs::packets(&tx_id, block_height, &receipts),
+            tx_index,
+        ));
+        utxo_packets
+            .extend(with_tx_index(utxos::packets(tx, &tx_id), tx_index));
     }
 
-    Ok(tasks)
+    Ok(vec![
+        publish_batch(transaction_packets, &streams.transactions, opts),
+        publish_batch(input_packets, &streams.inputs, opts),
+        publish_batch(output_packets, &streams.outputs, opts),
+        publish_batch(receipt_packets, &streams.receipts, opts),
+        publish_batch(log_packets, &streams.logs, opts),
+        publish_batch(utxo_packets, &streams.utxos, opts),
+    ])
 }
 
-fn publish_tasks(
-    tx_item: (usize, &FuelCoreTransaction),
-    tx_id: &Bytes32,
-    tx_status: &TransactionStatus,
-    stream: &Stream<Transaction>,
-    opts: &Arc<PublishOpts>,
-    receipts: &Vec<FuelCoreReceipt>,
-) -> Vec<JoinHandle<anyhow::Result<()>>> {
-    let block_height = &opts.block_height;
-    let base_asset_id = &opts.base_asset_id;
-
-    packets_from_tx(
-        tx_item,
-        tx_id,
-        tx_status,
-        base_asset_id,
-        block_height,
-        receipts,
-    )
-    .iter()
-    .map(|packet| publish(packet, Arc::new(stream.to_owned()), opts))
-    .collect()
+fn with_tx_index<S: Streamable>(
+    packets: Vec<PublishPacket<S>>,
+    tx_index: u32,
+) -> impl Iterator<Item = PublishPacket<S>> {
+    packets
+        .into_iter()
+        .map(move |packet| packet.with_tx_index(tx_index))
 }
 
 fn packets_from_tx(
@@ -1,5 +1,3 @@
-use std::sync::Arc;
-
 use fuel_core_types::fuel_tx::{
     input::{
         coin::{CoinPredicate, CoinSigned},
@@ -16,25 +14,14 @@ use fuel_core_types::fuel_tx::{
 };
 use fuel_streams_core::prelude::*;
 use rayon::prelude::*;
-use tokio::task::JoinHandle;
-
-use crate::{publish, PublishOpts};
 
-pub fn publish_tasks(
+pub fn packets(
     tx: &FuelCoreTransaction,
     tx_id: &Bytes32,
-    stream: &Stream<Utxo>,
-    opts: &Arc<PublishOpts>,
-) -> Vec<JoinHandle<anyhow::Result<()>>> {
-    let packets = tx
-        .inputs()
+) -> Vec<PublishPacket<Utxo>> {
+    tx.inputs()
         .par_iter()
         .filter_map(|input| utxo_packet(input, tx_id, input.utxo_id().cloned()))
-        .collect::<Vec<_>>();
-
-    packets
-        .into_iter()
-        .map(|packet| publish(&packet, Arc::new(stream.to_owned()), opts))
         .collect()
 }
 
@@ -15,6 +15,17 @@ pub struct Streams {
 }
 
 impl Streams {
+    /// Names of the streams, as used in their retention settings.
+    pub const NAMES: &'static [&'static str] = &[
+        Transaction::NAME,
+        Block::NAME,
+        Input::NAME,
+        Output::NAME,
+        Receipt::NAME,
+        Utxo::NAME,
+        Log::NAME,
+    ];
+
     pub async fn new(nats_client: &NatsClient) -> Self {
         Self {
             transactions: Stream::<Transaction>::new(nats_client).await,
@@ -66,7 +66,7 @@ mod tests {
     use fuel_core::service::Config;
     use fuel_core_bin::FuelService;
     use fuel_core_services::State;
-    use fuel_streams_core::prelude::NATS_URL;
+    use fuel_streams_core::prelude::{NatsClientOpts, NATS_URL};
 
     use crate::{
         server::state::{HealthResponse, ServerState},
@@ -84,9 +84,11 @@ mod tests {
         let telemetry = Telemetry::new().await.unwrap();
 
         let fuel_core = FuelCore::from(fuel_service);
-        let publisher = Publisher::new(fuel_core.arc(), NATS_URL, telemetry)
-            .await
-            .unwrap();
+        let nats_client_opts = NatsClientOpts::admin_opts(NATS_URL);
+        let publisher =
+            Publisher::new(fuel_core.arc(), &nats_client_opts, telemetry)
+                .await
+                .unwrap();
         let state = ServerState::new(publisher).await;
         assert!(state.publisher.nats_client.is_connected());
 
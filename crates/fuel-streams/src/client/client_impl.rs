@@ -72,4 +72,28 @@ impl Client {
             .map_err(ClientError::ConnectionFailed)?;
         Ok(Self { conn })
     }
+
+    /// Creates a client that doesn't connect to NATS, and reads streams kept
+    /// in the memory of the process instead.
+    ///
+    /// Streams are shared by every in-memory client of the process, so
+    /// anything published through an in-memory `NatsClient`, e.g. by a
+    /// publisher running in the same test, can be subscribed to.
+    ///
+    /// This method is only available when compiled with the `in-memory` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fuel_streams::client::Client;
+    ///
+    /// # async fn example() -> Result<(), fuel_streams::Error> {
+    /// let client = Client::in_memory().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "in-memory")]
+    pub async fn in_memory() -> Result<Self, crate::Error> {
+        Self::with_opts(&NatsClientOpts::in_memory()).await
+    }
 }
@@ -9,6 +9,6 @@ pub enum Error {
     /// An error occurred in the stream
     StreamError(#[from] crate::stream::StreamError),
 
-    /// Consuming messages error
-    MessagesError(#[from] fuel_streams_core::types::MessagesError),
+    /// Decoding a message failed
+    DecodeError(#[from] fuel_streams_core::DecodeError),
 }
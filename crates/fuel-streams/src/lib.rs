@@ -12,10 +12,7 @@ pub mod subjects {
 }
 
 pub mod types {
-    pub use fuel_streams_core::{
-        nats::{types::*, NatsClientOpts},
-        types::*,
-    };
+    pub use fuel_streams_core::{nats::NatsClientOpts, types::*};
 
     pub use crate::client::types::*;
 }
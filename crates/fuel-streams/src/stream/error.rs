@@ -14,4 +14,49 @@ pub enum StreamError {
         #[source]
         source: fuel_streams_core::StreamError,
     },
+
+    /// Failed to receive a message from the stream
+    Receive {
+        #[source]
+        source: fuel_streams_core::StreamError,
+    },
+
+    /// Failed to query the stream
+    Query {
+        #[source]
+        source: fuel_streams_core::StreamError,
+    },
+
+    /// Failed to acknowledge a message of the stream
+    Ack {
+        #[source]
+        source: fuel_streams_core::StreamError,
+    },
+
+    /// Failed to decode a message from the stream
+    Decode {
+        #[source]
+        source: fuel_streams_core::DecodeError,
+    },
+
+    /// Filter on `{subject}` doesn't belong to the `{stream}` stream
+    InvalidFilter {
+        subject: String,
+        stream: &'static str,
+    },
+
+    /// Filter on `{subject}` partially overlaps the filter on `{existing}`
+    OverlappingFilter { subject: String, existing: String },
+
+    /// Message signature could not be verified
+    InvalidSignature {
+        #[source]
+        source: fuel_streams_core::SignatureError,
+    },
+
+    /// Message received on `{subject}` holds the payload of `{payload_subject}`
+    SubjectMismatch {
+        subject: String,
+        payload_subject: String,
+    },
 }
@@ -1,11 +1,22 @@
 mod error;
+mod queries;
 mod stream_impl;
 
 pub use error::*;
 pub use fuel_streams_core::stream::{
+    parse_verifying_key,
+    DecodeError,
+    DeliverFrom,
+    DurableConfig,
+    EncodeError,
+    EncodingError,
+    SerializationType,
+    SignatureError,
     StreamData,
     StreamEncoder,
+    StreamHeaders,
+    StreamMetadata,
     Streamable,
-    SubscribeConsumerConfig,
+    VerifyingKey,
 };
 pub use stream_impl::*;
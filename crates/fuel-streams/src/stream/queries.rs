@@ -0,0 +1,103 @@
+use fuel_streams_core::{
+    prelude::SubjectBuildable,
+    types::{Block, Bytes32, Receipt, Transaction},
+    StreamData,
+    Streamable,
+};
+
+use crate::{
+    blocks::BlocksSubject,
+    stream::{Stream, StreamError},
+    transactions::TransactionsSubject,
+};
+
+impl Stream<Block> {
+    /// Returns the block at `height`, if the stream retains it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use fuel_streams::client::Client;
+    /// use fuel_streams::stream::Stream;
+    /// use fuel_streams::blocks::Block;
+    ///
+    /// # async fn example() -> Result<(), fuel_streams::Error> {
+    /// # let client = Client::connect("nats://localhost:4222").await?;
+    /// let stream = Stream::<Block>::new(&client).await;
+    /// if let Some(block) = stream.get_block(1000).await? {
+    ///     println!("Block 1000: {:?}", block.payload);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_block(
+        &self,
+        height: u32,
+    ) -> Result<Option<StreamData<Block>>, StreamError> {
+        let subject = BlocksSubject::new().with_height(Some(height.into()));
+        self.get(subject).await
+    }
+}
+
+impl Stream<Transaction> {
+    /// Returns the transaction with the id `tx_id`, if the stream retains
+    /// it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use fuel_streams::client::Client;
+    /// use fuel_streams::stream::Stream;
+    /// use fuel_streams::transactions::Transaction;
+    /// use fuel_streams::types::Bytes32;
+    ///
+    /// # async fn example() -> Result<(), fuel_streams::Error> {
+    /// # let client = Client::connect("nats://localhost:4222").await?;
+    /// let stream = Stream::<Transaction>::new(&client).await;
+    /// let tx_id = Bytes32::zeroed();
+    /// if let Some(transaction) = stream.get_transaction(&tx_id).await? {
+    ///     println!("Transaction: {:?}", transaction.payload);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_transaction(
+        &self,
+        tx_id: &Bytes32,
+    ) -> Result<Option<StreamData<Transaction>>, StreamError> {
+        let subject =
+            TransactionsSubject::new().with_tx_id(Some(tx_id.clone()));
+        self.get(subject).await
+    }
+}
+
+impl Stream<Receipt> {
+    /// Returns the receipts of the transaction with the id `tx_id` retained
+    /// by the stream, in the order they were published in.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use fuel_streams::client::Client;
+    /// use fuel_streams::stream::Stream;
+    /// use fuel_streams::types::{Bytes32, Receipt};
+    ///
+    /// # async fn example() -> Result<(), fuel_streams::Error> {
+    /// # let client = Client::connect("nats://localhost:4222").await?;
+    /// let stream = Stream::<Receipt>::new(&client).await;
+    /// let tx_id = Bytes32::zeroed();
+    /// for receipt in stream.get_receipts(&tx_id).await? {
+    ///     println!("Receipt: {:?}", receipt.payload);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_receipts(
+        &self,
+        tx_id: &Bytes32,
+    ) -> Result<Vec<StreamData<Receipt>>, StreamError> {
+        // Receipt subjects differ by kind, but all start with the tx id
+        let subject = format!("{}.{tx_id}.>", Receipt::NAME);
+        self.get_all_raw(&subject).await
+    }
+}
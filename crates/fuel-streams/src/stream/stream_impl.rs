@@ -1,9 +1,25 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bytes::Bytes;
 use fuel_streams_core::{
     prelude::{IntoSubject, SubjectBuildable},
-    types::{DeliverPolicy, PullConsumerStream},
+    verify_payload,
+    AckableMessage,
+    DeliverFrom,
+    DurableConfig,
+    MessageAcker,
+    MessageStream,
+    StreamData,
+    StreamHeaders,
+    StreamMessage,
+    StreamMetadata,
     Streamable,
-    SubscribeConsumerConfig,
+    VerifyingKey,
 };
+use futures::{future, stream::BoxStream, StreamExt};
 
 use crate::{client::Client, stream::StreamError};
 
@@ -36,11 +52,154 @@ impl<S: SubjectBuildable> Filter<S> {
     }
 }
 
+/// Decoded messages of a subscription to a `Stream<S>`.
+pub type Subscription<S> =
+    BoxStream<'static, Result<StreamData<S>, StreamError>>;
+
+/// A message of a `Stream<S>` as received, before being decoded, so that it
+/// can be routed or filtered on its subject and headers first.
+#[derive(Debug, Clone)]
+pub struct RawMessage<S: Streamable> {
+    /// The subject the message was published on
+    pub subject: String,
+    /// The headers the message was published with
+    pub headers: StreamHeaders,
+    payload: Bytes,
+    verifying_key: Option<VerifyingKey>,
+    _marker: std::marker::PhantomData<S>,
+}
+
+impl<S: Streamable> RawMessage<S> {
+    /// The encoded payload of the message.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Decodes the message, after checking its signature when the stream it
+    /// was received from verifies them.
+    ///
+    /// The subject a payload was published with is part of what is signed,
+    /// so messages received on another subject are rejected with
+    /// `StreamError::SubjectMismatch`, as a signed payload could otherwise be
+    /// replayed on any subject.
+    pub async fn decode(&self) -> Result<StreamData<S>, StreamError> {
+        if let Some(verifying_key) = &self.verifying_key {
+            verify_payload(verifying_key, &self.payload)
+                .map_err(|source| StreamError::InvalidSignature { source })?;
+        }
+        let data = S::decode_raw(&self.payload).await.map_err(|error| {
+            StreamError::Decode {
+                source: error.with_subject(self.subject.clone()),
+            }
+        })?;
+        if data.subject != self.subject {
+            return Err(StreamError::SubjectMismatch {
+                subject: self.subject.clone(),
+                payload_subject: data.subject,
+            });
+        }
+        Ok(data)
+    }
+}
+
+/// Undecoded messages of a subscription to a `Stream<S>`.
+pub type RawSubscription<S> =
+    BoxStream<'static, Result<RawMessage<S>, StreamError>>;
+
+/// A message delivered to a durable consumer, to be acknowledged once
+/// processed.
+#[derive(Debug, Clone)]
+pub struct Delivery<S: Streamable> {
+    /// The decoded message
+    pub data: StreamData<S>,
+    /// The headers the message was published with
+    pub headers: StreamHeaders,
+    /// Number of times the message was delivered, starting at 1
+    pub delivered: u64,
+    acker: Arc<dyn MessageAcker>,
+}
+
+impl<S: Streamable> Delivery<S> {
+    /// Marks the message as processed, so that the consumer resumes after it.
+    pub async fn ack(&self) -> Result<(), StreamError> {
+        self.acker
+            .ack()
+            .await
+            .map_err(|source| StreamError::Ack { source })
+    }
+
+    /// Asks for the message to be delivered again, after `delay` if any.
+    pub async fn nak(
+        &self,
+        delay: Option<Duration>,
+    ) -> Result<(), StreamError> {
+        self.acker
+            .nak(delay)
+            .await
+            .map_err(|source| StreamError::Ack { source })
+    }
+
+    /// Marks the message as one that can't be processed, so that it isn't
+    /// delivered again.
+    pub async fn term(&self) -> Result<(), StreamError> {
+        self.acker
+            .term()
+            .await
+            .map_err(|source| StreamError::Ack { source })
+    }
+}
+
+/// Messages delivered to a durable consumer of a `Stream<S>`.
+pub type DurableSubscription<S> =
+    BoxStream<'static, Result<Delivery<S>, StreamError>>;
+
+/// The clock the times of a `StreamConfig` are compared to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeReference {
+    /// The time messages were published at
+    #[default]
+    Published,
+    /// The time of the blocks messages belong to, i.e. `BlockHeader.time`,
+    /// or the time they were published at for messages without one
+    Block,
+}
+
+impl TimeReference {
+    fn time_of(&self, metadata: &StreamMetadata) -> SystemTime {
+        let timestamp_ms = match self {
+            TimeReference::Published => metadata.timestamp_ms,
+            TimeReference::Block => {
+                metadata.block_timestamp_ms.unwrap_or(metadata.timestamp_ms)
+            }
+        };
+        UNIX_EPOCH + Duration::from_millis(timestamp_ms)
+    }
+}
+
+/// Number of messages fetched at once by queries returning several of them
+const QUERY_PAGE_SIZE: usize = 256;
+
+/// A page of the keys listed by `Stream::list_keys`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeysPage {
+    /// The keys of the page, in the order they were published in
+    pub keys: Vec<String>,
+    /// The cursor of the next page, or `None` if this one is the last
+    pub next_cursor: Option<u64>,
+}
+
 /// Configuration options for a stream.
 #[derive(Debug, Clone, Default)]
 pub struct StreamConfig {
-    /// The delivery policy for the stream.
-    pub deliver_policy: DeliverPolicy,
+    /// Where the subscription starts delivering messages from.
+    pub deliver_from: DeliverFrom,
+    /// Time after which the subscription ends, if any. It ends with the
+    /// first message past that time, making it a bounded window. A window
+    /// ending in the past only holds the messages stored when subscribing,
+    /// so it ends after the last of them on a stream that went quiet.
+    pub until: Option<SystemTime>,
+    /// The clock `DeliverFrom::StartTime` and `until` are compared to.
+    pub time_reference: TimeReference,
 }
 
 /// Represents a stream of data.
@@ -51,9 +210,10 @@ pub struct StreamConfig {
 pub struct Stream<S: Streamable> {
     stream: fuel_streams_core::Stream<S>,
     filter_subjects: Vec<String>,
+    verifying_key: Option<VerifyingKey>,
 }
 
-impl<S: Streamable> Stream<S> {
+impl<S: Streamable + 'static> Stream<S> {
     /// Creates a new `Stream` instance.
     ///
     /// # Parameters
@@ -83,18 +243,29 @@ impl<S: Streamable> Stream<S> {
         Self {
             stream,
             filter_subjects: Vec::new(),
+            verifying_key: None,
         }
     }
 
     /// Adds a filter to the stream.
     ///
+    /// Every subscription only yields the messages matching any of the
+    /// filters added, or every message of the stream if there are none.
+    /// A filter covered by one already added, e.g. `blocks.*.5` after
+    /// `blocks.*.*`, is dropped, while one covering filters already added
+    /// replaces them.
+    ///
     /// # Parameters
     ///
     /// * `filter`: An object that can be converted into a subject filter.
     ///
     /// # Returns
     ///
-    /// Returns a reference to the `Stream` instance for method chaining.
+    /// Returns a reference to the `Stream` instance for method chaining, or
+    /// `StreamError::InvalidFilter` if the subject of the filter isn't one
+    /// the stream's data is published on, e.g. a `TransactionsSubject` for a
+    /// `Stream<Block>`, or `StreamError::OverlappingFilter` if it partially
+    /// overlaps a filter already added, which JetStream consumers reject.
     ///
     /// # Examples
     ///
@@ -110,21 +281,112 @@ impl<S: Streamable> Stream<S> {
     /// let filter = Filter::<BlocksSubject>::build()
     ///     .with_producer(Some(Address::zeroed()))
     ///     .with_height(Some(5.into()));
-    /// stream.with_filter(filter);
+    /// stream.with_filter(filter)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_filter(
+        &mut self,
+        filter: impl IntoSubject,
+    ) -> Result<&Self, StreamError> {
+        let subject = Self::filter_subject(&filter)?;
+        // JetStream consumers reject overlapping filter subjects, so a filter
+        // covered by another one is dropped in favour of it
+        if self
+            .filter_subjects
+            .iter()
+            .any(|existing| subject_covers(existing, &subject))
+        {
+            return Ok(self);
+        }
+        self.filter_subjects
+            .retain(|existing| !subject_covers(&subject, existing));
+        if let Some(existing) = self
+            .filter_subjects
+            .iter()
+            .find(|existing| subjects_overlap(existing, &subject))
+        {
+            return Err(StreamError::OverlappingFilter {
+                subject,
+                existing: existing.clone(),
+            });
+        }
+        self.filter_subjects.push(subject);
+        Ok(self)
+    }
+
+    /// Returns the subject of `filter`, unless it isn't one the stream's
+    /// data is published on.
+    fn filter_subject(
+        filter: &impl IntoSubject,
+    ) -> Result<String, StreamError> {
+        let subject = filter.parse();
+        if !S::WILDCARD_LIST.contains(&filter.wildcard()) {
+            return Err(StreamError::InvalidFilter {
+                subject,
+                stream: S::NAME,
+            });
+        }
+        Ok(subject)
+    }
+
+    /// Enables the verification of message signatures.
+    ///
+    /// Once enabled, `subscribe` yields an error for every message that
+    /// wasn't signed with the key of `verifying_key`, i.e. the public key of
+    /// the publisher, instead of the message itself.
+    ///
+    /// # Parameters
+    ///
+    /// * `verifying_key`: The ed25519 public key of the publisher.
+    ///
+    /// # Returns
+    ///
+    /// Returns a reference to the `Stream` instance for method chaining.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use fuel_streams::client::Client;
+    /// use fuel_streams::stream::{parse_verifying_key, Stream};
+    /// use fuel_streams::blocks::Block;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = Client::connect("nats://localhost:4222").await?;
+    /// # let mut stream = Stream::<Block>::new(&client).await;
+    /// let key = parse_verifying_key(
+    ///     "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+    /// )?;
+    /// stream.with_verifying_key(key);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn with_filter(&mut self, filter: impl IntoSubject) -> &Self {
-        self.filter_subjects.push(filter.parse());
+    pub fn with_verifying_key(&mut self, verifying_key: VerifyingKey) -> &Self {
+        self.verifying_key = Some(verifying_key);
         self
     }
 
-    /// Subscribes to the stream.
+    /// Checks the signature of an encoded message payload when signature
+    /// verification is enabled.
+    pub fn verify(&self, payload: &[u8]) -> Result<(), StreamError> {
+        match &self.verifying_key {
+            Some(verifying_key) => verify_payload(verifying_key, payload)
+                .map(|_| ())
+                .map_err(|source| StreamError::InvalidSignature { source }),
+            None => Ok(()),
+        }
+    }
+
+    /// Subscribes to the messages published on the stream from now on,
+    /// matching any of its filters.
     ///
     /// # Returns
     ///
-    /// Returns a `Result` containing a `futures::Stream` of byte vectors on success,
-    /// or a `StreamError` on failure.
+    /// Returns a `Result` containing a `Subscription`, i.e. a
+    /// `futures::Stream` of decoded `StreamData<S>`, on success, or a
+    /// `StreamError` on failure. Errors
+    /// receiving, verifying or decoding a message are yielded by the
+    /// `futures::Stream`, which keeps going afterwards.
     ///
     /// # Examples
     ///
@@ -140,19 +402,19 @@ impl<S: Streamable> Stream<S> {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn subscribe(
-        &self,
-    ) -> Result<impl futures::Stream<Item = Option<Vec<u8>>>, StreamError> {
-        // TODO: Why implicitly select a stream for the user?
-        // TODO: Should this be a combination of streams
-        self.stream
-        // TODO: Improve DX by ensuring the stream returns the streamable entity directly
-            .subscribe(S::WILDCARD_LIST[0])
+    pub async fn subscribe(&self) -> Result<Subscription<S>, StreamError> {
+        let messages = self
+            .stream
+            .backend()
+            .subscribe(&self.filter_subjects, DeliverFrom::New)
             .await
-            .map_err(|source| StreamError::Subscribe { source })
+            .map_err(|source| StreamError::Subscribe { source })?;
+
+        Ok(self.decode_messages(messages))
     }
 
-    /// Subscribes to the stream with custom configuration options.
+    /// Subscribes to the stream with custom configuration options, e.g. to
+    /// read the messages published before subscribing.
     ///
     /// # Parameters
     ///
@@ -160,22 +422,40 @@ impl<S: Streamable> Stream<S> {
     ///
     /// # Returns
     ///
-    /// Returns a `Result` containing a `PullConsumerStream` on success,
-    /// or a `StreamError` on failure.
+    /// Returns a `Result` containing a `Subscription` on success, or a
+    /// `StreamError` on failure, like `subscribe`. Subscribing from a
+    /// `DeliverFrom::BlockHeight` fails for streams whose subjects don't
+    /// hold block heights, and when no message of that block is retained.
+    ///
+    /// With `TimeReference::Block`, the messages of the blocks produced
+    /// before `DeliverFrom::StartTime` are skipped, as blocks are published
+    /// after being produced, sometimes long after when catching up.
     ///
     /// # Examples
     ///
     /// ```no_run
+    /// use std::time::{Duration, SystemTime};
+    ///
     /// use fuel_streams::client::Client;
-    /// use fuel_streams::stream::{Stream, StreamConfig};
+    /// use fuel_streams::stream::{DeliverFrom, Stream, StreamConfig, TimeReference};
     /// use fuel_streams::blocks::Block;
-    /// use fuel_streams::types::DeliverPolicy;
     ///
     /// # async fn example() -> Result<(), fuel_streams::Error> {
     /// # let client = Client::connect("nats://localhost:4222").await?;
     /// # let stream = Stream::<Block>::new(&client).await;
+    /// // Replay the blocks from height 1000
+    /// let config = StreamConfig {
+    ///     deliver_from: DeliverFrom::BlockHeight(1000),
+    ///     ..Default::default()
+    /// };
+    /// let subscription = stream.subscribe_with_config(config).await?;
+    ///
+    /// // The blocks produced during the last hour, until now
+    /// let now = SystemTime::now();
     /// let config = StreamConfig {
-    ///     deliver_policy: DeliverPolicy::All,
+    ///     deliver_from: DeliverFrom::StartTime(now - Duration::from_secs(3600)),
+    ///     until: Some(now),
+    ///     time_reference: TimeReference::Block,
     /// };
     /// let subscription = stream.subscribe_with_config(config).await?;
     /// # Ok(())
@@ -184,14 +464,386 @@ impl<S: Streamable> Stream<S> {
     pub async fn subscribe_with_config(
         &self,
         opts: StreamConfig,
-    ) -> Result<PullConsumerStream, StreamError> {
-        self.stream
-            .subscribe_consumer(SubscribeConsumerConfig {
-                deliver_policy: opts.deliver_policy,
-                filter_subjects: self.filter_subjects.to_owned(),
+    ) -> Result<Subscription<S>, StreamError> {
+        let deliver_from = self
+            .stream
+            .resolve_deliver_from(opts.deliver_from)
+            .await
+            .map_err(|source| StreamError::SubscribeWithOpts { source })?;
+        let backend = self.stream.backend();
+        let messages = match opts.until {
+            Some(until) if until <= SystemTime::now() => {
+                backend
+                    .subscribe_stored(&self.filter_subjects, deliver_from)
+                    .await
+            }
+            _ => backend.subscribe(&self.filter_subjects, deliver_from).await,
+        }
+        .map_err(|source| StreamError::SubscribeWithOpts { source })?;
+
+        let StreamConfig {
+            deliver_from,
+            until,
+            time_reference,
+        } = opts;
+        let start_time = match deliver_from {
+            DeliverFrom::StartTime(start_time)
+                if time_reference == TimeReference::Block =>
+            {
+                Some(start_time)
+            }
+            _ => None,
+        };
+        Ok(self
+            .decode_messages(messages)
+            .filter(move |data| {
+                future::ready(match (data, start_time) {
+                    (Ok(data), Some(start_time)) => {
+                        time_reference.time_of(&data.metadata) >= start_time
+                    }
+                    _ => true,
+                })
+            })
+            .take_while(move |data| {
+                future::ready(match (data, until) {
+                    (Ok(data), Some(until)) => {
+                        time_reference.time_of(&data.metadata) <= until
+                    }
+                    _ => true,
+                })
             })
+            .boxed())
+    }
+
+    /// Subscribes to the stream through a durable consumer, which keeps
+    /// track of the messages it acknowledged. Subscribing again under the
+    /// same name, e.g. after a restart, resumes after the last of them.
+    ///
+    /// # Parameters
+    ///
+    /// * `config`: A `DurableConfig` with the name of the consumer, along with
+    ///   where it starts from and how messages are redelivered when it is
+    ///   created.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing a `DurableSubscription` on success, or a
+    /// `StreamError` on failure. Every `Delivery` must be acknowledged once
+    /// processed, or it is delivered again after the `ack_wait` of the
+    /// consumer. Messages that can't be verified or decoded are terminated,
+    /// and yielded as errors.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use fuel_streams::client::Client;
+    /// use fuel_streams::stream::{DurableConfig, Stream};
+    /// use fuel_streams::blocks::Block;
+    /// use futures::StreamExt;
+    ///
+    /// # async fn example() -> Result<(), fuel_streams::Error> {
+    /// # let client = Client::connect("nats://localhost:4222").await?;
+    /// # let stream = Stream::<Block>::new(&client).await;
+    /// let config = DurableConfig::new("indexer").with_max_deliver(Some(5));
+    /// let mut subscription = stream.subscribe_durable(config).await?;
+    /// while let Some(delivery) = subscription.next().await {
+    ///     let delivery = delivery?;
+    ///     println!("Received block: {:?}", delivery.data.payload);
+    ///     delivery.ack().await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn subscribe_durable(
+        &self,
+        config: DurableConfig,
+    ) -> Result<DurableSubscription<S>, StreamError> {
+        let deliver_from = self
+            .stream
+            .resolve_deliver_from(config.deliver_from)
+            .await
+            .map_err(|source| StreamError::Subscribe { source })?;
+        let config = config.with_deliver_from(deliver_from);
+        let messages = self
+            .stream
+            .backend()
+            .subscribe_durable(&self.filter_subjects, &config)
+            .await
+            .map_err(|source| StreamError::Subscribe { source })?;
+
+        let stream = self.clone();
+        Ok(messages
+            .then(move |message| {
+                let stream = stream.clone();
+                async move { stream.decode_delivery(message).await }
+            })
+            .boxed())
+    }
+
+    /// Subscribes to the messages of the stream matching any of its
+    /// filters, starting from `deliver_from`, without decoding them.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing a `RawSubscription` on success, or a
+    /// `StreamError` on failure, like `subscribe_with_config`. Messages are
+    /// only verified and decoded by `RawMessage::decode`, so that the ones
+    /// that aren't needed can be skipped on their headers beforehand.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use fuel_streams::client::Client;
+    /// use fuel_streams::stream::{DeliverFrom, Stream};
+    /// use fuel_streams::transactions::Transaction;
+    /// use futures::StreamExt;
+    ///
+    /// # async fn example() -> Result<(), fuel_streams::Error> {
+    /// # let client = Client::connect("nats://localhost:4222").await?;
+    /// # let stream = Stream::<Transaction>::new(&client).await;
+    /// let mut subscription = stream.subscribe_raw(DeliverFrom::All).await?;
+    /// while let Some(message) = subscription.next().await {
+    ///     let message = message?;
+    ///     // Only decode the first transaction of every block
+    ///     if message.headers.tx_index == Some(0) {
+    ///         let transaction = message.decode().await?;
+    ///         println!("Transaction: {:?}", transaction.payload);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn subscribe_raw(
+        &self,
+        deliver_from: DeliverFrom,
+    ) -> Result<RawSubscription<S>, StreamError> {
+        let deliver_from = self
+            .stream
+            .resolve_deliver_from(deliver_from)
+            .await
+            .map_err(|source| StreamError::SubscribeWithOpts { source })?;
+        let messages = self
+            .stream
+            .backend()
+            .subscribe(&self.filter_subjects, deliver_from)
             .await
-            .map_err(|source| StreamError::SubscribeWithOpts { source })
+            .map_err(|source| StreamError::SubscribeWithOpts { source })?;
+
+        let stream = self.clone();
+        Ok(messages
+            .map(move |message| {
+                message
+                    .map(|message| stream.raw_message(message))
+                    .map_err(|source| StreamError::Receive { source })
+            })
+            .boxed())
+    }
+
+    fn raw_message(&self, message: StreamMessage) -> RawMessage<S> {
+        RawMessage {
+            subject: message.subject,
+            headers: message.headers,
+            payload: message.payload,
+            verifying_key: self.verifying_key,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    fn decode_messages(&self, messages: MessageStream) -> Subscription<S> {
+        let stream = self.clone();
+        messages
+            .then(move |message| {
+                let stream = stream.clone();
+                async move { stream.decode(message).await }
+            })
+            .boxed()
+    }
+
+    async fn decode(
+        &self,
+        message: Result<StreamMessage, fuel_streams_core::StreamError>,
+    ) -> Result<StreamData<S>, StreamError> {
+        let message =
+            message.map_err(|source| StreamError::Receive { source })?;
+        self.raw_message(message).decode().await
+    }
+
+    /// Returns the last message published on the subjects matching
+    /// `subject`, e.g. the payload of a single key of the stream.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the decoded message, or `None` if no
+    /// message matches, on success, or a `StreamError` on failure, e.g.
+    /// `StreamError::InvalidFilter` if the subject isn't one the stream's
+    /// data is published on.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use fuel_streams::client::Client;
+    /// use fuel_streams::stream::{Filter, Stream};
+    /// use fuel_streams::blocks::{Block, BlocksSubject};
+    ///
+    /// # async fn example() -> Result<(), fuel_streams::Error> {
+    /// # let client = Client::connect("nats://localhost:4222").await?;
+    /// # let stream = Stream::<Block>::new(&client).await;
+    /// let subject = Filter::<BlocksSubject>::build().with_height(Some(5.into()));
+    /// if let Some(block) = stream.get(subject).await? {
+    ///     println!("Block 5: {:?}", block.payload);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get(
+        &self,
+        subject: impl IntoSubject,
+    ) -> Result<Option<StreamData<S>>, StreamError> {
+        let subject = Self::filter_subject(&subject)?;
+        self.get_raw(&subject).await
+    }
+
+    /// Lists the keys, i.e. subjects, of the messages matching `filter`, a
+    /// page at a time.
+    ///
+    /// # Parameters
+    ///
+    /// * `filter`: The subject filter the keys must match.
+    /// * `cursor`: Where the page starts, i.e. the `next_cursor` of the
+    ///   previous page, or `None` for the first page.
+    /// * `limit`: The number of keys of a page, at most.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing a `KeysPage` on success, or a
+    /// `StreamError` on failure, like `get`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use fuel_streams::client::Client;
+    /// use fuel_streams::stream::{Filter, Stream};
+    /// use fuel_streams::blocks::{Block, BlocksSubject};
+    ///
+    /// # async fn example() -> Result<(), fuel_streams::Error> {
+    /// # let client = Client::connect("nats://localhost:4222").await?;
+    /// # let stream = Stream::<Block>::new(&client).await;
+    /// let mut cursor = None;
+    /// loop {
+    ///     let filter = Filter::<BlocksSubject>::build();
+    ///     let page = stream.list_keys(filter, cursor, 100).await?;
+    ///     println!("Keys: {:?}", page.keys);
+    ///     match page.next_cursor {
+    ///         Some(next_cursor) => cursor = Some(next_cursor),
+    ///         None => break,
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_keys(
+        &self,
+        filter: impl IntoSubject,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> Result<KeysPage, StreamError> {
+        let subject = Self::filter_subject(&filter)?;
+        let messages = self
+            .stream
+            .backend()
+            .fetch(&[subject], cursor.unwrap_or(1), limit)
+            .await
+            .map_err(|source| StreamError::Query { source })?;
+
+        let next_cursor = match messages.last() {
+            Some((sequence, _)) if messages.len() == limit => {
+                Some(sequence + 1)
+            }
+            _ => None,
+        };
+        Ok(KeysPage {
+            keys: messages
+                .into_iter()
+                .map(|(_, message)| message.subject)
+                .collect(),
+            next_cursor,
+        })
+    }
+
+    /// Returns the last message published on the subjects matching the
+    /// `subject` wildcard, without checking that it belongs to the stream.
+    pub(crate) async fn get_raw(
+        &self,
+        subject: &str,
+    ) -> Result<Option<StreamData<S>>, StreamError> {
+        let message = self
+            .stream
+            .backend()
+            .last_published(subject)
+            .await
+            .map_err(|source| StreamError::Query { source })?;
+
+        match message {
+            Some(message) => Ok(Some(self.decode(Ok(message)).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns every message published on the subjects matching the
+    /// `subject` wildcard, without checking that it belongs to the stream.
+    pub(crate) async fn get_all_raw(
+        &self,
+        subject: &str,
+    ) -> Result<Vec<StreamData<S>>, StreamError> {
+        let filter_subjects = [subject.to_string()];
+        let mut from_sequence = 1;
+        let mut all = vec![];
+        loop {
+            let messages = self
+                .stream
+                .backend()
+                .fetch(&filter_subjects, from_sequence, QUERY_PAGE_SIZE)
+                .await
+                .map_err(|source| StreamError::Query { source })?;
+
+            let is_last_page = messages.len() < QUERY_PAGE_SIZE;
+            for (sequence, message) in messages {
+                from_sequence = sequence + 1;
+                all.push(self.decode(Ok(message)).await?);
+            }
+            if is_last_page {
+                return Ok(all);
+            }
+        }
+    }
+
+    async fn decode_delivery(
+        &self,
+        message: Result<AckableMessage, fuel_streams_core::StreamError>,
+    ) -> Result<Delivery<S>, StreamError> {
+        let AckableMessage {
+            message,
+            delivered,
+            acker,
+        } = message.map_err(|source| StreamError::Receive { source })?;
+
+        let message = self.raw_message(message);
+        match message.decode().await {
+            Ok(data) => Ok(Delivery {
+                data,
+                headers: message.headers,
+                delivered,
+                acker,
+            }),
+            Err(error) => {
+                // Delivering the message again would fail the same way
+                acker
+                    .term()
+                    .await
+                    .map_err(|source| StreamError::Ack { source })?;
+                Err(error)
+            }
+        }
     }
 
     /// Returns a reference to the underlying `fuel_streams_core::Stream`.
@@ -206,3 +858,35 @@ impl<S: Streamable> Stream<S> {
         &self.stream
     }
 }
+
+/// Whether every subject matching the NATS subject filter `filter` also
+/// matches `covering`, in which `*` matches a single token and `>` matches
+/// one or more trailing tokens.
+fn subject_covers(covering: &str, filter: &str) -> bool {
+    let mut covering_tokens = covering.split('.');
+    let mut filter_tokens = filter.split('.');
+    loop {
+        match (covering_tokens.next(), filter_tokens.next()) {
+            (Some(">"), Some(_)) => return true,
+            (Some("*"), Some(token)) if token != ">" => continue,
+            (Some(covering), Some(filter)) if covering == filter => continue,
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// Whether some subject matches both NATS subject filters.
+fn subjects_overlap(filter: &str, other: &str) -> bool {
+    let mut filter_tokens = filter.split('.');
+    let mut other_tokens = other.split('.');
+    loop {
+        match (filter_tokens.next(), other_tokens.next()) {
+            (Some(">"), Some(_)) | (Some(_), Some(">")) => return true,
+            (Some("*"), Some(_)) | (Some(_), Some("*")) => continue,
+            (Some(filter), Some(other)) if filter == other => continue,
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
@@ -30,7 +30,8 @@ async fn main() -> anyhow::Result<()> {
 
     // Configure the stream to start from the last published log
     let config = StreamConfig {
-        deliver_policy: DeliverPolicy::Last,
+        deliver_from: DeliverFrom::Last,
+        ..Default::default()
     };
 
     // Subscribe to the log stream with the specified configuration
@@ -39,11 +40,10 @@ async fn main() -> anyhow::Result<()> {
     println!("Listening for logs...");
 
     // Process incoming logs
-    while let Some(bytes) = sub.next().await {
-        let message = bytes?;
-        let decoded_msg = Log::decode_raw(message.payload.to_vec()).await;
+    while let Some(message) = sub.next().await {
+        let decoded_msg = message?;
         let log_subject = decoded_msg.subject;
-        let log_published_at = decoded_msg.timestamp;
+        let log_published_at = decoded_msg.metadata.timestamp_ms;
 
         println!(
             "Received log:\n  Subject: {}\n  Published at: {}\n  Log: {:?}\n",
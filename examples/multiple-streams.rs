@@ -16,9 +16,9 @@ use fuel_streams::{
     client::Client,
     subjects::*,
     types::*,
+    DeliverFrom,
     Filter,
     StreamConfig,
-    StreamEncoder,
 };
 use futures::{future::try_join_all, StreamExt};
 
@@ -125,14 +125,14 @@ async fn stream_blocks(
     let mut block_stream = fuel_streams::Stream::<Block>::new(client).await;
 
     let mut sub = match filter {
-        Some(filter) => block_stream.with_filter(filter).subscribe().await?,
+        Some(filter) => block_stream.with_filter(filter)?.subscribe().await?,
         None => block_stream.subscribe().await?,
     };
-    while let Some(bytes) = sub.next().await {
-        let decoded_msg = Block::decode_raw(bytes.unwrap()).await;
+    while let Some(message) = sub.next().await {
+        let decoded_msg = message?;
         let block_height = decoded_msg.payload.height;
         let block_subject = decoded_msg.subject;
-        let block_published_at = decoded_msg.timestamp;
+        let block_published_at = decoded_msg.metadata.timestamp_ms;
         println!(
             "Received block: height={}, subject={}, published_at={}",
             block_height, block_subject, block_published_at
@@ -150,26 +150,25 @@ async fn stream_transactions(
 
     // here we apply a config to the streaming to start getting only from the last published transaction onwards
     let config = StreamConfig {
-        deliver_policy: DeliverPolicy::Last,
+        deliver_from: DeliverFrom::Last,
+        ..Default::default()
     };
 
     let mut sub = match filter {
         Some(filter) => {
             txs_stream
-                .with_filter(filter)
+                .with_filter(filter)?
                 .subscribe_with_config(config)
                 .await?
         }
         None => txs_stream.subscribe_with_config(config).await?,
     };
 
-    while let Some(bytes) = sub.next().await {
-        let message = bytes?;
-        let decoded_msg =
-            Transaction::decode_raw(message.payload.to_vec()).await;
+    while let Some(message) = sub.next().await {
+        let decoded_msg = message?;
         let tx = decoded_msg.payload;
         let tx_subject = decoded_msg.subject;
-        let tx_published_at = decoded_msg.timestamp;
+        let tx_published_at = decoded_msg.metadata.timestamp_ms;
         println!(
             "Received transaction: data={:?}, subject={}, published_at={}",
             tx, tx_subject, tx_published_at
@@ -203,13 +202,13 @@ async fn stream_transactions_by_contract(
         .with_id_value(Some((*contract_id).into()));
 
     // Filtered stream
-    let mut sub = txs_stream.with_filter(filter).subscribe().await?;
+    let mut sub = txs_stream.with_filter(filter)?.subscribe().await?;
 
-    while let Some(bytes) = sub.next().await {
-        let decoded_msg = Transaction::decode_raw(bytes.unwrap()).await;
+    while let Some(message) = sub.next().await {
+        let decoded_msg = message?;
         let tx = decoded_msg.payload;
         let tx_subject = decoded_msg.subject;
-        let tx_published_at = decoded_msg.timestamp;
+        let tx_published_at = decoded_msg.metadata.timestamp_ms;
         println!(
             "Received transaction for contract: data={:?}, subject={}, published_at={}",
             tx, tx_subject, tx_published_at
@@ -244,49 +243,49 @@ async fn stream_contract(
 
     receipt_stream.with_filter(
         ReceiptsBurnSubject::new().with_contract_id(Some(contract_id.into())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsCallSubject::new().with_from(Some(contract_id.into())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsReturnSubject::new().with_id(Some(contract_id.into())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsReturnDataSubject::new().with_id(Some(contract_id.into())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsPanicSubject::new().with_id(Some(contract_id.into())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsRevertSubject::new().with_id(Some(contract_id.into())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsLogSubject::new().with_id(Some(contract_id.into())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsLogDataSubject::new().with_id(Some(contract_id.into())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsTransferSubject::new().with_from(Some(contract_id.into())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsTransferOutSubject::new().with_from(Some(contract_id.into())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsMintSubject::new().with_contract_id(Some(contract_id.into())),
-    );
+    )?;
 
     let mut sub = receipt_stream.subscribe().await?;
 
-    while let Some(bytes) = sub.next().await {
-        let decoded_msg = Receipt::decode_raw(bytes.unwrap().to_vec()).await;
+    while let Some(message) = sub.next().await {
+        let decoded_msg = message?;
         let receipt = decoded_msg.payload;
 
         // Check if the receipt has a contract_id and if it matches our target
         if let Some(receipt_contract_id) = &receipt.contract_id {
             if *receipt_contract_id == contract_id.into() {
                 let receipt_subject = decoded_msg.subject;
-                let receipt_published_at = decoded_msg.timestamp;
+                let receipt_published_at = decoded_msg.metadata.timestamp_ms;
                 println!(
                     "Received contract receipt: data={:?}, subject={}, published_at={}",
                     receipt, receipt_subject, receipt_published_at
@@ -321,15 +320,15 @@ async fn stream_inputs_by_contract(
         InputsByIdSubject::new()
             .with_id_kind(Some(IdentifierKind::ContractID))
             .with_id_value(Some((*contract_id).into())),
-    );
+    )?;
 
     let mut sub = inputs_stream.subscribe().await?;
 
-    while let Some(bytes) = sub.next().await {
-        let decoded_msg = Input::decode_raw(bytes.unwrap().to_vec()).await;
+    while let Some(message) = sub.next().await {
+        let decoded_msg = message?;
         let input = decoded_msg.payload;
         let input_subject = decoded_msg.subject;
-        let input_published_at = decoded_msg.timestamp;
+        let input_published_at = decoded_msg.metadata.timestamp_ms;
         println!(
             "Received input for contract: data={:?}, subject={}, published_at={}",
             input, input_subject, input_published_at
@@ -362,15 +361,15 @@ async fn stream_receipts_by_contract(
         ReceiptsByIdSubject::new()
             .with_id_kind(Some(IdentifierKind::ContractID))
             .with_id_value(Some((*contract_id).into())),
-    );
+    )?;
 
     let mut sub = receipt_stream.subscribe().await?;
 
-    while let Some(bytes) = sub.next().await {
-        let decoded_msg = Receipt::decode_raw(bytes.unwrap().to_vec()).await;
+    while let Some(message) = sub.next().await {
+        let decoded_msg = message?;
         let receipt = decoded_msg.payload;
         let receipt_subject = decoded_msg.subject;
-        let receipt_published_at = decoded_msg.timestamp;
+        let receipt_published_at = decoded_msg.metadata.timestamp_ms;
         println!(
             "Received receipt for contract: data={:?}, subject={}, published_at={}",
             receipt, receipt_subject, receipt_published_at
@@ -30,7 +30,8 @@ async fn main() -> anyhow::Result<()> {
 
     // Configure the stream to start from the last published output
     let config = StreamConfig {
-        deliver_policy: DeliverPolicy::Last,
+        deliver_from: DeliverFrom::Last,
+        ..Default::default()
     };
 
     // Subscribe to the output stream with the specified configuration
@@ -39,11 +40,10 @@ async fn main() -> anyhow::Result<()> {
     println!("Listening for outputs...");
 
     // Process incoming outputs
-    while let Some(bytes) = sub.next().await {
-        let message = bytes?;
-        let decoded_msg = Output::decode_raw(message.payload.to_vec()).await;
+    while let Some(message) = sub.next().await {
+        let decoded_msg = message?;
         let tx_subject = decoded_msg.subject;
-        let tx_published_at = decoded_msg.timestamp;
+        let tx_published_at = decoded_msg.metadata.timestamp_ms;
 
         println!(
             "Received output:\n  Subject: {}\n  Published at: {}\n  Output: {:?}\n",
@@ -61,43 +61,44 @@ async fn main() -> Result<()> {
     receipt_stream.with_filter(
         ReceiptsBurnSubject::default()
             .with_contract_id(Some(contract_id.clone())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsCallSubject::default().with_from(Some(contract_id.clone())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsReturnSubject::default().with_id(Some(contract_id.clone())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsReturnDataSubject::default().with_id(Some(contract_id.clone())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsPanicSubject::default().with_id(Some(contract_id.clone())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsRevertSubject::default().with_id(Some(contract_id.clone())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsLogSubject::default().with_id(Some(contract_id.clone())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsLogDataSubject::default().with_id(Some(contract_id.clone())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsTransferSubject::default().with_from(Some(contract_id.clone())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsTransferOutSubject::default()
             .with_from(Some(contract_id.clone())),
-    );
+    )?;
     receipt_stream.with_filter(
         ReceiptsMintSubject::default()
             .with_contract_id(Some(contract_id.clone())),
-    );
+    )?;
 
     // Configure the stream to start from the first published receipt
     let config = StreamConfig {
-        deliver_policy: DeliverPolicy::All,
+        deliver_from: DeliverFrom::All,
+        ..Default::default()
     };
 
     // Subscribe to the receipt stream
@@ -106,12 +107,11 @@ async fn main() -> Result<()> {
     println!("Listening for receipts...");
 
     // Process incoming receipts
-    while let Some(bytes) = sub.next().await {
-        let message = bytes.unwrap();
-        let decoded_msg = Receipt::decode_raw(message.payload.to_vec()).await;
+    while let Some(message) = sub.next().await {
+        let decoded_msg = message?;
         let receipt = decoded_msg.payload;
         let receipt_subject = decoded_msg.subject;
-        let receipt_published_at = decoded_msg.timestamp;
+        let receipt_published_at = decoded_msg.metadata.timestamp_ms;
         println!(
             "Received receipt:\n  Subject: {}\n  Published at: {}\n  Data: {:?}\n",
             receipt_subject, receipt_published_at, receipt
@@ -0,0 +1,79 @@
+// Copyright 2024 Fuel Labs <contact@fuel.sh>
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use fuel_streams::prelude::*;
+use futures::StreamExt;
+
+/// The URL of the Fuel streaming service.
+const FUEL_STREAMING_SERVICE_URL: &str = "nats://fuel-streaming.testnet:4222";
+
+/// The number of payloads captured from each stream.
+const SAMPLES_PER_STREAM: usize = 2000;
+
+/// The file the trained dictionary is written to.
+const DICTIONARY_PATH: &str = "fuel-payloads.dict";
+
+/// Captures up to `SAMPLES_PER_STREAM` payloads from the stream of the given
+/// type, serialized as the publisher serializes them before compression.
+macro_rules! capture_samples {
+    ($client:expr, $type:ty, $samples:expr) => {{
+        let stream = fuel_streams::Stream::<$type>::new($client).await;
+        let config = StreamConfig {
+            deliver_from: DeliverFrom::All,
+            ..Default::default()
+        };
+        let mut sub = stream
+            .subscribe_with_config(config)
+            .await?
+            .take(SAMPLES_PER_STREAM);
+
+        while let Some(message) = sub.next().await {
+            let decoded_msg = message?;
+            let serialized =
+                <$type>::data_parser().serialize(&decoded_msg).await?;
+            $samples.push(serialized);
+        }
+    }};
+}
+
+// This example demonstrates how to train a zstd dictionary on blocks,
+// transactions and receipts captured from a Fuel network. The resulting file
+// can be given to the publisher with `--compression zstd-dict
+// --compression-dictionary fuel-payloads.dict`, and must be loaded by
+// consumers with `ZstdDictionaryStore::global().insert(..)`.
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // Initialize a client connection to the Fuel streaming service
+    let client = Client::connect(FUEL_STREAMING_SERVICE_URL).await?;
+
+    println!("Capturing samples...");
+
+    let mut samples = Vec::new();
+    capture_samples!(&client, Block, samples);
+    capture_samples!(&client, Transaction, samples);
+    capture_samples!(&client, Receipt, samples);
+
+    println!("Training dictionary on {} samples...", samples.len());
+
+    let dictionary =
+        ZstdDictionary::train(&samples, DEFAULT_ZSTD_DICTIONARY_SIZE)?;
+    std::fs::write(DICTIONARY_PATH, dictionary.as_bytes())?;
+
+    println!(
+        "Dictionary {} written to {}",
+        dictionary.id(),
+        DICTIONARY_PATH
+    );
+
+    Ok(())
+}
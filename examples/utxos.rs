@@ -30,7 +30,8 @@ async fn main() -> anyhow::Result<()> {
 
     // Configure the stream to start from the last published UTXO
     let config = StreamConfig {
-        deliver_policy: DeliverPolicy::Last,
+        deliver_from: DeliverFrom::Last,
+        ..Default::default()
     };
 
     // Subscribe to the UTXO stream with the specified configuration
@@ -39,11 +40,10 @@ async fn main() -> anyhow::Result<()> {
     println!("Listening for UTXOs...");
 
     // Process incoming UTXOs
-    while let Some(bytes) = sub.next().await {
-        let message = bytes?;
-        let decoded_msg = Utxo::decode_raw(message.payload.to_vec()).await;
+    while let Some(message) = sub.next().await {
+        let decoded_msg = message?;
         let utxo_subject = decoded_msg.subject;
-        let utxo_published_at = decoded_msg.timestamp;
+        let utxo_published_at = decoded_msg.metadata.timestamp_ms;
 
         println!(
             "Received UTXO:\n  Subject: {}\n  Published at: {}\n  UTXO: {:?}\n",
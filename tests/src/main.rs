@@ -108,11 +108,10 @@ async fn main() -> BoxedResult<()> {
 
     loop {
         tokio::select! {
-            bytes = sub.next() => {
-                let (index, bytes) = bytes.unzip();
-                if let Some(bytes) = bytes.flatten() {
+            message = sub.next() => {
+                let (index, message) = message.unzip();
+                if let Some(Ok(decoded_msg)) = message {
                     println!("Valid subscription");
-                    let decoded_msg = Block::decode_raw(bytes).await;
                     let (subject, block) = items[index.unwrap()].to_owned();
                     let height = decoded_msg.payload.height;
                     assert_eq!(decoded_msg.subject, subject.parse());
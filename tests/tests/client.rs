@@ -23,7 +23,7 @@ fn gen_random_string(size: usize) -> String {
 #[tokio::test]
 async fn conn_streams_has_required_streams() -> BoxedResult<()> {
     let (client, streams) = server_setup().await.unwrap();
-    let mut context_streams = client.jetstream.stream_names();
+    let mut context_streams = client.jetstream()?.stream_names();
 
     let mut names = HashSet::new();
     while let Some(name) = context_streams.try_next().await? {
@@ -77,7 +77,7 @@ async fn public_user_cannot_create_streams() -> BoxedResult<()> {
         (gen_random_string(6), gen_random_string(6));
 
     assert!(client
-        .jetstream
+        .jetstream()?
         .create_stream(types::NatsStreamConfig {
             name: random_stream_title,
             subjects: vec![random_subject],
@@ -99,7 +99,7 @@ async fn public_user_cannot_create_stores() -> BoxedResult<()> {
 
     let client = NatsClient::connect(&opts).await?;
     assert!(client
-        .jetstream
+        .jetstream()?
         .create_key_value(types::KvStoreConfig {
             bucket: random_bucket_title,
             ..Default::default()
@@ -110,6 +110,33 @@ async fn public_user_cannot_create_stores() -> BoxedResult<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn public_user_keeps_stream_retention() -> BoxedResult<()> {
+    let namespace = format!("namespace-{}", gen_random_string(6));
+    let retention = RetentionConfig::default()
+        .with_max_age(Some(Duration::from_secs(3600)))
+        .with_max_messages(Some(1000));
+    let opts = NatsClientOpts::admin_opts(NATS_URL)
+        .with_namespace(&namespace)
+        .with_stream_retention(Block::NAME, retention);
+    let client = NatsClient::connect(&opts).await?;
+    let stream = fuel_streams_core::Stream::<Block>::new(&client).await;
+    let mut info = stream.jetstream().unwrap().store().stream.clone();
+    let config = info.info().await?.config.clone();
+    assert_eq!(config.max_age, Duration::from_secs(3600));
+    assert_eq!(config.max_messages, 1000);
+
+    // opening the stream with the default options leaves its limits alone
+    let opts = NatsClientOpts::default_opts(NATS_URL)
+        .with_namespace(&namespace)
+        .with_timeout(1);
+    let public_client = NatsClient::connect(&opts).await?;
+    let _ = fuel_streams_core::Stream::<Block>::new(&public_client).await;
+    assert_eq!(info.info().await?.config, config);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn public_user_cannot_delete_stores() -> BoxedResult<()> {
     let opts = NatsClientOpts::admin_opts(NATS_URL)
@@ -120,7 +147,7 @@ async fn public_user_cannot_delete_stores() -> BoxedResult<()> {
 
     let client = NatsClient::connect(&opts).await?;
     client
-        .jetstream
+        .jetstream()?
         .create_key_value(types::KvStoreConfig {
             bucket: random_bucket_title.clone(),
             ..Default::default()
@@ -133,7 +160,7 @@ async fn public_user_cannot_delete_stores() -> BoxedResult<()> {
     let client = NatsClient::connect(&opts).await?;
 
     assert!(client
-        .jetstream
+        .jetstream()?
         .delete_key_value(&random_bucket_title)
         .await
         .is_err());
@@ -152,7 +179,7 @@ async fn public_user_cannot_delete_stream() -> BoxedResult<()> {
         (gen_random_string(6), gen_random_string(6));
 
     client
-        .jetstream
+        .jetstream()?
         .create_stream(types::NatsStreamConfig {
             name: random_stream_title.clone(),
             subjects: vec![random_subject],
@@ -165,7 +192,7 @@ async fn public_user_cannot_delete_stream() -> BoxedResult<()> {
 
     assert!(
         public_client
-            .jetstream
+            .jetstream()?
             .delete_stream(&random_stream_title)
             .await
             .is_err(),
@@ -238,7 +265,7 @@ async fn admin_user_can_delete_stream() -> BoxedResult<()> {
         (gen_random_string(6), gen_random_string(6));
 
     client
-        .jetstream
+        .jetstream()?
         .create_stream(types::NatsStreamConfig {
             name: random_stream_title.clone(),
             subjects: vec![random_subject],
@@ -246,7 +273,10 @@ async fn admin_user_can_delete_stream() -> BoxedResult<()> {
         })
         .await?;
 
-    let status = client.jetstream.delete_stream(&random_stream_title).await?;
+    let status = client
+        .jetstream()?
+        .delete_stream(&random_stream_title)
+        .await?;
     assert!(status.success, "Stream must be deleted at this point");
 
     Ok(())
@@ -262,7 +292,7 @@ async fn admin_user_can_delete_stores() -> BoxedResult<()> {
 
     let client = NatsClient::connect(&opts).await?;
     client
-        .jetstream
+        .jetstream()?
         .create_key_value(types::KvStoreConfig {
             bucket: random_bucket_title.clone(),
             ..Default::default()
@@ -270,7 +300,7 @@ async fn admin_user_can_delete_stores() -> BoxedResult<()> {
         .await?;
 
     assert!(client
-        .jetstream
+        .jetstream()?
         .delete_key_value(&random_bucket_title)
         .await
         .is_ok());
@@ -297,8 +327,8 @@ async fn ensure_deduplication_when_publishing() -> BoxedResult<()> {
     'l: loop {
         match timeout(timeout_duration, sub.next()).await {
             Ok(Some((idx, entry))) => {
-                assert!(entry.is_some());
-                let decoded_msg = Block::decode_raw(entry.unwrap()).await;
+                assert!(entry.is_ok());
+                let decoded_msg = entry.unwrap();
                 let (subject, _block) = items[idx].to_owned();
                 let height = decoded_msg.payload.height;
                 assert_eq!(decoded_msg.subject, subject.parse());
@@ -313,3 +343,110 @@ async fn ensure_deduplication_when_publishing() -> BoxedResult<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn republishing_after_duplicate_window_when_publishing() -> BoxedResult<()>
+{
+    let retention = RetentionConfig::default()
+        .with_duplicate_window(Duration::from_secs(1));
+    let opts = NatsClientOpts::admin_opts(NATS_URL)
+        .with_rdn_namespace()
+        .with_retention(retention);
+    let client = Client::with_opts(&opts).await?;
+    let stream = fuel_streams::Stream::<Block>::new(&client).await;
+    let mut sub = stream.subscribe().await?;
+
+    let block = MockBlock::build(1001);
+    let subject = BlocksSubject::from(&block);
+    let metadata = StreamMetadata::now().with_block(&BlockContext {
+        chain_id: 0,
+        height: block.height,
+        timestamp_ms: 0,
+    });
+
+    // publishing the block twice within the duplicate window, then once
+    // more after it
+    for wait in [Duration::ZERO, Duration::ZERO, Duration::from_secs(2)] {
+        tokio::time::sleep(wait).await;
+        stream
+            .stream()
+            .publish_with_metadata(&subject, &block, metadata.clone())
+            .await?;
+    }
+
+    let mut received = 0;
+    while let Ok(Some(entry)) =
+        timeout(Duration::from_secs(1), sub.next()).await
+    {
+        assert_eq!(entry?.payload, block);
+        received += 1;
+    }
+    assert_eq!(received, 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn verifying_stream_rejects_unsigned_messages() -> BoxedResult<()> {
+    let (conn, _) = server_setup().await.unwrap();
+    let client = Client::with_opts(&conn.opts).await.unwrap();
+    let mut stream = fuel_streams::Stream::<Block>::new(&client).await;
+    let producer = Some(Address::zeroed());
+    publish_blocks(stream.stream(), producer, None).unwrap();
+
+    let verifying_key = SigningKey::from_bytes(&[1; 32]).verifying_key();
+    stream.with_verifying_key(verifying_key);
+    let mut sub = stream.subscribe().await.unwrap();
+
+    let entry = timeout(Duration::from_secs(1), sub.next()).await?;
+    assert!(matches!(
+        entry,
+        Some(Err(fuel_streams::StreamError::InvalidSignature {
+            source: SignatureError::Unsigned
+        }))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn verifying_stream_rejects_payloads_replayed_on_other_subjects(
+) -> BoxedResult<()> {
+    let opts = NatsClientOpts::in_memory().with_rdn_namespace();
+    let client = Client::with_opts(&opts).await?;
+    let mut stream = fuel_streams::Stream::<Block>::new(&client).await;
+    let signing_key = SigningKey::from_bytes(&[1; 32]);
+    stream.with_verifying_key(signing_key.verifying_key());
+
+    // A validly signed block, published on its own subject and replayed on
+    // the subject of another height
+    let block = MockBlock::build(1);
+    let subject = BlocksSubject::from(&block).parse();
+    let replayed_subject =
+        BlocksSubject::new().with_height(Some(2.into())).parse();
+    let signed = sign_payload(&signing_key, &block.encode(&subject).await?);
+    for subject in [&subject, &replayed_subject] {
+        let message = StreamMessage {
+            subject: subject.clone(),
+            payload: signed.clone().into(),
+            headers: StreamHeaders::default(),
+        };
+        stream
+            .stream()
+            .backend()
+            .publish(message, &PublishMode::Upsert)
+            .await?;
+    }
+
+    let data = stream.get_block(1).await?.unwrap();
+    assert_eq!(data.payload, block);
+    assert!(matches!(
+        stream.get_block(2).await,
+        Err(fuel_streams::StreamError::SubjectMismatch {
+            subject: received_on,
+            payload_subject,
+        }) if received_on == replayed_subject && payload_subject == subject
+    ));
+
+    Ok(())
+}
@@ -103,6 +103,32 @@ async fn publishes_a_block_message_when_a_single_block_has_been_mined() {
         .is_ok_and(|result| result.is_some()));
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn publishes_to_in_memory_clients() {
+    let opts = NatsClientOpts::in_memory().with_rdn_namespace();
+    let client = fuel_streams::client::Client::with_opts(&opts)
+        .await
+        .unwrap();
+    let stream = fuel_streams::Stream::<Block>::new(&client).await;
+    let mut subscription = stream.subscribe().await.unwrap();
+
+    let (blocks_broadcaster, _) = broadcast::channel::<ImporterResult>(1);
+    let fuel_core = TestFuelCore::default(blocks_broadcaster.clone()).arc();
+    let nats_client = NatsClient::connect(&opts).await.unwrap();
+    let publisher = Publisher::default(&nats_client, fuel_core).await.unwrap();
+
+    publish_block(&publisher, &blocks_broadcaster).await;
+
+    let block = subscription.next().await.unwrap().unwrap().payload;
+    let last_published = publisher
+        .get_streams()
+        .blocks
+        .get_last_published(BlocksSubject::WILDCARD)
+        .await
+        .unwrap();
+    assert_eq!(last_published, Some(block));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn publishes_transaction_for_each_published_block() {
     let (blocks_broadcaster, _) = broadcast::channel::<ImporterResult>(1);
@@ -221,7 +247,7 @@ async fn publishes_receipts() {
         publisher.get_streams().receipts.catchup(10).await.unwrap();
 
     let receipts: HashSet<Receipt> = receipts.iter().map(Into::into).collect();
-    while let Some(Some(receipt)) = receipts_stream.next().await {
+    while let Some(Ok(receipt)) = receipts_stream.next().await {
         assert!(receipts.contains(&receipt));
     }
 }
@@ -1,3 +1,5 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use fuel_streams::prelude::*;
 use fuel_streams_core::prelude::*;
 use futures::{future::try_join_all, StreamExt};
@@ -13,8 +15,8 @@ async fn blocks_streams_subscribe() {
     let items = publish_blocks(stream.stream(), producer, None).unwrap().0;
 
     let mut sub = stream.subscribe().await.unwrap().enumerate();
-    while let Some((i, bytes)) = sub.next().await {
-        let decoded_msg = Block::decode_raw(bytes.unwrap()).await;
+    while let Some((i, message)) = sub.next().await {
+        let decoded_msg = message.unwrap();
         let (subject, block) = items[i].to_owned();
         let height = decoded_msg.payload.height;
 
@@ -45,6 +47,7 @@ async fn blocks_streams_subscribe_with_filter() {
     // creating subscription
     let mut sub = stream
         .with_filter(filter)
+        .unwrap()
         .subscribe_with_config(StreamConfig::default())
         .await
         .unwrap()
@@ -52,9 +55,7 @@ async fn blocks_streams_subscribe_with_filter() {
 
     // result should be just 1 single message with height 5
     while let Some(message) = sub.next().await {
-        let message = message.unwrap();
-        let decoded_msg =
-            Block::decode_raw(message.payload.clone().into()).await;
+        let decoded_msg = message.unwrap();
         let height = decoded_msg.payload.height;
         assert_eq!(height, 5);
         if height == 5 {
@@ -63,6 +64,390 @@ async fn blocks_streams_subscribe_with_filter() {
     }
 }
 
+#[tokio::test]
+async fn blocks_streams_subscribe_with_config_in_memory() {
+    let opts = NatsClientOpts::in_memory().with_rdn_namespace();
+    let client = Client::with_opts(&opts).await.unwrap();
+    let mut stream = fuel_streams::Stream::<Block>::new(&client).await;
+    let producer = Some(Address::zeroed());
+
+    // publishing 10 blocks before subscribing
+    let (items, join_handle) =
+        publish_blocks(stream.stream(), producer, None).unwrap();
+    join_handle.await.unwrap();
+
+    // the history is delivered as decoded messages
+    let mut sub = stream
+        .with_filter(Filter::<BlocksSubject>::build())
+        .unwrap()
+        .subscribe_with_config(StreamConfig {
+            deliver_from: DeliverFrom::All,
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .take(10)
+        .enumerate();
+
+    while let Some((i, message)) = sub.next().await {
+        let decoded_msg = message.unwrap();
+        let (subject, block) = items[i].to_owned();
+        assert_eq!(decoded_msg.subject, subject.parse());
+        assert_eq!(decoded_msg.payload, block);
+    }
+}
+
+#[tokio::test]
+async fn blocks_streams_subscribe_from_block_height() {
+    let opts = NatsClientOpts::in_memory().with_rdn_namespace();
+    let client = Client::with_opts(&opts).await.unwrap();
+    let stream = fuel_streams::Stream::<Block>::new(&client).await;
+    let producer = Some(Address::zeroed());
+
+    // publishing 10 blocks before subscribing
+    let (items, join_handle) =
+        publish_blocks(stream.stream(), producer, None).unwrap();
+    join_handle.await.unwrap();
+
+    // replaying from height 4
+    let mut sub = stream
+        .subscribe_with_config(StreamConfig {
+            deliver_from: DeliverFrom::BlockHeight(4),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .take(6)
+        .enumerate();
+
+    while let Some((i, message)) = sub.next().await {
+        let (_, block) = items[i + 4].to_owned();
+        assert_eq!(message.unwrap().payload, block);
+    }
+
+    // heights that aren't retained can't be resolved
+    let result = stream
+        .subscribe_with_config(StreamConfig {
+            deliver_from: DeliverFrom::BlockHeight(100),
+            ..Default::default()
+        })
+        .await;
+    assert!(matches!(
+        result,
+        Err(fuel_streams::StreamError::SubscribeWithOpts {
+            source: fuel_streams_core::StreamError::BlockHeightNotFound {
+                height: 100,
+                ..
+            }
+        })
+    ));
+}
+
+#[tokio::test]
+async fn blocks_streams_subscribe_within_block_time_window() {
+    let opts = NatsClientOpts::in_memory().with_rdn_namespace();
+    let client = Client::with_opts(&opts).await.unwrap();
+    let stream = fuel_streams::Stream::<Block>::new(&client).await;
+
+    // publishing 10 blocks, produced a second apart
+    for height in 0..10 {
+        let block = MockBlock::build(height);
+        let subject = BlocksSubject::build(None, Some(height.into()));
+        let metadata = StreamMetadata::now().with_block(&BlockContext {
+            chain_id: 0,
+            height,
+            timestamp_ms: u64::from(height) * 1000,
+        });
+        stream
+            .stream()
+            .publish_with_metadata(&subject, &block, metadata)
+            .await
+            .unwrap();
+    }
+
+    // the blocks produced between the 3rd and the 6th second
+    let heights: Vec<u32> = stream
+        .subscribe_with_config(StreamConfig {
+            deliver_from: DeliverFrom::StartTime(
+                UNIX_EPOCH + Duration::from_secs(3),
+            ),
+            until: Some(UNIX_EPOCH + Duration::from_secs(6)),
+            time_reference: TimeReference::Block,
+        })
+        .await
+        .unwrap()
+        .map(|message| message.unwrap().payload.height)
+        .collect()
+        .await;
+    assert_eq!(heights, vec![3, 4, 5, 6]);
+}
+
+#[tokio::test]
+async fn blocks_streams_time_window_in_the_past_completes() {
+    let opts = NatsClientOpts::in_memory().with_rdn_namespace();
+    let client = Client::with_opts(&opts).await.unwrap();
+    let stream = fuel_streams::Stream::<Block>::new(&client).await;
+    let producer = Some(Address::zeroed());
+
+    let (items, join_handle) =
+        publish_blocks(stream.stream(), producer, None).unwrap();
+    join_handle.await.unwrap();
+
+    // no block is published after the end of the window, which ends with
+    // the last block stored when subscribing
+    let until = SystemTime::now();
+    let subscription = stream
+        .subscribe_with_config(StreamConfig {
+            deliver_from: DeliverFrom::StartTime(
+                until - Duration::from_secs(3600),
+            ),
+            until: Some(until),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let blocks: Vec<Block> = tokio::time::timeout(
+        Duration::from_secs(5),
+        subscription
+            .map(|message| message.unwrap().payload)
+            .collect(),
+    )
+    .await
+    .expect("a window in the past must complete");
+    let expected: Vec<Block> =
+        items.into_iter().map(|(_, block)| block).collect();
+    assert_eq!(blocks, expected);
+
+    // nor when no block was published within it
+    let subscription = stream
+        .subscribe_with_config(StreamConfig {
+            deliver_from: DeliverFrom::StartTime(until),
+            until: Some(until),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let blocks: Vec<_> =
+        tokio::time::timeout(Duration::from_secs(5), subscription.collect())
+            .await
+            .expect("an empty window in the past must complete");
+    assert!(blocks.is_empty());
+}
+
+#[tokio::test]
+async fn blocks_streams_get_and_list_keys() {
+    let opts = NatsClientOpts::in_memory().with_rdn_namespace();
+    let client = Client::with_opts(&opts).await.unwrap();
+    let stream = fuel_streams::Stream::<Block>::new(&client).await;
+    let producer = Some(Address::zeroed());
+
+    // publishing 10 blocks
+    let (items, join_handle) =
+        publish_blocks(stream.stream(), producer, None).unwrap();
+    join_handle.await.unwrap();
+
+    let block = stream.get_block(3).await.unwrap().unwrap();
+    assert_eq!(block.payload, items[3].1);
+    assert!(stream.get_block(100).await.unwrap().is_none());
+
+    // listing the keys 4 at a time
+    let mut keys = vec![];
+    let mut pages = 0;
+    let mut cursor = None;
+    loop {
+        let filter = Filter::<BlocksSubject>::build();
+        let page = stream.list_keys(filter, cursor, 4).await.unwrap();
+        keys.extend(page.keys);
+        pages += 1;
+        match page.next_cursor {
+            Some(next_cursor) => cursor = Some(next_cursor),
+            None => break,
+        }
+    }
+    let subjects: Vec<String> =
+        items.iter().map(|(subject, _)| subject.parse()).collect();
+    assert_eq!(keys, subjects);
+    assert_eq!(pages, 3);
+}
+
+#[tokio::test]
+async fn transactions_streams_get_by_id() {
+    let opts = NatsClientOpts::in_memory().with_rdn_namespace();
+    let client = Client::with_opts(&opts).await.unwrap();
+    let stream = fuel_streams::Stream::<Transaction>::new(&client).await;
+    let mock_block = MockBlock::build(1);
+
+    // publishing 10 transactions
+    let (items, join_handle) =
+        publish_transactions(stream.stream(), &mock_block, None).unwrap();
+    join_handle.await.unwrap();
+
+    let (_, tx) = &items[3];
+    let transaction = stream.get_transaction(&tx.id).await.unwrap().unwrap();
+    assert_eq!(&transaction.payload, tx);
+}
+
+#[tokio::test]
+async fn transactions_streams_subscribe_raw_with_headers() {
+    let opts = NatsClientOpts::in_memory().with_rdn_namespace();
+    let client = Client::with_opts(&opts).await.unwrap();
+    let stream = fuel_streams::Stream::<Transaction>::new(&client).await;
+    let metadata = StreamMetadata::now().with_block(&BlockContext {
+        chain_id: 0,
+        height: 1,
+        timestamp_ms: 0,
+    });
+
+    // publishing 4 transactions of the block at height 1
+    let mut txs = vec![];
+    for tx_index in 0..4 {
+        let tx = MockTransaction::build();
+        let subject = TransactionsSubject::from(&tx)
+            .with_block_height(Some(1.into()))
+            .with_index(Some(tx_index as usize));
+        let headers = StreamHeaders::default().with_tx_index(Some(tx_index));
+        stream
+            .stream()
+            .publish_with_headers(&subject, &tx, metadata.clone(), headers)
+            .await
+            .unwrap();
+        txs.push(tx);
+    }
+
+    // only decoding the transaction at index 2
+    let mut sub = stream.subscribe_raw(DeliverFrom::All).await.unwrap();
+    let mut decoded = vec![];
+    for _ in 0..4 {
+        let message = sub.next().await.unwrap().unwrap();
+        assert_eq!(message.headers.block_height, Some(1));
+        assert_eq!(
+            message.headers.schema_version,
+            Some(Transaction::SCHEMA_VERSION)
+        );
+        if message.headers.tx_index == Some(2) {
+            decoded.push(message.decode().await.unwrap().payload);
+        }
+    }
+    assert_eq!(decoded, vec![txs[2].clone()]);
+}
+
+#[tokio::test]
+async fn blocks_streams_subscribe_with_multiple_filters() {
+    let opts = NatsClientOpts::in_memory().with_rdn_namespace();
+    let client = Client::with_opts(&opts).await.unwrap();
+    let mut stream = fuel_streams::Stream::<Block>::new(&client).await;
+
+    // filtering by heights 3 and 7
+    stream
+        .with_filter(
+            Filter::<BlocksSubject>::build().with_height(Some(3.into())),
+        )
+        .unwrap();
+    stream
+        .with_filter(
+            Filter::<BlocksSubject>::build().with_height(Some(7.into())),
+        )
+        .unwrap();
+    let mut sub = stream.subscribe().await.unwrap();
+
+    // publishing 10 blocks after subscribing
+    let producer = Some(Address::zeroed());
+    publish_blocks(stream.stream(), producer, None).unwrap();
+
+    let mut heights = vec![];
+    while let Some(message) = sub.next().await {
+        heights.push(message.unwrap().payload.height);
+        if heights.len() == 2 {
+            break;
+        }
+    }
+    assert_eq!(heights, vec![3, 7]);
+}
+
+#[tokio::test]
+async fn blocks_streams_subscribe_with_overlapping_filters() {
+    let (conn, _) = server_setup().await.unwrap();
+    let client = Client::with_opts(&conn.opts).await.unwrap();
+    let mut stream = fuel_streams::Stream::<Block>::new(&client).await;
+    let producer = Some(Address::zeroed());
+
+    // filtering by height 5 overlaps filtering by producer 0x000 partially
+    let by_height =
+        Filter::<BlocksSubject>::build().with_height(Some(5.into()));
+    let by_producer =
+        Filter::<BlocksSubject>::build().with_producer(producer.clone());
+    stream.with_filter(by_height.clone()).unwrap();
+    assert!(matches!(
+        stream.with_filter(by_producer),
+        Err(fuel_streams::StreamError::OverlappingFilter { .. })
+    ));
+
+    // filtering by every block replaces the filter by height 5, and is
+    // kept when filtering by height 5 again
+    stream
+        .with_filter(Filter::<BlocksSubject>::build())
+        .unwrap();
+    stream.with_filter(by_height).unwrap();
+    let mut sub = stream.subscribe().await.unwrap();
+
+    // publishing 10 blocks after subscribing
+    publish_blocks(stream.stream(), producer, None).unwrap();
+
+    let mut heights = vec![];
+    while let Some(message) = sub.next().await {
+        heights.push(message.unwrap().payload.height);
+        if heights.len() == 10 {
+            break;
+        }
+    }
+    assert_eq!(heights, (0..10).collect::<Vec<u32>>());
+}
+
+#[tokio::test]
+async fn streams_reject_filters_of_other_streams() {
+    let opts = NatsClientOpts::in_memory().with_rdn_namespace();
+    let client = Client::with_opts(&opts).await.unwrap();
+    let mut stream = fuel_streams::Stream::<Block>::new(&client).await;
+
+    let filter = Filter::<TransactionsSubject>::build();
+    assert!(matches!(
+        stream.with_filter(filter),
+        Err(fuel_streams::StreamError::InvalidFilter {
+            stream: "blocks",
+            ..
+        })
+    ));
+}
+
+#[tokio::test]
+async fn blocks_streams_durable_consumers_resume() {
+    let opts = NatsClientOpts::in_memory().with_rdn_namespace();
+    let client = Client::with_opts(&opts).await.unwrap();
+    let stream = fuel_streams::Stream::<Block>::new(&client).await;
+    let producer = Some(Address::zeroed());
+
+    // publishing 10 blocks
+    let (items, join_handle) =
+        publish_blocks(stream.stream(), producer, None).unwrap();
+    join_handle.await.unwrap();
+
+    // acknowledging the first 5 blocks
+    let config = DurableConfig::new("blocks-consumer");
+    let mut sub = stream.subscribe_durable(config.clone()).await.unwrap();
+    for (_, block) in items.iter().take(5) {
+        let delivery = sub.next().await.unwrap().unwrap();
+        assert_eq!(&delivery.data.payload, block);
+        delivery.ack().await.unwrap();
+    }
+    drop(sub);
+
+    // resuming under the same name starts from the 6th block
+    let mut sub = stream.subscribe_durable(config).await.unwrap();
+    let delivery = sub.next().await.unwrap().unwrap();
+    assert_eq!(delivery.data.payload, items[5].1);
+    assert_eq!(delivery.delivered, 1);
+}
+
 #[tokio::test]
 async fn transactions_streams_subscribe() {
     let (conn, _) = server_setup().await.unwrap();
@@ -75,9 +460,8 @@ async fn transactions_streams_subscribe() {
         .0;
 
     let mut sub = stream.subscribe().await.unwrap().enumerate();
-    while let Some((i, bytes)) = sub.next().await {
-        let decoded_msg =
-            Transaction::decode_raw(bytes.unwrap().to_vec()).await;
+    while let Some((i, message)) = sub.next().await {
+        let decoded_msg = message.unwrap();
 
         let (_, transaction) = items[i].to_owned();
         assert_eq!(decoded_msg.payload, transaction);
@@ -106,6 +490,7 @@ async fn transactions_streams_subscribe_with_filter() {
     // creating subscription
     let mut sub = stream
         .with_filter(filter)
+        .unwrap()
         .subscribe_with_config(StreamConfig::default())
         .await
         .unwrap()
@@ -114,12 +499,10 @@ async fn transactions_streams_subscribe_with_filter() {
 
     // result should be 10 transactions messages
     while let Some((i, message)) = sub.next().await {
-        let message = message.unwrap();
-        let payload = message.payload.clone().into();
-        let decoded_msg = Transaction::decode(payload).await;
+        let decoded_msg = message.unwrap();
 
         let (_, transaction) = items[i].to_owned();
-        assert_eq!(decoded_msg, transaction);
+        assert_eq!(decoded_msg.payload, transaction);
         if i == 9 {
             break;
         }
@@ -144,8 +527,8 @@ async fn multiple_subscribers_same_subject() {
         let items = items.clone();
         handles.push(tokio::spawn(async move {
             let mut sub = stream.subscribe().await.unwrap().enumerate();
-            while let Some((i, bytes)) = sub.next().await {
-                let decoded_msg = Block::decode_raw(bytes.unwrap()).await;
+            while let Some((i, message)) = sub.next().await {
+                let decoded_msg = message.unwrap();
                 let (subject, block) = items[i].to_owned();
                 let height = decoded_msg.payload.height;
 
@@ -203,8 +586,8 @@ async fn multiple_subscribers_different_subjects() {
         let items = block_items.clone();
         handles.push(tokio::spawn(async move {
             let mut sub = stream.subscribe().await.unwrap().enumerate();
-            while let Some((i, bytes)) = sub.next().await {
-                let decoded_msg = Block::decode_raw(bytes.unwrap()).await;
+            while let Some((i, message)) = sub.next().await {
+                let decoded_msg = message.unwrap();
                 let (subject, block) = items[i].to_owned();
                 let height = decoded_msg.payload.height;
 
@@ -223,9 +606,8 @@ async fn multiple_subscribers_different_subjects() {
         let items = txs_items.clone();
         handles.push(tokio::spawn(async move {
             let mut sub = stream.subscribe().await.unwrap().enumerate();
-            while let Some((i, bytes)) = sub.next().await {
-                let decoded_msg =
-                    Transaction::decode_raw(bytes.unwrap().to_vec()).await;
+            while let Some((i, message)) = sub.next().await {
+                let decoded_msg = message.unwrap();
                 let (_, transaction) = items[i].to_owned();
                 assert_eq!(decoded_msg.payload, transaction);
                 if i == 9 {